@@ -0,0 +1,33 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: Material;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var sprite_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var sprite_sampler: sampler;
+
+struct Material {
+    uv_rect: vec4<f32>,
+    shift: f32,
+    flip_x: u32,
+    flash: f32,
+    _pad: f32,
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    var uv = mesh.uv;
+    if material.flip_x != 0u {
+        uv.x = 1.0 - uv.x;
+    }
+
+    let color = textureSample(sprite_texture, sprite_sampler, mix(material.uv_rect.xy, material.uv_rect.zw, uv));
+
+    // Positive shifts (approaching) push energy towards blue, negative shifts (receding) towards red.
+    let s = clamp(log2(max(material.shift, 0.0001)), -1.0, 1.0);
+    let c = color.rgb;
+    let blue = vec3<f32>(c.r * 0.4, c.g * 0.7, min(1.0, c.b + 0.5 * (c.r + c.g)));
+    let red = vec3<f32>(min(1.0, c.r + 0.5 * (c.g + c.b)), c.g * 0.7, c.b * 0.4);
+    let shifted = select(mix(c, red, -s), mix(c, blue, s), s > 0.0);
+
+    // Hit flashes wash the sprite out to white, on top of any shift.
+    return vec4<f32>(mix(shifted, vec3<f32>(1.0), clamp(material.flash, 0.0, 1.0)), color.a);
+}
@@ -0,0 +1,29 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import bevy_sprite::mesh2d_view_bindings::globals
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: Material;
+
+struct Material {
+    strength: f32,
+    _pad: vec3<f32>,
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // `x` runs along the direction of travel, with the front at `1.0`.
+    let p = mesh.uv * 2.0 - 1.0;
+
+    // Fade out towards the edges of an ellipse, and more slowly towards the trailing end.
+    let falloff = 1.0 - clamp(length(vec2<f32>(p.x * select(1.0, 0.6, p.x < 0.0), p.y)), 0.0, 1.0);
+    if falloff <= 0.0 {
+        discard;
+    }
+
+    // Ripples that stream backwards, wobbling across the direction of travel.
+    let t = globals.time;
+    let wobble = sin(p.y * 9.0 + t * 5.0) * 0.15;
+    let ripples = 0.5 + 0.5 * sin((p.x + wobble) * 14.0 + t * 18.0);
+    let shimmer = ripples * ripples * falloff * falloff;
+
+    return vec4<f32>(vec3<f32>(0.85, 0.92, 1.0), shimmer * 0.35 * material.strength);
+}
@@ -0,0 +1,45 @@
+#import bevy_render::globals::Globals
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+@group(0) @binding(1) var<uniform> globals: Globals;
+@group(1) @binding(0) var<uniform> material: Material;
+
+struct Material {
+    strength: f32,
+    _pad: vec3<f32>,
+}
+
+/// How dark the vignette is at `r`, from the center of the screen at `0.0` to the corners at `1.0`.
+fn vignette(r: f32, inner: f32) -> f32 {
+    return smoothstep(inner, 1.0, r);
+}
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let strength = material.strength;
+    if strength <= 0.0 {
+        discard;
+    }
+
+    let r = length(in.uv * 2.0 - 1.0) / sqrt(2.0);
+
+    // Closes in as the strength rises, with a faint pulse near full strength.
+    let pulse = sin(globals.time * 6.0) * 0.03 * strength * strength;
+    let inner = mix(1.0, 0.35, strength) + pulse;
+
+    // The channels start at slightly different radii, fringing the edge of the vignette red on the
+    // inside and blue further out, like chromatic aberration.
+    let split = 0.08 * strength;
+    let red = vignette(r * (1.0 + split), inner);
+    let green = vignette(r, inner);
+    let blue = vignette(r * (1.0 - split), inner);
+    let red_fringe = clamp(red - green, 0.0, 1.0);
+    let blue_fringe = clamp(green - blue, 0.0, 1.0);
+
+    let fringe = red_fringe * vec3<f32>(1.0, 0.2, 0.3) + blue_fringe * vec3<f32>(0.3, 0.4, 1.0);
+    let darkness = blue * 0.85;
+    let alpha = clamp(darkness + red_fringe + blue_fringe, 0.0, 1.0) * strength;
+    let color = fringe / max(alpha / strength, 0.0001);
+
+    return vec4<f32>(color, alpha);
+}
@@ -0,0 +1,109 @@
+//! How long building a large tileset stalls other tasks sharing its thread.
+//!
+//! Each iteration builds a tileset while a stand-in for other work yields in a loop on the same
+//! thread, and the time reported is the longest it went without being polled. Copying every tile
+//! in one go with `add_tile`, as the level loader used to, is compared against `add_tiles`.
+//!
+//! ```sh
+//! cargo bench --bench tileset
+//! ```
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::futures_lite::future,
+};
+use bevy_jam_7::bench::TilesetImageBuilder;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+const TILE_SIZE: u32 = 16;
+/// The source image is this many tiles wide and tall.
+const SOURCE_TILES: u32 = 64;
+const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+fn source_image() -> Image {
+    let size = TILE_SIZE * SOURCE_TILES;
+    Image::new_fill(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 0, 255, 255],
+        FORMAT,
+        RenderAssetUsages::all(),
+    )
+}
+
+/// The offset of every tile in the source image.
+fn source_offsets() -> Vec<UVec2> {
+    (0..SOURCE_TILES)
+        .flat_map(|y| (0..SOURCE_TILES).map(move |x| UVec2::new(x, y) * TILE_SIZE))
+        .collect()
+}
+
+fn builder() -> TilesetImageBuilder {
+    TilesetImageBuilder::new(UVec2::splat(TILE_SIZE), FORMAT).unwrap()
+}
+
+/// Runs `build` to completion alongside a task that yields in a loop, and returns the longest
+/// time that task went without being polled.
+fn longest_stall(build: impl Future<Output = ()>) -> Duration {
+    let mut last = Instant::now();
+    let mut longest = Duration::ZERO;
+    future::block_on(future::or(build, async {
+        loop {
+            let now = Instant::now();
+            longest = longest.max(now - last);
+            last = now;
+            future::yield_now().await;
+        }
+    }));
+    // The time since the last poll counts too, since a build that never yields is never
+    // interrupted at all
+    longest.max(last.elapsed())
+}
+
+fn tileset_stall(c: &mut Criterion) {
+    let source = source_image();
+    let offsets = source_offsets();
+
+    let mut group = c.benchmark_group("tileset_stall");
+    group.bench_function("add_tile", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| {
+                    let mut tileset = builder();
+                    longest_stall(async {
+                        for &offset in &offsets {
+                            tileset.add_tile(&source, offset).unwrap();
+                        }
+                    })
+                })
+                .sum()
+        });
+    });
+    group.bench_function("add_tiles", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| {
+                    let mut tileset = builder();
+                    longest_stall(async {
+                        tileset
+                            .add_tiles(&source, offsets.iter().copied())
+                            .await
+                            .unwrap();
+                    })
+                })
+                .sum()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tileset_stall);
+criterion_main!(benches);
@@ -1,11 +1,45 @@
+//! Sprite sheet animations, and the players that step through them.
+//!
+//! Animations can be built in code, or loaded from `.anim.ron` and `.anim.json` files with the
+//! [`AnimationLoader`] so they can be edited and hot-reloaded without recompiling. See
+//! [`AnimationDef`] for the format.
+//!
+//! Sprites on other entities can show the same frames as an animation player, from an atlas of
+//! their own, by pointing an [`AnimationTarget`] at it. This suits overlays like hats or glows
+//! drawn on a sheet laid out like the one they're worn over.
+//!
+//! Frames can carry a hitbox and a hurtbox. Children of an animated entity with a [`FrameBox`]
+//! become sensor colliders that follow the boxes of the current frame, and are disabled on frames
+//! without one. What they do when touched is up to their collision layers and the entity they
+//! belong to, so the active frames of a melee attack can be authored with its animation.
+
 use std::{ops::Range, time::Duration};
 
-use bevy::prelude::*;
+use avian2d::prelude::{Collider, ColliderDisabled, Sensor};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppSystems, physics::ProperTime};
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_asset::<Animation>().add_systems(
+    app.init_asset::<Animation>()
+        .init_asset_loader::<AnimationLoader>();
+
+    app.add_systems(
         Update,
-        (update_animation_players, update_sprite_animations).chain(),
+        (
+            update_animation_players,
+            (
+                update_sprite_animations,
+                update_animation_targets,
+                sync_frame_boxes,
+            ),
+        )
+            .chain()
+            .after(AppSystems::TickTimers),
     );
 }
 
@@ -19,22 +53,33 @@ pub struct AnimationEvent {
 #[derive(Asset, Reflect, Debug)]
 pub struct Animation {
     pub frames: Vec<Frame>,
+    pub loop_mode: LoopMode,
+}
+
+/// What an animation does after its last frame.
+#[derive(Reflect, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoopMode {
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Holds the last frame.
+    Once,
 }
 
 impl Animation {
     pub fn from_frame_range_and_millis(range: Range<usize>, frame_millis: u64) -> Self {
         let duration = Duration::from_millis(frame_millis);
         Self {
-            frames: range
-                .map(|index| Frame {
-                    index,
-                    duration,
-                    markers: Vec::new(),
-                })
-                .collect(),
+            frames: range.map(|index| Frame::new(index, duration)).collect(),
+            loop_mode: LoopMode::Loop,
         }
     }
 
+    pub fn with_loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
     pub fn with_marker(mut self, marker: usize, frames: impl IntoIterator<Item = usize>) -> Self {
         for i in frames {
             self.frames[i].markers.push(marker);
@@ -42,6 +87,23 @@ impl Animation {
         self
     }
 
+    /// Gives the frames listed in `boxes` its hitbox and hurtbox, keeping any they already have
+    /// where `boxes` leaves one out. Frames past the end of the animation are ignored.
+    pub fn with_frame_boxes(mut self, boxes: &FrameBoxesDef) -> Self {
+        for &i in &boxes.frames {
+            if let Some(frame) = self.frames.get_mut(i) {
+                frame.hitbox = boxes.hitbox.or(frame.hitbox);
+                frame.hurtbox = boxes.hurtbox.or(frame.hurtbox);
+            }
+        }
+        self
+    }
+
+    /// How long one play-through of the animation takes.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
     fn frame_markers(&self, frame: usize) -> &[usize] {
         self.frames.get(frame).map_or(&[], |frame| &frame.markers)
     }
@@ -52,14 +114,81 @@ pub struct Frame {
     pub index: usize,
     pub duration: Duration,
     pub markers: Vec<usize>,
+    /// The area this frame deals damage in, relative to the animated entity and facing right.
+    pub hitbox: Option<Rect>,
+    /// The area this frame can be hurt in, relative to the animated entity and facing right.
+    pub hurtbox: Option<Rect>,
+}
+
+impl Frame {
+    /// A frame showing the atlas `index`, without markers or boxes.
+    pub fn new(index: usize, duration: Duration) -> Self {
+        Self {
+            index,
+            duration,
+            markers: Vec::new(),
+            hitbox: None,
+            hurtbox: None,
+        }
+    }
+}
+
+/// Shows the current frame of another entity's [`AnimationPlayer`] on this entity's sprite, with
+/// the same atlas index and the same flip.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship(relationship_target = AnimationTargets)]
+pub struct AnimationTarget(pub Entity);
+
+/// The sprites showing this entity's animation, besides its own.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[relationship_target(relationship = AnimationTarget)]
+pub struct AnimationTargets(Vec<Entity>);
+
+/// Which of the current frame's boxes a [`FrameBox`] follows.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FrameBoxKind {
+    Hitbox,
+    Hurtbox,
 }
 
+/// A sensor collider that follows the hitbox or hurtbox of its parent's current animation frame.
+///
+/// Boxes are mirrored when the parent's sprite is flipped, and disabled on frames without one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Sensor, ColliderDisabled, Transform)]
+pub struct FrameBox {
+    pub kind: FrameBoxKind,
+    /// The box the collider was last fitted to.
+    current: Option<Rect>,
+}
+
+impl FrameBox {
+    pub fn new(kind: FrameBoxKind) -> Self {
+        Self {
+            kind,
+            current: None,
+        }
+    }
+}
+
+/// Plays an [`Animation`] on the entity's sprite.
+///
+/// Changing [`animation`](Self::animation) restarts playback from the first frame, unless
+/// [`retain_state`](Self::retain_state) is set. Changing the speed or pausing doesn't, but still
+/// counts as a change to the player, so they should only be written when they differ.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 #[require(AnimationPlayerState)]
 pub struct AnimationPlayer {
     pub animation: Handle<Animation>,
     pub retain_state: bool,
+    /// How fast the animation plays, as a multiple of its frame durations.
+    pub speed: f32,
+    /// Whether the animation is held on its current frame.
+    pub paused: bool,
 }
 
 impl From<Handle<Animation>> for AnimationPlayer {
@@ -67,6 +196,8 @@ impl From<Handle<Animation>> for AnimationPlayer {
         Self {
             animation,
             retain_state: false,
+            speed: 1.0,
+            paused: false,
         }
     }
 }
@@ -74,6 +205,8 @@ impl From<Handle<Animation>> for AnimationPlayer {
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct AnimationPlayerState {
+    /// The animation being played, if it has started.
+    animation: Option<AssetId<Animation>>,
     frame_index: usize,
     atlas_index: usize,
     timer: Timer,
@@ -88,12 +221,22 @@ impl AnimationPlayerState {
     //     self.atlas_index
     // }
 
-    fn init(animation: &Animation) -> Self {
+    /// Restarts the animation from its first frame on the next update, such as after its frames
+    /// have changed.
+    pub fn restart(&mut self) {
+        self.animation = None;
+    }
+
+    fn init(id: AssetId<Animation>, animation: &Animation) -> Self {
         let Some(first_frame) = animation.frames.first() else {
-            return Self::default();
+            return Self {
+                animation: Some(id),
+                ..default()
+            };
         };
 
         Self {
+            animation: Some(id),
             frame_index: 0,
             atlas_index: first_frame.index,
             timer: Timer::new(first_frame.duration, TimerMode::Once),
@@ -104,39 +247,57 @@ impl AnimationPlayerState {
         self.timer.tick(delta).is_finished()
     }
 
-    fn go_to_next_frame(&mut self, animation: &Animation) {
+    /// Moves on to the next frame, returning whether there was one to move to.
+    fn go_to_next_frame(&mut self, animation: &Animation) -> bool {
         if animation.frames.is_empty() {
-            return;
+            return false;
         }
 
+        let last = animation.frames.len() - 1;
+        if animation.loop_mode == LoopMode::Once && self.frame_index >= last {
+            return false;
+        }
         let index = (self.frame_index + 1) % animation.frames.len();
         let frame = &animation.frames[index];
 
         self.frame_index = index;
         self.atlas_index = frame.index;
         self.timer = Timer::new(frame.duration, TimerMode::Once);
+        true
     }
 }
 
 fn update_animation_players(
     time: Res<Time>,
     animations: Res<Assets<Animation>>,
-    mut animation_players: Query<(Entity, Ref<AnimationPlayer>, &mut AnimationPlayerState)>,
+    mut animation_players: Query<(
+        Entity,
+        &AnimationPlayer,
+        &mut AnimationPlayerState,
+        Option<&ProperTime>,
+    )>,
     mut commands: Commands,
 ) {
-    for (entity, player, mut state) in &mut animation_players {
+    for (entity, player, mut state, proper_time) in &mut animation_players {
         let Some(animation) = animations.get(&player.animation) else {
             continue;
         };
 
-        if player.is_changed() && !player.retain_state {
-            *state = AnimationPlayerState::init(animation);
-            continue;
+        let animation_id = player.animation.id();
+        if state.animation != Some(animation_id) {
+            if state.animation.is_none() || !player.retain_state {
+                *state = AnimationPlayerState::init(animation_id, animation);
+                continue;
+            }
+            state.animation = Some(animation_id);
         }
 
-        if state.bypass_change_detection().tick(time.delta()) {
-            state.go_to_next_frame(animation);
-
+        if player.paused {
+            continue;
+        }
+        let delta = proper_time.map_or(time.delta(), ProperTime::delta);
+        let delta = delta.mul_f32(player.speed.max(0.0));
+        if state.bypass_change_detection().tick(delta) && state.go_to_next_frame(animation) {
             for &marker in animation.frame_markers(state.frame_index) {
                 commands.trigger(AnimationEvent { entity, marker });
             }
@@ -153,3 +314,194 @@ fn update_sprite_animations(
         }
     }
 }
+
+fn update_animation_targets(
+    sources: Query<
+        (&AnimationPlayerState, &AnimationTargets, Option<&Sprite>),
+        Or<(
+            Changed<AnimationPlayerState>,
+            Changed<AnimationTargets>,
+            Changed<Sprite>,
+        )>,
+    >,
+    mut targets: Query<&mut Sprite, (With<AnimationTarget>, Without<AnimationPlayerState>)>,
+) {
+    for (state, animation_targets, source_sprite) in &sources {
+        let flip_x = source_sprite.map(|sprite| sprite.flip_x);
+        let mut iter = targets.iter_many_mut(animation_targets.iter());
+        while let Some(mut sprite) = iter.fetch_next() {
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = state.atlas_index;
+            }
+            if let Some(flip_x) = flip_x {
+                sprite.flip_x = flip_x;
+            }
+        }
+    }
+}
+
+fn sync_frame_boxes(
+    animations: Res<Assets<Animation>>,
+    animation_players: Query<
+        (
+            &AnimationPlayer,
+            &AnimationPlayerState,
+            &Children,
+            Option<&Sprite>,
+        ),
+        Or<(Changed<AnimationPlayerState>, Changed<Sprite>)>,
+    >,
+    mut frame_boxes: Query<(Entity, &mut FrameBox, &mut Transform)>,
+    mut commands: Commands,
+) {
+    for (player, state, children, sprite) in &animation_players {
+        let frame = animations
+            .get(&player.animation)
+            .and_then(|animation| animation.frames.get(state.frame_index));
+        let flip = sprite.is_some_and(|sprite| sprite.flip_x);
+
+        let mut iter = frame_boxes.iter_many_mut(children);
+        while let Some((entity, mut frame_box, mut transform)) = iter.fetch_next() {
+            let rect = frame.and_then(|frame| match frame_box.kind {
+                FrameBoxKind::Hitbox => frame.hitbox,
+                FrameBoxKind::Hurtbox => frame.hurtbox,
+            });
+            let Some(rect) = rect else {
+                if frame_box.current.take().is_some() {
+                    commands.entity(entity).insert(ColliderDisabled);
+                }
+                continue;
+            };
+
+            let center = rect.center();
+            transform.translation.x = if flip { -center.x } else { center.x };
+            transform.translation.y = center.y;
+            if frame_box
+                .current
+                .is_none_or(|current| current.size() != rect.size())
+            {
+                let size = rect.size();
+                commands
+                    .entity(entity)
+                    .insert(Collider::rectangle(size.x, size.y))
+                    .remove::<ColliderDisabled>();
+            } else if frame_box.current.is_none() {
+                commands.entity(entity).remove::<ColliderDisabled>();
+            }
+            frame_box.current = Some(rect);
+        }
+    }
+}
+
+/// The definition of an [`Animation`] in an animation file, made of spans of consecutive atlas
+/// indices.
+///
+/// ```ron
+/// (
+///     spans: [
+///         (atlas: (start: 4, end: 8), millis: 50),
+///         (atlas: (start: 8, end: 9), millis: 200),
+///     ],
+///     markers: [(marker: 0, frames: [1, 3])],
+///     boxes: [(frames: [2, 3], hitbox: Some((min: (0.2, 0.0), max: (1.0, 0.6))))],
+///     loop_mode: Once,
+/// )
+/// ```
+#[derive(Deserialize, Debug)]
+pub struct AnimationDef {
+    pub spans: Vec<FrameSpanDef>,
+    /// Markers triggered as frames start, by index within the whole animation.
+    #[serde(default)]
+    pub markers: Vec<MarkerDef>,
+    #[serde(default)]
+    pub boxes: Vec<FrameBoxesDef>,
+    #[serde(default)]
+    pub loop_mode: LoopMode,
+}
+
+/// A run of frames that show consecutive atlas indices for the same duration.
+#[derive(Deserialize, Debug)]
+pub struct FrameSpanDef {
+    pub atlas: Range<usize>,
+    pub millis: u64,
+}
+
+/// A marker triggered on some frames of an animation.
+#[derive(Deserialize, Debug)]
+pub struct MarkerDef {
+    pub marker: usize,
+    pub frames: Vec<usize>,
+}
+
+/// The hitbox and hurtbox of some frames of an animation, by index within the whole animation.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FrameBoxesDef {
+    pub frames: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hitbox: Option<Rect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hurtbox: Option<Rect>,
+}
+
+impl AnimationDef {
+    /// Builds the animation. Markers and boxes on frames past the end of the animation are
+    /// ignored.
+    pub fn build(&self) -> Animation {
+        let frames = self
+            .spans
+            .iter()
+            .flat_map(|span| {
+                let duration = Duration::from_millis(span.millis);
+                span.atlas
+                    .clone()
+                    .map(move |index| Frame::new(index, duration))
+            })
+            .collect::<Vec<_>>();
+        let len = frames.len();
+
+        let mut animation = Animation {
+            frames,
+            loop_mode: self.loop_mode,
+        };
+        for marker in &self.markers {
+            let frames = marker.frames.iter().copied().filter(|&i| i < len);
+            animation = animation.with_marker(marker.marker, frames);
+        }
+        for boxes in &self.boxes {
+            animation = animation.with_frame_boxes(boxes);
+        }
+        animation
+    }
+}
+
+/// Loads an [`Animation`] from an [`AnimationDef`] in RON or JSON, going by the file extension.
+#[derive(TypePath, Default)]
+pub struct AnimationLoader;
+
+impl AssetLoader for AnimationLoader {
+    type Asset = Animation;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let def: AnimationDef =
+            if load_context.path().get_full_extension().as_deref() == Some("anim.json") {
+                serde_json::from_slice(&bytes)?
+            } else {
+                ron::de::from_bytes(&bytes)?
+            };
+        Ok(def.build())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron", "anim.json"]
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::collections::VecDeque;
 
-use bevy::prelude::*;
+use bevy::{asset::RecursiveDependencyLoadState, prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
@@ -46,12 +46,20 @@ pub struct ResourceHandles {
     #[reflect(ignore)]
     waiting: VecDeque<(UntypedHandle, InsertLoadedResource)>,
     finished: Vec<UntypedHandle>,
+    /// Assets that failed to load, or that have a dependency that failed, with the error.
+    #[reflect(ignore)]
+    failed: Vec<(UntypedHandle, String)>,
 }
 
 impl ResourceHandles {
     /// Returns true if all requested [`Asset`]s have finished loading and are available as [`Resource`]s.
     pub fn is_all_done(&self) -> bool {
-        self.waiting.is_empty()
+        self.waiting.is_empty() && self.failed.is_empty()
+    }
+
+    /// The errors from [`Asset`]s that failed to load. These will never become [`Resource`]s.
+    pub fn failures(&self) -> impl Iterator<Item = &str> {
+        self.failed.iter().map(|(_, error)| error.as_str())
     }
 }
 
@@ -63,6 +71,11 @@ fn load_resource_assets(world: &mut World) {
                 if assets.is_loaded_with_dependencies(&handle) {
                     insert_fn(world, &handle);
                     resource_handles.finished.push(handle);
+                } else if let RecursiveDependencyLoadState::Failed(error) =
+                    assets.recursive_dependency_load_state(&handle)
+                {
+                    error!("Failed to load resource asset: {error}");
+                    resource_handles.failed.push((handle, error.to_string()));
                 } else {
                     resource_handles.waiting.push_back((handle, insert_fn));
                 }
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    animation::{Animation, Frame, LoopMode},
+    assets::serialize::aseprite::{
+        self as de, AsepriteDirection, AsepriteFrame, AsepriteFrames, AsepriteTag,
+    },
+};
+
+/// A sprite sheet exported from Aseprite, with an atlas layout of its frames and an animation for
+/// each of its tags.
+///
+/// The layout and animations are also labeled sub-assets, so `player.aseprite.json#idle` loads
+/// just the `idle` animation and `player.aseprite.json#layout` just the layout.
+#[derive(Asset, Reflect, Debug)]
+pub struct AsepriteSheet {
+    #[dependency]
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    /// The animation of each tag, by name.
+    pub animations: HashMap<String, Handle<Animation>>,
+}
+
+/// Loads the JSON data of an Aseprite sheet export, along with the image it describes.
+///
+/// Frames must be exported as an array rather than a hash, and shouldn't be trimmed or rotated.
+#[derive(TypePath, Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = AsepriteSheet;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let sheet: de::AsepriteSheet = serde_json::from_slice(&bytes)?;
+        let AsepriteFrames::Array(frames) = sheet.frames else {
+            return Err("frames must be exported as an array, not a hash".into());
+        };
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(sheet.meta.size.w, sheet.meta.size.h));
+        for frame in &frames {
+            let rect = frame.frame;
+            layout.add_texture(URect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h));
+        }
+
+        let mut animations = HashMap::new();
+        for tag in &sheet.meta.frame_tags {
+            if tag.from > tag.to || tag.to >= frames.len() {
+                return Err(format!("tag {:?} has frames out of range", tag.name).into());
+            }
+            let handle =
+                load_context.add_labeled_asset(tag.name.clone(), tag_animation(tag, &frames));
+            animations.insert(tag.name.clone(), handle);
+        }
+
+        let image_path = load_context.path().resolve_embed(&sheet.meta.image)?;
+        Ok(AsepriteSheet {
+            image: load_context.load(image_path),
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
+            animations,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+/// Builds the animation for a tag, playing its frames in the tag's direction. Tags with a repeat
+/// count play that many times and then hold their last frame.
+fn tag_animation(tag: &AsepriteTag, frames: &[AsepriteFrame]) -> Animation {
+    let forward = tag.from..=tag.to;
+    let mut indices = match tag.direction {
+        AsepriteDirection::Forward => forward.collect::<Vec<_>>(),
+        AsepriteDirection::Reverse => forward.rev().collect(),
+        // Back and forth without showing the end frames twice in a row
+        AsepriteDirection::Pingpong => forward.clone().chain(forward.rev().skip(1)).collect(),
+        AsepriteDirection::PingpongReverse => {
+            forward.clone().rev().chain(forward.skip(1)).collect()
+        }
+    };
+    if matches!(
+        tag.direction,
+        AsepriteDirection::Pingpong | AsepriteDirection::PingpongReverse
+    ) && indices.len() > 2
+    {
+        // The first frame starts the next loop
+        indices.pop();
+    }
+
+    let repeat = tag
+        .repeat
+        .as_deref()
+        .and_then(|repeat| repeat.parse::<usize>().ok());
+    let loop_mode = match repeat {
+        Some(count) if count > 0 => {
+            indices = indices.repeat(count);
+            LoopMode::Once
+        }
+        _ => LoopMode::Loop,
+    };
+
+    Animation {
+        frames: indices
+            .into_iter()
+            .map(|index| Frame::new(index, Duration::from_millis(frames[index].duration)))
+            .collect(),
+        loop_mode,
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+
+use crate::assets::{enemy::Enemy, serialize::boss as de};
+
+/// An enemy with a larger health pool, that fights in phases.
+#[derive(Asset, Reflect, Debug)]
+pub struct Boss {
+    pub name: String,
+    pub max_health: f32,
+    /// The phases of the fight, from full health down. There is always at least one.
+    pub phases: Vec<BossPhase>,
+}
+
+/// A stage of a boss fight, with its own enemy definition.
+#[derive(Reflect, Debug, Clone)]
+pub struct BossPhase {
+    /// The fraction of max health at or below which this phase starts.
+    pub health: f32,
+    pub enemy: Handle<Enemy>,
+}
+
+impl Boss {
+    /// The index of the phase for the given fraction of max health.
+    pub fn phase_at(&self, health: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| health <= phase.health)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(TypePath, Default)]
+pub struct BossLoader;
+
+impl AssetLoader for BossLoader {
+    type Asset = Boss;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut boss: de::Boss = serde_json::from_slice(&bytes)?;
+        if boss.phases.is_empty() {
+            return Err("boss has no phases".into());
+        }
+        boss.phases.sort_by(|a, b| b.health.total_cmp(&a.health));
+
+        Ok(Boss {
+            name: boss.name,
+            max_health: boss.max_health,
+            phases: boss
+                .phases
+                .into_iter()
+                .map(|phase| BossPhase {
+                    health: phase.health,
+                    enemy: load_context.load(phase.enemy),
+                })
+                .collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["boss.json"]
+    }
+}
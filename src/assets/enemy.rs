@@ -1,12 +1,20 @@
 use avian2d::prelude::Collider;
 use bevy::{
-    asset::{AssetLoader, LoadContext, io::Reader},
+    asset::{AssetLoader, AssetPath, LoadContext, io::Reader},
     platform::collections::HashMap,
     prelude::*,
 };
 
 use crate::{
-    animation::Animation, assets::serialize::enemy as de, demo::movement::MovementController,
+    animation::Animation,
+    assets::{
+        loot::LootTable,
+        serialize::enemy::{self as de, EnemySound},
+    },
+    gameplay::{
+        enemies::ai::AiParams,
+        movement::{FlyingController, MovementArchetype, MovementController},
+    },
 };
 
 #[derive(Asset, Reflect, Debug)]
@@ -20,15 +28,61 @@ pub struct Enemy {
     pub jump_anim: Handle<Animation>,
     pub peak_anim: Handle<Animation>,
     pub fall_anim: Handle<Animation>,
+    /// Played once by the enemy's corpse. Enemies without one are idle as they die.
+    pub death_anim: Option<Handle<Animation>>,
+    /// Whether any of the enemy's animations has hitboxes, which it needs a collider to follow.
+    pub hitboxes: bool,
     #[reflect(ignore)]
     pub collider: Collider,
     pub collider_offset: Vec2,
-    pub movement: MovementController,
+    pub movement: MovementArchetype,
+    pub ai: AiParams,
+    pub attack: Option<EnemyAttack>,
+    pub sounds: EnemySounds,
+    pub loot: Option<LootTable>,
+    pub tags: Vec<String>,
+}
+
+/// How an enemy shoots at players.
+#[derive(Reflect, Debug, Clone)]
+pub struct EnemyAttack {
+    pub projectile: Option<Handle<Image>>,
+    pub speed: f32,
+    pub damage: f32,
+    pub cooldown_secs: f32,
+    pub range: f32,
+    pub bounces: u32,
+    pub penetration: f32,
+}
+
+/// The sounds an enemy makes, by [`EnemySound`].
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct EnemySounds {
+    pub step: Vec<Handle<AudioSource>>,
+    pub jump: Vec<Handle<AudioSource>>,
+    pub hurt: Vec<Handle<AudioSource>>,
+    pub death: Vec<Handle<AudioSource>>,
+}
+
+impl EnemySounds {
+    /// The clips to pick from when `sound` plays.
+    pub fn get(&self, sound: EnemySound) -> &[Handle<AudioSource>] {
+        match sound {
+            EnemySound::Step => &self.step,
+            EnemySound::Jump => &self.jump,
+            EnemySound::Hurt => &self.hurt,
+            EnemySound::Death => &self.death,
+        }
+    }
 }
 
 #[derive(Asset, Reflect)]
 pub struct EnemyManifest {
     pub enemies: HashMap<String, Handle<Enemy>>,
+    /// The unresolved definitions this manifest was loaded from. Dev tools edit these and write
+    /// them back.
+    #[reflect(ignore)]
+    pub source: de::EnemyManifest,
 }
 
 #[derive(TypePath, Default)]
@@ -49,8 +103,9 @@ impl AssetLoader for EnemyManifestLoader {
         reader.read_to_end(&mut bytes).await?;
 
         let mut manifest = HashMap::new();
-        let manifest_toml: de::EnemyManifest = serde_json::from_slice(&bytes)?;
-        for (label, enemy_def) in manifest_toml.enemies {
+        let source = de::EnemyManifest::from_slice(&bytes)?;
+        for label in source.enemies.keys().cloned() {
+            let enemy_def = source.resolve(&label)?;
             let handle = load_context.labeled_asset_scope(label.clone(), |ctx| {
                 let enemy = Enemy {
                     name: enemy_def.name.clone(),
@@ -76,17 +131,33 @@ impl AssetLoader for EnemyManifestLoader {
                         .ok_or("missing peak animation")?,
                     fall_anim: load_animation(ctx, &label, &enemy_def.atlas_animations, "fall")
                         .ok_or("missing fall animation")?,
+                    death_anim: load_animation(ctx, &label, &enemy_def.atlas_animations, "death"),
+                    hitboxes: enemy_def
+                        .atlas_animations
+                        .values()
+                        .flat_map(|anim| &anim.boxes)
+                        .any(|boxes| boxes.hitbox.is_some()),
                     collider: enemy_def.collider.shape.into(),
                     collider_offset: enemy_def.collider.offset,
-                    movement: MovementController {
-                        max_speed: enemy_def.movement.max_speed,
-                        accel_air: enemy_def.movement.accel_air,
-                        accel_ground: enemy_def.movement.accel_ground,
-                        jump_strength: enemy_def.movement.jump_strength,
-                        damping_factor_air: enemy_def.movement.damping_factor_air,
-                        damping_factor_ground: enemy_def.movement.damping_factor_ground,
-                        max_slope_angle: enemy_def.movement.max_slope_angle,
+                    movement: enemy_def.movement.into(),
+                    ai: enemy_def.ai.into(),
+                    attack: enemy_def.attack.map(|attack| EnemyAttack {
+                        projectile: attack.projectile.map(|path| ctx.load(path)),
+                        speed: attack.speed,
+                        damage: attack.damage,
+                        cooldown_secs: attack.cooldown_secs,
+                        range: attack.range,
+                        bounces: attack.bounces,
+                        penetration: attack.penetration,
+                    }),
+                    sounds: EnemySounds {
+                        step: load_sounds(ctx, enemy_def.sounds.step),
+                        jump: load_sounds(ctx, enemy_def.sounds.jump),
+                        hurt: load_sounds(ctx, enemy_def.sounds.hurt),
+                        death: load_sounds(ctx, enemy_def.sounds.death),
                     },
+                    loot: enemy_def.loot,
+                    tags: enemy_def.tags,
                 };
 
                 info!("Loaded enemy {label:?}");
@@ -97,7 +168,10 @@ impl AssetLoader for EnemyManifestLoader {
             manifest.insert(label, handle);
         }
 
-        Ok(EnemyManifest { enemies: manifest })
+        Ok(EnemyManifest {
+            enemies: manifest,
+            source,
+        })
     }
 
     fn extensions(&self) -> &[&str] {
@@ -105,16 +179,88 @@ impl AssetLoader for EnemyManifestLoader {
     }
 }
 
+impl From<de::EnemyMovement> for MovementArchetype {
+    fn from(movement: de::EnemyMovement) -> Self {
+        match movement.kind {
+            de::MovementKind::Walking => Self::Walking(MovementController {
+                max_speed: movement.max_speed,
+                accel_air: movement.accel_air,
+                accel_ground: movement.accel_ground,
+                jump_strength: movement.jump_strength,
+                damping_factor_air: movement.damping_factor_air,
+                damping_factor_ground: movement.damping_factor_ground,
+                max_slope_angle: movement.max_slope_angle,
+            }),
+            de::MovementKind::Flying => Self::Flying(FlyingController {
+                max_speed: movement.max_speed,
+                accel: movement.accel_flying,
+                hover_amplitude: movement.hover_amplitude,
+                hover_period_secs: movement.hover_period_secs,
+                dive_speed: movement.dive_speed,
+            }),
+        }
+    }
+}
+
+impl From<de::EnemyAi> for AiParams {
+    fn from(ai: de::EnemyAi) -> Self {
+        Self {
+            aggro_range: ai.aggro_range,
+            attack_range: ai.attack_range,
+            give_up_secs: ai.give_up_secs,
+            flee_health: ai.flee_health,
+        }
+    }
+}
+
+impl Enemy {
+    /// Returns the handle of the named animation, if this enemy has one.
+    pub fn animation(&self, name: &str) -> Option<&Handle<Animation>> {
+        match name {
+            "idle" => Some(&self.idle_anim),
+            "walk" => Some(&self.walk_anim),
+            "jump" => Some(&self.jump_anim),
+            "peak" => Some(&self.peak_anim),
+            "fall" => Some(&self.fall_anim),
+            "death" => self.death_anim.as_ref(),
+            _ => None,
+        }
+    }
+}
+
 fn load_animation(
     ctx: &mut LoadContext<'_>,
     label: &str,
     atlas_animations: &HashMap<String, de::EnemyAnimation>,
     name: &str,
 ) -> Option<Handle<Animation>> {
-    atlas_animations.get(name).map(|anim| {
-        ctx.add_labeled_asset(
-            format!("{label}_{name}_anim"),
-            Animation::from_frame_range_and_millis(anim.start..anim.end, anim.frame_millis.into()),
-        )
-    })
+    atlas_animations
+        .get(name)
+        .map(|anim| ctx.add_labeled_asset(format!("{label}_{name}_anim"), enemy_animation(anim)))
+}
+
+fn load_sounds(
+    ctx: &mut LoadContext<'_>,
+    paths: Vec<AssetPath<'static>>,
+) -> Vec<Handle<AudioSource>> {
+    paths.into_iter().map(|path| ctx.load(path)).collect()
+}
+
+/// Builds an animation from its definition, with a marker for each sound it plays.
+///
+/// Markers and boxes on frames past the end of the animation are ignored.
+pub fn enemy_animation(anim: &de::EnemyAnimation) -> Animation {
+    let mut animation = Animation::from_frame_range_and_millis(
+        anim.start..anim.end.max(anim.start + 1),
+        anim.frame_millis.max(1).into(),
+    );
+    let len = animation.frames.len();
+    for marker in &anim.markers {
+        let frames = marker.frames.iter().copied().filter(|&frame| frame < len);
+        animation = animation.with_marker(marker.sound.marker(), frames);
+    }
+    for boxes in &anim.boxes {
+        animation = animation.with_frame_boxes(boxes);
+    }
+    animation
 }
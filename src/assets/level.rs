@@ -1,37 +1,70 @@
 use bevy::{
-    asset::{AssetLoader, LoadContext, LoadDirectError, io::Reader},
+    asset::{
+        AssetLoader, LoadContext, LoadDirectError, ReadAssetBytesError,
+        io::{AssetReaderError, Reader},
+    },
     math::I64Vec2,
-    platform::collections::{HashMap, hash_map::Entry},
+    platform::collections::HashMap,
     prelude::*,
     sprite_render::{TileData, TilemapChunkTileData},
 };
+use serde::{Deserialize, de::DeserializeOwned};
 use thiserror::Error;
 
-use crate::assets::{
-    level::{
-        level_collision::LevelCollisionBuilder,
-        tileset_image::{AddTileError, TilesetImageBuilder, UnsupportedFormatError},
-    },
-    serialize::ldtk::{
-        EntityInstance as LdtkEntity, LayerInstance as LdtkLayer, Level as LdtkLevel,
+use crate::{
+    assets::{
+        level::{
+            level_collision::LevelCollisionBuilder,
+            tileset_image::{AddTilesError, UnsupportedFormatError},
+        },
+        serialize::ldtk::{
+            EntityInstance as LdtkEntity, FieldInstance as LdtkField, LayerInstance as LdtkLayer,
+            Level as LdtkLevel,
+        },
     },
+    physics::LevelPhysics,
+    units::{TILE_SIZE, TilesPerSecond, TilesPerSecondSquared, WorldMeters},
 };
 
 mod level_collision;
-mod tileset_image;
+mod tileset_cache;
+pub(crate) mod tileset_image;
 
-pub use level_collision::LevelCollider;
+pub use level_collision::{LevelCollider, LevelOutline};
+pub use tileset_cache::TilesetCache;
 
 #[derive(Asset, Reflect)]
 pub struct Level {
     pub name: String,
     pub grid_size: UVec2,
+    /// The level's bottom-left corner in the LDtk world, in tiles.
     pub grid_offset: IVec2,
     pub player_spawn: Vec2,
     pub enemy_spawns: Vec<EnemySpawn>,
+    pub npc_spawns: Vec<NpcSpawn>,
+    pub doors: Vec<DoorSpawn>,
+    pub light_zones: Vec<LightZoneSpawn>,
+    pub zero_gravity_zones: Vec<ZeroGravityZoneSpawn>,
+    pub moving_platforms: Vec<MovingPlatformSpawn>,
+    pub water_volumes: Vec<WaterVolumeSpawn>,
+    pub props: Vec<PropSpawn>,
+    pub items: Vec<ItemSpawn>,
+    pub trigger_zones: Vec<TriggerZoneSpawn>,
+    pub exits: Vec<LevelExitSpawn>,
+    /// Every entity in the level, for spawners registered with [`RegisterLdtkEntity`].
+    pub entities: Vec<LdtkEntitySpawn>,
+    pub physics: LevelPhysics,
     pub terrain_tileset: Handle<Image>,
     pub terrain_tiledata: TilemapChunkTileData,
+    pub terrain_animations: Vec<TileAnimation>,
     pub terrain_colliders: Vec<LevelCollider>,
+    /// The same terrain as [`terrain_colliders`](Self::terrain_colliders), traced as outlines.
+    pub terrain_outlines: Vec<LevelOutline>,
+    /// Colliders for one-way platforms, which can only be landed on from above.
+    pub platform_colliders: Vec<LevelCollider>,
+    pub hazard_colliders: Vec<HazardCollider>,
+    /// Solid terrain with a different feel to plain walls.
+    pub surface_colliders: Vec<SurfaceCollider>,
 }
 
 impl Level {
@@ -52,14 +85,280 @@ impl Level {
     }
 }
 
+/// A sequence of tiles that cycles in place, like a waterfall or a torch.
+#[derive(Reflect, Clone, Debug)]
+pub struct TileAnimation {
+    /// The frames of the animation, as indices into the level's tileset.
+    pub frames: Vec<u16>,
+    pub frame_secs: f32,
+    /// The tilemap cells playing the animation, and the frame each one was placed on in LDtk.
+    pub cells: Vec<(usize, usize)>,
+}
+
 #[derive(Reflect)]
 pub struct EnemySpawn {
+    /// The LDtk instance id, which the level's events refer to.
+    pub iid: String,
     pub label: String,
     pub position: Vec2,
+    /// The direction the enemy starts walking in, `-1.0` for left or `1.0` for right.
+    pub facing: f32,
+    /// How far the enemy may wander from its spawn point, or `None` to wander freely.
+    pub patrol_radius: Option<f32>,
+}
+
+/// A non-hostile character placed with an `Npc` LDtk entity.
+#[derive(Reflect)]
+pub struct NpcSpawn {
+    /// The label of the actor definition in the enemy manifest used for visuals and movement.
+    pub label: String,
+    pub name: String,
+    pub position: Vec2,
+    /// Points the NPC walks between, in order.
+    pub waypoints: Vec<Vec2>,
+    /// How long the NPC idles at each waypoint, in seconds.
+    pub wait_secs: f32,
+    pub dialogue: Option<String>,
+}
+
+/// An entrance to another level, placed with a `Door` LDtk entity.
+#[derive(Reflect)]
+pub struct DoorSpawn {
+    /// A unique name for the door, used to track whether it has been unlocked.
+    pub id: String,
+    pub position: Vec2,
+    /// The level the door leads to.
+    pub level: Handle<Level>,
+    /// The door that must be passed through before this one unlocks, if any.
+    pub unlocked_by: Option<String>,
+}
+
+/// A region with its own speed of light, placed with a `Speed_Of_Light_Zone` LDtk entity.
+#[derive(Reflect)]
+pub struct LightZoneSpawn {
+    pub rect: Rect,
+    pub speed_of_light: f32,
 }
 
-#[derive(TypePath, Default)]
-pub struct LevelLoader;
+/// A region without gravity, placed with a `Zero_Gravity_Zone` LDtk entity.
+#[derive(Reflect)]
+pub struct ZeroGravityZoneSpawn {
+    pub rect: Rect,
+}
+
+/// A platform placed with a `Moving_Platform` LDtk entity.
+#[derive(Reflect)]
+pub struct MovingPlatformSpawn {
+    pub size: Vec2,
+    /// Positions of the platform's center, starting where it was placed.
+    pub waypoints: Vec<Vec2>,
+    pub speed: f32,
+    pub easing: EaseFunction,
+}
+
+/// A body of water placed with a `Water_Volume` LDtk entity.
+#[derive(Reflect)]
+pub struct WaterVolumeSpawn {
+    pub rect: Rect,
+    pub buoyancy: f32,
+}
+
+/// A breakable object placed with a `Prop` LDtk entity.
+#[derive(Reflect)]
+pub struct PropSpawn {
+    /// The LDtk instance id, which the level's events refer to.
+    pub iid: String,
+    /// The kind of prop, such as `crate` or `pot`.
+    pub kind: String,
+    pub position: Vec2,
+    /// The label of the loot table it drops from.
+    pub loot_table: String,
+}
+
+/// A collectible placed with an `Item` LDtk entity.
+#[derive(Reflect)]
+pub struct ItemSpawn {
+    /// The LDtk instance id, which the level's events refer to.
+    pub iid: String,
+    /// The item's type, such as `gold_coin`.
+    pub item: String,
+    pub position: Vec2,
+    /// Whether the item is carried through the rest of a run once collected, like a key.
+    pub persistent: bool,
+}
+
+/// A region that reports when players enter or leave it, placed with a `Trigger_Zone` LDtk entity.
+#[derive(Reflect)]
+pub struct TriggerZoneSpawn {
+    /// The zone's `Id` field, or its LDtk iid if that isn't set.
+    pub id: String,
+    pub rect: Rect,
+    /// Every other field on the entity, with strings unquoted and anything else as JSON.
+    pub fields: HashMap<String, String>,
+}
+
+/// The way out of a level, placed with a `Level_Exit` LDtk entity.
+#[derive(Reflect)]
+pub struct LevelExitSpawn {
+    pub id: String,
+    pub rect: Rect,
+    /// The level to continue to, or `None` to finish the run and return to the hub.
+    pub next: Option<Handle<Level>>,
+}
+
+/// Terrain that hurts whatever touches it.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HazardKind {
+    Spikes,
+    Lava,
+}
+
+impl HazardKind {
+    const ALL: [Self; 2] = [Self::Spikes, Self::Lava];
+
+    /// The `Terrain` int-grid value for this hazard.
+    fn int_grid_value(self) -> i64 {
+        match self {
+            Self::Spikes => 3,
+            Self::Lava => 4,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy)]
+pub struct HazardCollider {
+    pub kind: HazardKind,
+    pub collider: LevelCollider,
+}
+
+/// Solid terrain that changes how characters move and sound on it.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceKind {
+    Ice,
+    Mud,
+    Bouncy,
+}
+
+impl SurfaceKind {
+    const ALL: [Self; 3] = [Self::Ice, Self::Mud, Self::Bouncy];
+
+    /// The `Terrain` int-grid value for this surface.
+    fn int_grid_value(self) -> i64 {
+        match self {
+            Self::Ice => 5,
+            Self::Mud => 6,
+            Self::Bouncy => 7,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy)]
+pub struct SurfaceCollider {
+    pub kind: SurfaceKind,
+    pub collider: LevelCollider,
+}
+
+/// An LDtk entity with its fields left as JSON, to be spawned by the [`LdtkEntityRegistry`].
+#[derive(Reflect)]
+pub struct LdtkEntitySpawn {
+    pub identifier: String,
+    pub iid: String,
+    /// The center of the entity's cell, in level coordinates.
+    pub position: Vec2,
+    /// The area covered by the entity, in level coordinates.
+    pub rect: Rect,
+    /// The entity's non-null fields by identifier.
+    #[reflect(ignore)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Error, Debug)]
+#[error("invalid fields on LDtk entity {iid:?}: {error}")]
+pub struct LdtkFieldsError {
+    iid: String,
+    error: serde_json::Error,
+}
+
+type LdtkEntitySpawner =
+    Box<dyn Fn(&LdtkEntitySpawn, &mut EntityCommands) -> Result + Send + Sync + 'static>;
+
+/// Spawners for LDtk entities, keyed by entity identifier.
+///
+/// Spawners are added with [`RegisterLdtkEntity::register_ldtk_entity`].
+#[derive(Resource, Default)]
+pub struct LdtkEntityRegistry {
+    spawners: HashMap<String, LdtkEntitySpawner>,
+}
+
+impl LdtkEntityRegistry {
+    /// Inserts the bundle registered for `entity` into `commands`, or returns `None` if nothing
+    /// is registered for its identifier.
+    pub fn spawn(&self, entity: &LdtkEntitySpawn, commands: &mut EntityCommands) -> Option<Result> {
+        let spawner = self.spawners.get(&entity.identifier)?;
+        Some(spawner(entity, commands))
+    }
+}
+
+pub trait RegisterLdtkEntity {
+    /// Spawns the bundle returned by `spawn` for every LDtk entity with the given identifier.
+    ///
+    /// The entity's fields are deserialized into `F` by their LDtk identifiers, so fields that
+    /// are null or missing can be given defaults with `#[serde(default)]`.
+    fn register_ldtk_entity<F, B>(
+        &mut self,
+        identifier: &str,
+        spawn: impl Fn(F, &LdtkEntitySpawn) -> B + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        F: DeserializeOwned,
+        B: Bundle;
+}
+
+impl RegisterLdtkEntity for App {
+    fn register_ldtk_entity<F, B>(
+        &mut self,
+        identifier: &str,
+        spawn: impl Fn(F, &LdtkEntitySpawn) -> B + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        F: DeserializeOwned,
+        B: Bundle,
+    {
+        let spawner: LdtkEntitySpawner = Box::new(move |entity, commands| {
+            let fields = F::deserialize(&serde_json::Value::Object(entity.fields.clone()))
+                .map_err(|error| LdtkFieldsError {
+                    iid: entity.iid.clone(),
+                    error,
+                })?;
+            commands.insert(spawn(fields, entity));
+            Ok(())
+        });
+
+        self.world_mut()
+            .get_resource_or_init::<LdtkEntityRegistry>()
+            .spawners
+            .insert(identifier.to_string(), spawner);
+        self
+    }
+}
+
+/// The `Terrain` int-grid value for solid walls.
+const WALL_VALUE: i64 = 1;
+/// The `Terrain` int-grid value for one-way platforms.
+const PLATFORM_VALUE: i64 = 2;
+
+#[derive(TypePath)]
+pub struct LevelLoader {
+    tilesets: TilesetCache,
+}
+
+impl FromWorld for LevelLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            tilesets: world.get_resource_or_init::<TilesetCache>().clone(),
+        }
+    }
+}
 
 impl AssetLoader for LevelLoader {
     type Asset = Level;
@@ -76,69 +375,263 @@ impl AssetLoader for LevelLoader {
         reader.read_to_end(&mut bytes).await?;
 
         let ldtk: LdtkLevel = serde_json::from_slice(&bytes)?;
-        let level_offset = IVec2::new(ldtk.world_x as _, -ldtk.world_y as _);
+        build_level(load_context, &self.tilesets, ldtk).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtkl"]
+    }
+}
 
-        let entities_layer = get_named_layer(&ldtk, "Entities").unwrap();
+/// Builds a [`Level`] from its LDtk definition, which may come from either a standalone `.ldtkl`
+/// file or a level embedded in a world file.
+pub(super) async fn build_level(
+    load_context: &mut LoadContext<'_>,
+    tilesets: &TilesetCache,
+    ldtk: LdtkLevel,
+) -> Result<Level> {
+    let entities_layer = get_named_layer(&ldtk, "Entities")?;
+
+    let player_spawn_entity = get_named_entity(entities_layer, "Player_Spawn")
+        .ok_or(BuildLevelError::MissingPlayerSpawn)?;
+    let player_spawn = grid_position(
+        entities_layer,
+        player_spawn_entity.grid[0],
+        player_spawn_entity.grid[1],
+    );
 
-        let player_spawn_entity = get_named_entity(entities_layer, "Player_Spawn").unwrap();
-        let player_spawn = I64Vec2::new(
-            player_spawn_entity.grid[0],
-            entities_layer.c_hei - player_spawn_entity.grid[1] - 1,
-        )
-        .as_vec2()
-            + Vec2::splat(0.5);
-
-        let enemy_spawns = iter_enemies(entities_layer)
-            .map(|(label, def)| EnemySpawn {
-                label: label.to_lowercase(),
-                position: I64Vec2::new(def.grid[0], entities_layer.c_hei - def.grid[1] - 1)
-                    .as_vec2()
-                    + Vec2::splat(0.5),
+    let enemy_spawns = iter_typed_entities(entities_layer, "Enemy", "Type", "EnemyType")
+        .map(|(label, def)| EnemySpawn {
+            iid: def.iid.clone(),
+            label: label.to_lowercase(),
+            position: grid_position(entities_layer, def.grid[0], def.grid[1]),
+            facing: if get_field(def, "Face_Left").and_then(|v| v.as_bool()) == Some(true) {
+                -1.0
+            } else {
+                1.0
+            },
+            patrol_radius: get_field(def, "Patrol_Radius")
+                .and_then(|v| v.as_f64())
+                .map(|tiles| WorldMeters::from_tiles(tiles as f32).0),
+        })
+        .collect();
+
+    let items = iter_typed_entities(entities_layer, "Item", "ItemType", "ItemType")
+        .map(|(item, def)| ItemSpawn {
+            iid: def.iid.clone(),
+            item: item.to_lowercase(),
+            position: grid_position(entities_layer, def.grid[0], def.grid[1]),
+            persistent: get_field(def, "Persistent").and_then(|v| v.as_bool()) == Some(true),
+        })
+        .collect();
+
+    let npc_spawns = iter_named_entities(entities_layer, "Npc")
+        .map(|def| npc_spawn(entities_layer, def))
+        .collect();
+
+    let doors = iter_named_entities(entities_layer, "Door")
+        .filter_map(|def| door_spawn(load_context, entities_layer, def))
+        .collect();
+
+    let light_zones = iter_named_entities(entities_layer, "Speed_Of_Light_Zone")
+        .filter_map(|def| {
+            let Some(speed_of_light) = get_field(def, "C").and_then(|v| v.as_f64()) else {
+                warn!("Speed of light zone {:?} has no `C` field", def.iid);
+                return None;
+            };
+            Some(LightZoneSpawn {
+                rect: grid_rect(entities_layer, def),
+                speed_of_light: speed_of_light as f32,
             })
-            .collect();
+        })
+        .collect();
 
-        let terrain_layer = get_named_layer(&ldtk, "Terrain").unwrap();
+    let zero_gravity_zones = iter_named_entities(entities_layer, "Zero_Gravity_Zone")
+        .map(|def| ZeroGravityZoneSpawn {
+            rect: grid_rect(entities_layer, def),
+        })
+        .collect();
+
+    let moving_platforms = iter_named_entities(entities_layer, "Moving_Platform")
+        .map(|def| moving_platform_spawn(entities_layer, def))
+        .collect();
+
+    let water_volumes = iter_named_entities(entities_layer, "Water_Volume")
+        .map(|def| WaterVolumeSpawn {
+            rect: grid_rect(entities_layer, def),
+            buoyancy: TilesPerSecondSquared(
+                get_field(def, "Buoyancy")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(5.0) as f32,
+            )
+            .to_world(),
+        })
+        .collect();
+
+    let props = iter_named_entities(entities_layer, "Prop")
+        .map(|def| {
+            let string_field = |name| get_field(def, name).and_then(|v| v.as_str());
+            let kind = string_field("Type").unwrap_or("crate").to_lowercase();
+            PropSpawn {
+                iid: def.iid.clone(),
+                loot_table: string_field("Loot").map_or_else(|| kind.clone(), str::to_lowercase),
+                kind,
+                position: grid_position(entities_layer, def.grid[0], def.grid[1]),
+            }
+        })
+        .collect();
+
+    let trigger_zones = iter_named_entities(entities_layer, "Trigger_Zone")
+        .map(|def| TriggerZoneSpawn {
+            id: get_field(def, "Id")
+                .and_then(|v| v.as_str())
+                .unwrap_or(&def.iid)
+                .to_string(),
+            rect: grid_rect(entities_layer, def),
+            fields: def
+                .field_instances
+                .iter()
+                .filter(|field| field.identifier != "Id")
+                .filter_map(|field| {
+                    let value = match field.value.as_ref()? {
+                        serde_json::Value::String(s) => s.clone(),
+                        value => value.to_string(),
+                    };
+                    Some((field.identifier.clone(), value))
+                })
+                .collect(),
+        })
+        .collect();
+
+    let exits = iter_named_entities(entities_layer, "Level_Exit")
+        .map(|def| LevelExitSpawn {
+            id: def.iid.clone(),
+            rect: grid_rect(entities_layer, def),
+            next: get_field(def, "Level")
+                .and_then(|v| v.as_str())
+                .map(|level| load_context.load(level.to_string())),
+        })
+        .collect();
 
-        let grid_size = UVec2::new(terrain_layer.c_wid as _, terrain_layer.c_hei as _);
-        let _grid_offset = IVec2::new(
-            terrain_layer.px_total_offset_x as _,
-            terrain_layer.px_total_offset_y as _,
-        ) / terrain_layer.grid_size as i32;
+    let entities = entities_layer
+        .entity_instances
+        .iter()
+        .map(|def| LdtkEntitySpawn {
+            identifier: def.identifier.clone(),
+            iid: def.iid.clone(),
+            position: grid_position(entities_layer, def.grid[0], def.grid[1]),
+            rect: grid_rect(entities_layer, def),
+            fields: def
+                .field_instances
+                .iter()
+                .filter_map(|field| Some((field.identifier.clone(), field.value.clone()?)))
+                .collect(),
+        })
+        .collect();
 
-        let terrain_colliders = LevelCollisionBuilder::from_grid(
-            grid_size,
-            terrain_layer.int_grid_csv.iter().map(|i| *i != 0).collect(),
-            true,
-        )
-        .build();
+    let float_field = |name| {
+        find_field(&ldtk.field_instances, name)
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+    };
+    let default_physics = LevelPhysics::default();
+    let physics = LevelPhysics {
+        gravity: default_physics.gravity * float_field("Gravity_Scale").unwrap_or(1.0),
+        damping_scale: float_field("Damping_Scale").unwrap_or(1.0),
+    };
 
-        let terrain_tiles_layer = get_named_layer(&ldtk, "TerrainTiles").unwrap();
-        let (terrain_tileset, terrain_tiledata) =
-            build_tilemap_from_layer(load_context, terrain_tiles_layer).await?;
+    let terrain_layer = get_named_layer(&ldtk, "Terrain")?;
 
-        Ok(Level {
-            name: ldtk.identifier,
+    let grid_size = UVec2::new(terrain_layer.c_wid as _, terrain_layer.c_hei as _);
+    // LDtk measures from the top-left in pixels, levels are laid out from the bottom-left in tiles
+    let grid_offset = IVec2::new(
+        (ldtk.world_x / terrain_layer.grid_size) as _,
+        (-ldtk.world_y / terrain_layer.grid_size - terrain_layer.c_hei) as _,
+    );
+
+    let int_grid_builder = |value| {
+        LevelCollisionBuilder::from_grid(
             grid_size,
-            grid_offset: level_offset,
-            player_spawn,
-            enemy_spawns,
-            terrain_tileset,
-            terrain_tiledata,
-            terrain_colliders,
+            terrain_layer
+                .int_grid_csv
+                .iter()
+                .map(|i| *i == value)
+                .collect(),
+            true,
+        )
+    };
+    let int_grid_colliders = |value| int_grid_builder(value).build();
+    let terrain_colliders = int_grid_colliders(WALL_VALUE);
+    let terrain_outlines = int_grid_builder(WALL_VALUE).build_outlines();
+    let platform_colliders = int_grid_colliders(PLATFORM_VALUE);
+    let hazard_colliders = HazardKind::ALL
+        .into_iter()
+        .flat_map(|kind| {
+            int_grid_colliders(kind.int_grid_value())
+                .into_iter()
+                .map(move |collider| HazardCollider { kind, collider })
         })
-    }
+        .collect();
+    let surface_colliders = SurfaceKind::ALL
+        .into_iter()
+        .flat_map(|kind| {
+            int_grid_colliders(kind.int_grid_value())
+                .into_iter()
+                .map(move |collider| SurfaceCollider { kind, collider })
+        })
+        .collect();
+
+    let terrain_tiles_layer = get_named_layer(&ldtk, "TerrainTiles")?;
+    let (terrain_tileset, terrain_tiledata, terrain_animations) =
+        build_tilemap_from_layer(load_context, tilesets, terrain_tiles_layer).await?;
+
+    Ok(Level {
+        name: ldtk.identifier,
+        grid_size,
+        grid_offset,
+        player_spawn,
+        enemy_spawns,
+        npc_spawns,
+        doors,
+        light_zones,
+        zero_gravity_zones,
+        moving_platforms,
+        water_volumes,
+        props,
+        items,
+        trigger_zones,
+        exits,
+        entities,
+        physics,
+        terrain_tileset,
+        terrain_tiledata,
+        terrain_animations,
+        terrain_colliders,
+        terrain_outlines,
+        platform_colliders,
+        hazard_colliders,
+        surface_colliders,
+    })
+}
 
-    fn extensions(&self) -> &[&str] {
-        &["ldtkl"]
-    }
+#[derive(Debug, Error)]
+pub enum BuildLevelError {
+    #[error("level has no {0:?} layer")]
+    MissingLayer(&'static str),
+    #[error("level has no `Player_Spawn` entity")]
+    MissingPlayerSpawn,
 }
 
-fn get_named_layer<'a>(level: &'a LdtkLevel, name: &str) -> Option<&'a LdtkLayer> {
+fn get_named_layer<'a>(
+    level: &'a LdtkLevel,
+    name: &'static str,
+) -> Result<&'a LdtkLayer, BuildLevelError> {
     level
         .layer_instances
-        .as_ref()?
         .iter()
+        .flatten()
         .find(|layer| layer.identifier == name)
+        .ok_or(BuildLevelError::MissingLayer(name))
 }
 
 fn get_named_entity<'a>(layer: &'a LdtkLayer, name: &str) -> Option<&'a LdtkEntity> {
@@ -158,15 +651,143 @@ fn iter_named_entities<'a>(
         .filter(move |entity| entity.identifier == name)
 }
 
-fn iter_enemies(layer: &LdtkLayer) -> impl Iterator<Item = (&str, &LdtkEntity)> {
-    iter_named_entities(layer, "Enemy").filter_map(|entity| {
+/// Converts LDtk grid coordinates (y down) into level coordinates (y up) at the cell's center.
+fn grid_position(layer: &LdtkLayer, cx: i64, cy: i64) -> Vec2 {
+    (I64Vec2::new(cx, layer.c_hei - cy - 1).as_vec2() + Vec2::splat(0.5)) * TILE_SIZE.0
+}
+
+/// Returns the area covered by a (possibly resized) entity, in level coordinates.
+fn grid_rect(layer: &LdtkLayer, entity: &LdtkEntity) -> Rect {
+    let cell = layer.grid_size as f32;
+    let min_x = entity.px[0] as f32 / cell;
+    let max_y = layer.c_hei as f32 - entity.px[1] as f32 / cell;
+    let rect = Rect::new(
+        min_x,
+        max_y - entity.height as f32 / cell,
+        min_x + entity.width as f32 / cell,
+        max_y,
+    );
+    Rect::from_corners(rect.min * TILE_SIZE.0, rect.max * TILE_SIZE.0)
+}
+
+fn get_field<'a>(entity: &'a LdtkEntity, name: &str) -> Option<&'a serde_json::Value> {
+    find_field(&entity.field_instances, name)
+}
+
+fn find_field<'a>(fields: &'a [LdtkField], name: &str) -> Option<&'a serde_json::Value> {
+    fields
+        .iter()
+        .find(|field| field.identifier == name)?
+        .value
+        .as_ref()
+}
+
+/// Reads an `Array<Point>` field as level coordinates at the center of each cell.
+fn point_array_field(layer: &LdtkLayer, entity: &LdtkEntity, name: &str) -> Vec<Vec2> {
+    get_field(entity, name)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|point| {
+            let cx = point.get("cx")?.as_i64()?;
+            let cy = point.get("cy")?.as_i64()?;
+            Some(grid_position(layer, cx, cy))
+        })
+        .collect()
+}
+
+fn npc_spawn(layer: &LdtkLayer, entity: &LdtkEntity) -> NpcSpawn {
+    let string_field = |name| get_field(entity, name).and_then(|v| v.as_str());
+    let waypoints = point_array_field(layer, entity, "Waypoints");
+
+    NpcSpawn {
+        label: string_field("Type").unwrap_or_default().to_lowercase(),
+        name: string_field("Name").unwrap_or("Villager").to_string(),
+        position: grid_position(layer, entity.grid[0], entity.grid[1]),
+        waypoints,
+        wait_secs: get_field(entity, "Wait")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(2.0) as f32,
+        dialogue: string_field("Dialogue").map(str::to_string),
+    }
+}
+
+fn moving_platform_spawn(layer: &LdtkLayer, entity: &LdtkEntity) -> MovingPlatformSpawn {
+    let rect = grid_rect(layer, entity);
+
+    // Path points mark where the platform's top-left cell goes.
+    let offset = rect.center() - grid_position(layer, entity.grid[0], entity.grid[1]);
+    let waypoints = std::iter::once(rect.center())
+        .chain(
+            point_array_field(layer, entity, "Path")
+                .into_iter()
+                .map(|point| point + offset),
+        )
+        .collect();
+
+    let easing = match get_field(entity, "Easing").and_then(|v| v.as_str()) {
+        None | Some("SineInOut") => EaseFunction::SineInOut,
+        Some("Linear") => EaseFunction::Linear,
+        Some("SmoothStep") => EaseFunction::SmoothStep,
+        Some("QuadraticInOut") => EaseFunction::QuadraticInOut,
+        Some("CubicInOut") => EaseFunction::CubicInOut,
+        Some(other) => {
+            warn!(
+                "Unknown easing {other:?} on moving platform {:?}",
+                entity.iid
+            );
+            EaseFunction::SineInOut
+        }
+    };
+
+    MovingPlatformSpawn {
+        size: rect.size(),
+        waypoints,
+        speed: TilesPerSecond(
+            get_field(entity, "Speed")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(3.0) as f32,
+        )
+        .to_world(),
+        easing,
+    }
+}
+
+fn door_spawn(
+    load_context: &mut LoadContext,
+    layer: &LdtkLayer,
+    entity: &LdtkEntity,
+) -> Option<DoorSpawn> {
+    let string_field = |name| get_field(entity, name).and_then(|v| v.as_str());
+    let Some(level) = string_field("Level") else {
+        warn!("Door {:?} doesn't lead anywhere", entity.iid);
+        return None;
+    };
+
+    Some(DoorSpawn {
+        id: string_field("Id").unwrap_or(&entity.iid).to_string(),
+        position: grid_position(layer, entity.grid[0], entity.grid[1]),
+        level: load_context.load(level.to_string()),
+        unlocked_by: string_field("Unlocked_By").map(str::to_string),
+    })
+}
+
+/// Iterates over the entities named `name` along with the value of their `field`, which must be
+/// the local enum `enum_name`.
+fn iter_typed_entities<'a>(
+    layer: &'a LdtkLayer,
+    name: &str,
+    field: &'a str,
+    enum_name: &str,
+) -> impl Iterator<Item = (&'a str, &'a LdtkEntity)> {
+    let field_type = format!("LocalEnum.{enum_name}");
+    iter_named_entities(layer, name).filter_map(move |entity| {
         entity
             .field_instances
             .iter()
-            .find_map(|field| {
-                if field.identifier == "Type" && field.field_instance_type == "LocalEnum.EnemyType"
-                {
-                    field.value.as_ref().and_then(|v| v.as_str())
+            .find_map(|instance| {
+                if instance.identifier == field && instance.field_instance_type == field_type {
+                    instance.value.as_ref().and_then(|v| v.as_str())
                 } else {
                     None
                 }
@@ -183,18 +804,43 @@ pub enum BuildTilemapError {
     LoadTilesetImage(#[from] LoadDirectError),
     #[error(transparent)]
     Format(#[from] UnsupportedFormatError),
-    #[error("failed to copy tile from source offset {offset:?}: {error}")]
-    AddTile {
-        offset: UVec2,
-        #[source]
-        error: AddTileError,
-    },
+    #[error("failed to read tile animations: {0}")]
+    ReadAnimations(#[from] ReadAssetBytesError),
+    #[error("failed to parse tile animations: {0}")]
+    ParseAnimations(#[from] serde_json::Error),
+    #[error(transparent)]
+    AddTiles(#[from] AddTilesError),
+}
+
+/// An animation in a tileset's `.anim.json` sidecar, e.g. `tiles.anim.json` for `tiles.png`.
+///
+/// The sidecar is a list of these. A tile placed in LDtk using any of the frames plays the whole
+/// animation, starting from that frame.
+#[derive(Deserialize)]
+struct TileAnimationDef {
+    /// Tile ids in the source tileset, as shown in LDtk.
+    frames: Vec<i64>,
+    fps: f32,
+}
+
+/// Reads the animations for a tileset, if it has a sidecar.
+async fn load_tile_animations(
+    load_context: &mut LoadContext<'_>,
+    tileset_path: &str,
+) -> Result<Vec<TileAnimationDef>, BuildTilemapError> {
+    let path = std::path::Path::new(tileset_path).with_extension("anim.json");
+    match load_context.read_asset_bytes(path).await {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_))) => Ok(vec![]),
+        Err(error) => Err(error.into()),
+    }
 }
 
 async fn build_tilemap_from_layer(
     load_context: &mut LoadContext<'_>,
+    tilesets: &TilesetCache,
     layer: &LdtkLayer,
-) -> Result<(Handle<Image>, TilemapChunkTileData), BuildTilemapError> {
+) -> Result<(Handle<Image>, TilemapChunkTileData, Vec<TileAnimation>), BuildTilemapError> {
     let tileset_path = layer
         .tileset_rel_path
         .as_ref()
@@ -204,6 +850,7 @@ async fn build_tilemap_from_layer(
         .immediate()
         .load::<Image>(tileset_path)
         .await?;
+    let animation_defs = load_tile_animations(load_context, tileset_path).await?;
 
     let tile_size = layer.grid_size;
     let tiles = if layer.grid_tiles.is_empty() {
@@ -212,21 +859,21 @@ async fn build_tilemap_from_layer(
         &layer.grid_tiles
     };
 
-    let mut tile_id_map = HashMap::new();
-    let mut tileset_builder = TilesetImageBuilder::new(
-        UVec2::splat(tile_size as _),
-        tileset_image.get().texture_descriptor.format,
-    )?;
-
-    for tile in tiles {
-        let offset = UVec2::new(tile.src[0] as _, tile.src[1] as _);
-        if let Entry::Vacant(e) = tile_id_map.entry(tile.t) {
-            e.insert(
-                tileset_builder
-                    .add_tile(tileset_image.get(), offset)
-                    .map_err(|error| BuildTilemapError::AddTile { offset, error })?,
-            );
+    // Shared tilesets hold every tile, so tile ids are used as they are.
+    let mut animations = Vec::new();
+    let mut animation_lookup = HashMap::new();
+    for def in &animation_defs {
+        if !tiles.iter().any(|tile| def.frames.contains(&tile.t)) {
+            continue;
         }
+        for (frame, &t) in def.frames.iter().enumerate() {
+            animation_lookup.insert(t, (animations.len(), frame));
+        }
+        animations.push(TileAnimation {
+            frames: def.frames.iter().map(|&t| t as u16).collect(),
+            frame_secs: def.fps.recip(),
+            cells: vec![],
+        });
     }
 
     let w = layer.c_wid as usize;
@@ -234,7 +881,15 @@ async fn build_tilemap_from_layer(
     let mut tile_data = vec![None; w * h];
     for tile in tiles {
         let i = (tile.px[0] + layer.c_wid * tile.px[1]) / tile_size;
-        tile_data[i as usize] = Some(TileData::from_tileset_index(tile_id_map[&tile.t]));
+        tile_data[i as usize] = Some(TileData::from_tileset_index(tile.t as u16));
+
+        if let Some(&(animation, frame)) = animation_lookup.get(&tile.t) {
+            // Matches the flip below
+            let (x, y) = (i as usize % w, i as usize / w);
+            animations[animation]
+                .cells
+                .push(((h - y - 1) * w + x, frame));
+        }
     }
 
     // Y-flip tilemap
@@ -244,10 +899,13 @@ async fn build_tilemap_from_layer(
         unsafe { core::ptr::swap_nonoverlapping(ptr.add(r * w), ptr.add((h - r - 1) * w), w) };
     }
 
-    let tileset_image = load_context.add_labeled_asset(
-        format!("{}_tiles", layer.identifier),
-        tileset_builder.build(),
-    );
+    let tileset_image = tilesets
+        .get_or_build(
+            tileset_path,
+            UVec2::splat(tile_size as _),
+            tileset_image.take(),
+        )
+        .await?;
 
-    Ok((tileset_image, TilemapChunkTileData(tile_data)))
+    Ok((tileset_image, TilemapChunkTileData(tile_data), animations))
 }
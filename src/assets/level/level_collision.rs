@@ -1,12 +1,15 @@
 use avian2d::prelude::Collider;
 use bevy::{
     math::{IRect, IVec2, URect, UVec2},
+    platform::collections::HashMap,
     prelude::Deref,
     reflect::{Reflect, ReflectDeserialize, ReflectSerialize},
     transform::components::Transform,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::units::WorldMeters;
+
 /// A rectangle describing a collision rectangle for level terrain.
 #[derive(Reflect, Serialize, Deserialize, Debug, Deref, Clone, Copy)]
 #[reflect(Serialize, Deserialize)]
@@ -17,10 +20,10 @@ impl LevelCollider {
     /// Creates a [`Collider`] and [`Transform`] for this collider in the level's local space.
     ///
     /// These should be added as children of the level entity.
-    pub fn into_collider_and_transform(self, scale: f32) -> (Collider, Transform) {
+    pub fn into_collider_and_transform(self, tile_size: WorldMeters) -> (Collider, Transform) {
         let rect = self.as_rect();
-        let size = rect.size() * scale;
-        let center = rect.center() * scale;
+        let size = rect.size() * tile_size.0;
+        let center = rect.center() * tile_size.0;
         (
             Collider::rectangle(size.x, size.y),
             Transform::from_translation(center.extend(0.0)),
@@ -28,6 +31,27 @@ impl LevelCollider {
     }
 }
 
+/// A closed loop tracing the edge of a solid region of level terrain, in grid coordinates.
+///
+/// Vertices wind counter-clockwise around solid ground (clockwise around holes), and only
+/// corners are kept.
+#[derive(Reflect, Serialize, Deserialize, Debug, Deref, Clone)]
+#[reflect(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LevelOutline(pub Vec<UVec2>);
+
+impl LevelOutline {
+    /// Creates a closed polyline [`Collider`] for this outline in the level's local space.
+    ///
+    /// Unlike a set of rectangles, there are no internal edges for characters to catch on.
+    pub fn to_collider(&self, tile_size: WorldMeters) -> Collider {
+        let vertices = self.iter().map(|v| v.as_vec2() * tile_size.0).collect();
+        let n = self.len() as u32;
+        let indices = (0..n).map(|i| [i, (i + 1) % n]).collect();
+        Collider::polyline(vertices, Some(indices))
+    }
+}
+
 /// Used to build colliders from a boolean collision grid.
 pub struct LevelCollisionBuilder {
     bounds: IRect,
@@ -174,6 +198,79 @@ impl LevelCollisionBuilder {
         colliders
     }
 
+    /// Traces the outlines of every solid region in the collision grid.
+    ///
+    /// This is an alternative to [`build`](Self::build) that produces one collider per region
+    /// instead of many rectangles, so there are no seams between them.
+    pub fn build_outlines(&self) -> Vec<LevelOutline> {
+        // Collect every cell edge between solid and empty cells, directed so the solid cell is on
+        // its left. This makes outer boundaries wind counter-clockwise and holes clockwise.
+        let mut edges: HashMap<IVec2, Vec<IVec2>> = HashMap::new();
+        for y in self.bounds.min.y..self.bounds.max.y {
+            for x in self.bounds.min.x..self.bounds.max.x {
+                let cell = IVec2::new(x, y);
+                if !self.get(cell) {
+                    continue;
+                }
+                for (side, start, dir) in [
+                    (IVec2::NEG_Y, IVec2::ZERO, IVec2::X),
+                    (IVec2::X, IVec2::X, IVec2::Y),
+                    (IVec2::Y, IVec2::ONE, IVec2::NEG_X),
+                    (IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y),
+                ] {
+                    if !self.get(cell + side) {
+                        edges.entry(cell + start).or_default().push(dir);
+                    }
+                }
+            }
+        }
+
+        // Chain edges into loops. Where two regions touch at a corner, turning left keeps them
+        // as separate loops.
+        let mut outlines = Vec::new();
+        let mut starts: Vec<_> = edges.keys().copied().collect();
+        starts.sort_by_key(|v| (v.y, v.x));
+        for start in starts {
+            while let Some(first_dir) = edges.get_mut(&start).and_then(Vec::pop) {
+                let mut vertices = vec![start];
+                let mut dir = first_dir;
+                let mut point = start + dir;
+                while point != start {
+                    let Some(outgoing) = edges.get_mut(&point) else {
+                        break;
+                    };
+                    let left = dir.perp();
+                    let Some(i) = [left, dir, -left]
+                        .into_iter()
+                        .find_map(|turn| outgoing.iter().position(|&d| d == turn))
+                    else {
+                        break;
+                    };
+                    let next_dir = outgoing.swap_remove(i);
+                    if next_dir != dir {
+                        vertices.push(point);
+                    }
+                    dir = next_dir;
+                    point += dir;
+                }
+
+                // The start is only a corner if the loop turns there.
+                if dir == first_dir {
+                    vertices.remove(0);
+                }
+
+                outlines.push(LevelOutline(
+                    vertices
+                        .into_iter()
+                        .map(|v| (v - self.bounds.min).as_uvec2())
+                        .collect(),
+                ));
+            }
+        }
+
+        outlines
+    }
+
     /// Returns the index of `grid` within `collision_grid`. Returns `None` if the coordinate is
     /// out of bounds.
     fn linearize(&self, grid: IVec2) -> Option<usize> {
@@ -0,0 +1,117 @@
+use std::{
+    hash::{BuildHasher, Hash, Hasher},
+    sync::{Arc, Mutex, Weak},
+};
+
+use bevy::{
+    asset::StrongHandle,
+    platform::{collections::HashMap, hash::FixedHasher},
+    prelude::*,
+    render::render_resource::TextureFormat,
+    tasks::AsyncComputeTaskPool,
+};
+
+use crate::assets::level::{BuildTilemapError, tileset_image::TilesetImageBuilder};
+
+/// Tileset images that have already been built, so that levels using the same LDtk tileset share
+/// one image instead of each building their own.
+///
+/// Shared tilesets hold every tile of their source image, so LDtk tile ids index them directly.
+/// Like the frames of tile animations, tiles are found assuming the source has no padding or
+/// spacing.
+///
+/// Entries don't keep their tilesets alive, so a tileset is freed along with the last level using
+/// it and rebuilt if it's needed again.
+#[derive(Resource, Clone)]
+pub struct TilesetCache {
+    asset_server: AssetServer,
+    tilesets: Arc<Mutex<HashMap<TilesetKey, CachedTileset>>>,
+}
+
+impl FromWorld for TilesetCache {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            asset_server: world.resource::<AssetServer>().clone(),
+            tilesets: default(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct TilesetKey {
+    path: String,
+    tile_size: UVec2,
+    format: TextureFormat,
+}
+
+struct CachedTileset {
+    /// A hash of the source image's pixels, so that tilesets are rebuilt when it changes.
+    source_hash: u64,
+    image: Weak<StrongHandle>,
+}
+
+impl TilesetCache {
+    /// Returns the tileset built from the source image at `path`, building it first if no level
+    /// is using it yet.
+    ///
+    /// Levels loading at the same time may each build the same tileset, in which case the last
+    /// one built is kept.
+    pub async fn get_or_build(
+        &self,
+        path: &str,
+        tile_size: UVec2,
+        source_image: Image,
+    ) -> Result<Handle<Image>, BuildTilemapError> {
+        let key = TilesetKey {
+            path: path.to_string(),
+            tile_size,
+            format: source_image.texture_descriptor.format,
+        };
+        let source_hash = hash_pixels(&source_image);
+
+        if let Some(cached) = self.tilesets.lock().unwrap().get(&key)
+            && cached.source_hash == source_hash
+            && let Some(image) = cached.image.upgrade()
+        {
+            return Ok(Handle::Strong(image));
+        }
+
+        // Copying is the slow part for big tilesets, so it's done on the compute pool to leave the
+        // IO threads free for other loads.
+        let mut tileset_builder = TilesetImageBuilder::new(tile_size, key.format)?;
+        let tile_offsets = tile_offsets(source_image.size(), tile_size);
+        let tileset_builder = AsyncComputeTaskPool::get()
+            .spawn(async move {
+                tileset_builder
+                    .add_tiles(&source_image, tile_offsets)
+                    .await
+                    .map(|()| tileset_builder)
+            })
+            .await?;
+
+        let handle = self.asset_server.add(tileset_builder.build());
+        if let Handle::Strong(image) = &handle {
+            self.tilesets.lock().unwrap().insert(
+                key,
+                CachedTileset {
+                    source_hash,
+                    image: Arc::downgrade(image),
+                },
+            );
+        }
+        Ok(handle)
+    }
+}
+
+/// The offset of every whole tile in a source image of the given size, in LDtk tile id order.
+fn tile_offsets(image_size: UVec2, tile_size: UVec2) -> impl Iterator<Item = UVec2> + use<> {
+    let tiles = image_size / tile_size;
+    (0..tiles.y).flat_map(move |y| (0..tiles.x).map(move |x| UVec2::new(x, y) * tile_size))
+}
+
+fn hash_pixels(image: &Image) -> u64 {
+    let mut hasher = FixedHasher.build_hasher();
+    image.size().hash(&mut hasher);
+    image.data.hash(&mut hasher);
+    hasher.finish()
+}
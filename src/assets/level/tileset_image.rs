@@ -4,9 +4,13 @@ use bevy::{
     math::USizeVec2,
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::futures_lite::future,
 };
 use thiserror::Error;
 
+/// How many tiles [`TilesetImageBuilder::add_tiles`] copies between yields.
+const TILES_PER_YIELD: usize = 64;
+
 /// Used to build a 2d layered tileset [`Image`] from one or more source images.
 pub struct TilesetImageBuilder {
     tile_size: USizeVec2,
@@ -69,6 +73,28 @@ impl TilesetImageBuilder {
         Ok(self.next_tile_id())
     }
 
+    /// Copies each tile at the given pixel offsets, in order, as though by [`add_tile`]. The
+    /// first tile gets the next id in the tileset, and the rest follow on from it.
+    ///
+    /// Yields to the executor every [`TILES_PER_YIELD`] tiles, so large tilesets don't stall other
+    /// tasks sharing the thread.
+    ///
+    /// [`add_tile`]: Self::add_tile
+    pub async fn add_tiles(
+        &mut self,
+        source_image: &Image,
+        source_offsets: impl IntoIterator<Item = UVec2>,
+    ) -> Result<(), AddTilesError> {
+        for (i, offset) in source_offsets.into_iter().enumerate() {
+            if i > 0 && i.is_multiple_of(TILES_PER_YIELD) {
+                future::yield_now().await;
+            }
+            self.add_tile(source_image, offset)
+                .map_err(|error| AddTilesError { offset, error })?;
+        }
+        Ok(())
+    }
+
     /// Returns the final tileset [`Image`].
     pub fn build(mut self) -> Image {
         info!("Built tileset with {} tiles", self.tiles);
@@ -110,6 +136,15 @@ impl TilesetImageBuilder {
 #[error("source image format {0:?} is unsupported")]
 pub struct UnsupportedFormatError(pub TextureFormat);
 
+/// Returned by [`TilesetImageBuilder::add_tiles`] when one of the tiles couldn't be copied.
+#[derive(Debug, Error)]
+#[error("failed to copy tile from source offset {offset:?}: {error}")]
+pub struct AddTilesError {
+    pub offset: UVec2,
+    #[source]
+    pub error: AddTileError,
+}
+
 /// Errors returned by [`TilesetImageBuilder::add_tile`].
 #[derive(Debug, Error)]
 pub enum AddTileError {
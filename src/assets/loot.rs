@@ -0,0 +1,96 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Named loot tables, loaded from a JSON map of labels to [`LootTable`]s.
+#[derive(Asset, Reflect, Deserialize, Deref, Debug)]
+pub struct LootTables(HashMap<String, LootTable>);
+
+/// What something drops when it's destroyed.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct LootTable {
+    /// How many times to pick from the entries.
+    #[serde(default = "one")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+/// One possible outcome of a [`LootTable`] roll.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct LootEntry {
+    /// The dropped item, or `None` for a roll that drops nothing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+    /// How likely this entry is relative to the others.
+    #[serde(default = "one")]
+    pub weight: u32,
+    #[serde(default = "one")]
+    pub min: u32,
+    #[serde(default = "one")]
+    pub max: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl LootTable {
+    /// Rolls the table, returning each dropped item and how many of it dropped.
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(&str, u32)> {
+        let total = self.entries.iter().map(|entry| entry.weight).sum::<u32>();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let mut drops = Vec::new();
+        for _ in 0..self.rolls {
+            let mut pick = rng.random_range(0..total);
+            let Some(entry) = self.entries.iter().find(|entry| {
+                if pick < entry.weight {
+                    true
+                } else {
+                    pick -= entry.weight;
+                    false
+                }
+            }) else {
+                continue;
+            };
+
+            if let Some(item) = &entry.item {
+                let count = rng.random_range(entry.min..=entry.max.max(entry.min));
+                if count > 0 {
+                    drops.push((item.as_str(), count));
+                }
+            }
+        }
+        drops
+    }
+}
+
+#[derive(TypePath, Default)]
+pub struct LootTablesLoader;
+
+impl AssetLoader for LootTablesLoader {
+    type Asset = LootTables;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["loot.json"]
+    }
+}
@@ -1,14 +1,32 @@
 use bevy::prelude::*;
 
+pub mod aseprite;
+pub mod boss;
 pub mod enemy;
 pub mod level;
+pub mod loot;
 pub mod serialize;
+pub mod world;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_asset::<level::Level>()
-        .init_asset_loader::<level::LevelLoader>();
+        .init_resource::<level::TilesetCache>()
+        .init_asset_loader::<level::LevelLoader>()
+        .init_resource::<level::LdtkEntityRegistry>();
+
+    app.init_asset::<world::LdtkWorld>()
+        .init_asset_loader::<world::LdtkWorldLoader>();
 
     app.init_asset::<enemy::Enemy>()
         .init_asset::<enemy::EnemyManifest>()
         .init_asset_loader::<enemy::EnemyManifestLoader>();
+
+    app.init_asset::<aseprite::AsepriteSheet>()
+        .init_asset_loader::<aseprite::AsepriteLoader>();
+
+    app.init_asset::<boss::Boss>()
+        .init_asset_loader::<boss::BossLoader>();
+
+    app.init_asset::<loot::LootTables>()
+        .init_asset_loader::<loot::LootTablesLoader>();
 }
@@ -0,0 +1,79 @@
+//! The JSON data Aseprite exports alongside a sprite sheet, with `--data` or the "Output" tab of
+//! the export dialog. Only the parts the game uses are read.
+
+use bevy::platform::collections::HashMap;
+use serde::{Deserialize, de::IgnoredAny};
+
+#[derive(Deserialize, Debug)]
+pub struct AsepriteSheet {
+    pub frames: AsepriteFrames,
+    pub meta: AsepriteMeta,
+}
+
+/// The frames of the sprite. Aseprite exports these as an array or as a map keyed by file name,
+/// but the map's order is lost when it's read, so only arrays can be loaded.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum AsepriteFrames {
+    Array(Vec<AsepriteFrame>),
+    /// Only recognized so it can be rejected with a clear error.
+    Hash(#[allow(dead_code)] HashMap<String, IgnoredAny>),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AsepriteFrame {
+    /// Where the frame is in the sheet.
+    pub frame: AsepriteRect,
+    /// How long the frame is shown for, in milliseconds.
+    pub duration: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AsepriteRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AsepriteMeta {
+    /// The sheet image, relative to the JSON file.
+    pub image: String,
+    pub size: AsepriteSize,
+    #[serde(default)]
+    pub frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AsepriteSize {
+    pub w: u32,
+    pub h: u32,
+}
+
+/// A named range of frames, which becomes an animation.
+#[derive(Deserialize, Debug)]
+pub struct AsepriteTag {
+    pub name: String,
+    /// The first frame of the tag.
+    pub from: usize,
+    /// The last frame of the tag, inclusive.
+    pub to: usize,
+    #[serde(default)]
+    pub direction: AsepriteDirection,
+    /// How many times the tag plays before stopping, as a string. Tags that repeat forever leave
+    /// this out.
+    #[serde(default)]
+    pub repeat: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AsepriteDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
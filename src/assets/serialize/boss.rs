@@ -0,0 +1,27 @@
+use bevy::asset::AssetPath;
+use serde::Deserialize;
+
+/// A boss as written on disk.
+#[derive(Deserialize, Clone)]
+pub struct Boss {
+    pub name: String,
+    pub max_health: f32,
+    /// The phases of the fight. The order doesn't matter, they're sorted by threshold on load.
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BossPhase {
+    /// The fraction of max health at or below which this phase starts. The first phase should
+    /// leave this at `1.0`.
+    #[serde(default = "BossPhase::default_health")]
+    pub health: f32,
+    /// The enemy definition used for this phase, like `enemies.json#mushroom_head`.
+    pub enemy: AssetPath<'static>,
+}
+
+impl BossPhase {
+    fn default_health() -> f32 {
+        1.0
+    }
+}
@@ -3,16 +3,203 @@ use bevy::{
     asset::AssetPath,
     math::{UVec2, Vec2},
     platform::collections::HashMap,
+    reflect::Reflect,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
 
-#[derive(Serialize, Deserialize)]
-#[serde(transparent)]
+use crate::{animation::FrameBoxesDef, assets::loot::LootTable};
+
+/// The newest manifest version understood by the loader.
+///
+/// - Version 1 is a bare map of labels to complete enemy definitions.
+/// - Version 2 adds `defaults`, named `templates`, and per-enemy `extends` inheritance.
+pub const ENEMY_MANIFEST_VERSION: u32 = 2;
+
+/// An enemy manifest as written on disk.
+///
+/// Enemy definitions are stored unresolved so that they can be written back without flattening
+/// their inheritance. Use [`EnemyManifest::resolve`] to get a complete [`Enemy`].
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct EnemyManifest {
-    pub enemies: HashMap<String, Enemy>,
+    pub version: u32,
+    /// Fields applied to every enemy before any templates.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub defaults: Map<String, Value>,
+    /// Partial definitions that enemies can extend, but which are not enemies themselves.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub templates: HashMap<String, EnemyDefinition>,
+    pub enemies: HashMap<String, EnemyDefinition>,
+}
+
+/// A possibly partial enemy definition.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct EnemyDefinition {
+    /// The label of a template or enemy whose fields this definition inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Error)]
+pub enum EnemyManifestError {
+    #[error("unsupported enemy manifest version {0} (newest is {ENEMY_MANIFEST_VERSION})")]
+    UnsupportedVersion(u64),
+    #[error("no enemy or template named {0:?}")]
+    UnknownDefinition(String),
+    #[error("{0:?} inherits from itself")]
+    InheritanceCycle(String),
+    #[error("invalid definition for {label:?}: {error}")]
+    InvalidDefinition {
+        label: String,
+        #[source]
+        error: serde_json::Error,
+    },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl EnemyManifest {
+    /// Parses a manifest of any supported version.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, EnemyManifestError> {
+        let value: Value = serde_json::from_slice(bytes)?;
+        match value.get("version").map(Value::as_u64) {
+            // Version 1 manifests have no version field.
+            None => Ok(Self {
+                version: ENEMY_MANIFEST_VERSION,
+                defaults: Map::new(),
+                templates: HashMap::new(),
+                enemies: serde_json::from_value(value)?,
+            }),
+            Some(Some(version)) if version <= ENEMY_MANIFEST_VERSION as u64 => {
+                Ok(serde_json::from_value(value)?)
+            }
+            Some(version) => Err(EnemyManifestError::UnsupportedVersion(
+                version.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Resolves the complete definition of the enemy with the given label.
+    pub fn resolve(&self, label: &str) -> Result<Enemy, EnemyManifestError> {
+        let value = self.resolve_value(label)?;
+        serde_json::from_value(value).map_err(|error| EnemyManifestError::InvalidDefinition {
+            label: label.to_string(),
+            error,
+        })
+    }
+
+    /// Replaces the definition of the enemy with the given label, storing only the fields that
+    /// differ from what it inherits.
+    pub fn set_resolved(&mut self, label: &str, enemy: &Enemy) -> Result<(), EnemyManifestError> {
+        let extends = self
+            .enemies
+            .get(label)
+            .ok_or_else(|| EnemyManifestError::UnknownDefinition(label.to_string()))?
+            .extends
+            .clone();
+
+        let inherited = match &extends {
+            Some(parent) => self.resolve_value(parent)?,
+            None => Value::Object(self.defaults.clone()),
+        };
+
+        let fields = match json_diff(&inherited, &serde_json::to_value(enemy)?) {
+            Some(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+
+        self.enemies
+            .insert(label.to_string(), EnemyDefinition { extends, fields });
+        Ok(())
+    }
+
+    fn get_definition(&self, label: &str) -> Option<&EnemyDefinition> {
+        self.enemies
+            .get(label)
+            .or_else(|| self.templates.get(label))
+    }
+
+    /// Merges the defaults and every definition in the inheritance chain of `label`.
+    fn resolve_value(&self, label: &str) -> Result<Value, EnemyManifestError> {
+        let mut chain = Vec::new();
+        let mut next = Some(label);
+        while let Some(label) = next {
+            if chain.iter().any(|(l, _)| *l == label) {
+                return Err(EnemyManifestError::InheritanceCycle(label.to_string()));
+            }
+
+            let def = self
+                .get_definition(label)
+                .ok_or_else(|| EnemyManifestError::UnknownDefinition(label.to_string()))?;
+            chain.push((label, def));
+            next = def.extends.as_deref();
+        }
+
+        let mut value = Value::Object(self.defaults.clone());
+        for (_, def) in chain.into_iter().rev() {
+            json_merge(&mut value, Value::Object(def.fields.clone()));
+        }
+        Ok(value)
+    }
+}
+
+/// Recursively merges `overrides` into `base`, like a JSON merge patch (RFC 7386). Objects are
+/// merged key by key, a `null` removes the key from `base`, and any other value replaces the base
+/// value entirely.
+fn json_merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                if value.is_null() {
+                    base.remove(&key);
+                } else {
+                    json_merge(base.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        // An object merged over anything else starts from an empty object, so that any `null`s in
+        // it are dropped rather than kept as values.
+        (base, overrides @ Value::Object(_)) => {
+            *base = Value::Object(Map::new());
+            json_merge(base, overrides);
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Returns the minimal value that, when merged into `base` with [`json_merge`], produces `value`.
+/// Keys of `base` that are missing from `value` are removed with a `null`.
+///
+/// Returns `None` if the two values are already equal.
+fn json_diff(base: &Value, value: &Value) -> Option<Value> {
+    match (base, value) {
+        (Value::Object(base), Value::Object(value)) => {
+            let changed = value.iter().filter_map(|(key, v)| match base.get(key) {
+                Some(b) => json_diff(b, v).map(|d| (key.clone(), d)),
+                None => Some((key.clone(), v.clone())),
+            });
+            let removed = base
+                .keys()
+                .filter(|key| !value.contains_key(*key))
+                .map(|key| (key.clone(), Value::Null));
+            let diff = changed.chain(removed).collect::<Map<_, _>>();
+            (!diff.is_empty()).then_some(Value::Object(diff))
+        }
+        // Compare at `f32` precision, since that's what the definitions are parsed into.
+        (Value::Number(b), Value::Number(v))
+            if b.as_f64().map(|n| n as f32) == v.as_f64().map(|n| n as f32) =>
+        {
+            None
+        }
+        (base, value) if base == value => None,
+        (_, value) => Some(value.clone()),
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Enemy {
     pub name: String,
     pub size: Vec2,
@@ -21,6 +208,19 @@ pub struct Enemy {
     pub atlas_animations: HashMap<String, EnemyAnimation>,
     pub collider: EnemyCollider,
     pub movement: EnemyMovement,
+    #[serde(default)]
+    pub ai: EnemyAi,
+    /// How the enemy shoots at players. Enemies without one only do contact damage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack: Option<EnemyAttack>,
+    #[serde(default, skip_serializing_if = "EnemySounds::is_empty")]
+    pub sounds: EnemySounds,
+    /// What the enemy drops when it dies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loot: Option<LootTable>,
+    /// Free-form tags that gameplay modules can use to customize spawned enemies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
@@ -30,14 +230,73 @@ pub struct EnemyAtlasLayout {
     pub size: UVec2,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct EnemyAnimation {
     pub start: usize,
     pub end: usize,
     pub frame_millis: u32,
+    /// Sounds played as the animation reaches certain frames.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<EnemyAnimationMarker>,
+    /// Hitboxes and hurtboxes of certain frames, relative to the center of the enemy's sprite.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boxes: Vec<FrameBoxesDef>,
+}
+
+/// Plays one of an enemy's [`EnemySounds`] on the given frames of an animation, counted from the
+/// animation's first frame.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct EnemyAnimationMarker {
+    pub sound: EnemySound,
+    pub frames: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemySound {
+    Step,
+    Jump,
+    Hurt,
+    Death,
+}
+
+impl EnemySound {
+    /// The animation marker that plays this sound.
+    pub fn marker(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_marker(marker: usize) -> Option<Self> {
+        [Self::Step, Self::Jump, Self::Hurt, Self::Death]
+            .into_iter()
+            .find(|sound| sound.marker() == marker)
+    }
+}
+
+/// The sounds an enemy makes. Each time one plays, a random clip is picked from its list.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EnemySounds {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub step: Vec<AssetPath<'static>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub jump: Vec<AssetPath<'static>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hurt: Vec<AssetPath<'static>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub death: Vec<AssetPath<'static>>,
+}
+
+impl EnemySounds {
+    pub fn is_empty(&self) -> bool {
+        self.step.is_empty()
+            && self.jump.is_empty()
+            && self.hurt.is_empty()
+            && self.death.is_empty()
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct EnemyCollider {
     #[serde(flatten)]
     pub shape: ColliderShape,
@@ -45,7 +304,7 @@ pub struct EnemyCollider {
     pub offset: Vec2,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 #[serde(tag = "shape")]
 pub enum ColliderShape {
     Rectangle { width: f32, height: f32 },
@@ -61,9 +320,11 @@ impl From<ColliderShape> for Collider {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// An enemy's movement tunables. Only the fields for its [`kind`](Self::kind) are used.
+#[derive(Serialize, Deserialize, Clone, Copy)]
 #[serde(default)]
 pub struct EnemyMovement {
+    pub kind: MovementKind,
     pub max_speed: f32,
     pub accel_ground: f32,
     pub accel_air: f32,
@@ -71,11 +332,16 @@ pub struct EnemyMovement {
     pub damping_factor_air: f32,
     pub damping_factor_ground: f32,
     pub max_slope_angle: f32,
+    pub accel_flying: f32,
+    pub hover_amplitude: f32,
+    pub hover_period_secs: f32,
+    pub dive_speed: f32,
 }
 
 impl Default for EnemyMovement {
     fn default() -> Self {
         Self {
+            kind: MovementKind::Walking,
             max_speed: 10.0,
             accel_ground: 1.0,
             accel_air: 0.1,
@@ -83,6 +349,190 @@ impl Default for EnemyMovement {
             damping_factor_air: 0.1,
             damping_factor_ground: 0.9,
             max_slope_angle: f32::to_radians(45.0),
+            accel_flying: 20.0,
+            hover_amplitude: 0.5,
+            hover_period_secs: 2.0,
+            dive_speed: 12.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementKind {
+    #[default]
+    Walking,
+    Flying,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct EnemyAi {
+    pub aggro_range: f32,
+    pub attack_range: f32,
+    pub give_up_secs: f32,
+    pub flee_health: f32,
+}
+
+impl Default for EnemyAi {
+    fn default() -> Self {
+        Self {
+            aggro_range: 12.0,
+            attack_range: 8.0,
+            give_up_secs: 3.0,
+            flee_health: 0.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EnemyAttack {
+    /// The projectile's image. Without one, projectiles are plain squares.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projectile: Option<AssetPath<'static>>,
+    pub speed: f32,
+    pub damage: f32,
+    pub cooldown_secs: f32,
+    /// How close a visible player needs to be to be shot at.
+    pub range: f32,
+    /// How many times each projectile can ricochet off surfaces.
+    pub bounces: u32,
+    /// How much surface hardness each projectile can go through.
+    pub penetration: f32,
+}
+
+impl Default for EnemyAttack {
+    fn default() -> Self {
+        Self {
+            projectile: None,
+            speed: 12.0,
+            damage: 1.0,
+            cooldown_secs: 2.0,
+            range: 10.0,
+            bounces: 0,
+            penetration: 0.0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn manifest(value: Value) -> EnemyManifest {
+        EnemyManifest::from_slice(value.to_string().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn migrates_version_1() {
+        let manifest = manifest(json!({ "slime": { "name": "Slime" } }));
+        assert_eq!(manifest.version, ENEMY_MANIFEST_VERSION);
+        assert!(manifest.defaults.is_empty());
+        assert!(manifest.templates.is_empty());
+        assert_eq!(manifest.enemies["slime"].fields["name"], json!("Slime"));
+        assert_eq!(manifest.enemies["slime"].extends, None);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let newer = json!({ "version": ENEMY_MANIFEST_VERSION + 1, "enemies": {} });
+        assert!(matches!(
+            EnemyManifest::from_slice(newer.to_string().as_bytes()),
+            Err(EnemyManifestError::UnsupportedVersion(v)) if v == ENEMY_MANIFEST_VERSION as u64 + 1
+        ));
+
+        let invalid = json!({ "version": "2", "enemies": {} });
+        assert!(matches!(
+            EnemyManifest::from_slice(invalid.to_string().as_bytes()),
+            Err(EnemyManifestError::UnsupportedVersion(0))
+        ));
+    }
+
+    #[test]
+    fn resolves_inheritance_in_order() {
+        let manifest = manifest(json!({
+            "version": 2,
+            "defaults": { "a": 0, "b": 0, "c": 0 },
+            "templates": { "base": { "b": 1, "c": 1 } },
+            "enemies": { "slime": { "extends": "base", "c": 2 } },
+        }));
+        assert_eq!(
+            manifest.resolve_value("slime").unwrap(),
+            json!({ "a": 0, "b": 1, "c": 2 })
+        );
+    }
+
+    #[test]
+    fn detects_inheritance_cycles() {
+        let manifest = manifest(json!({
+            "version": 2,
+            "templates": {
+                "a": { "extends": "b" },
+                "b": { "extends": "a" },
+                "self": { "extends": "self" },
+            },
+            "enemies": { "slime": { "extends": "a" } },
+        }));
+        assert!(matches!(
+            manifest.resolve_value("slime"),
+            Err(EnemyManifestError::InheritanceCycle(label)) if label == "a"
+        ));
+        assert!(matches!(
+            manifest.resolve_value("self"),
+            Err(EnemyManifestError::InheritanceCycle(label)) if label == "self"
+        ));
+        assert!(matches!(
+            manifest.resolve_value("missing"),
+            Err(EnemyManifestError::UnknownDefinition(label)) if label == "missing"
+        ));
+    }
+
+    #[test]
+    fn merge_is_recursive() {
+        let mut base = json!({ "a": { "x": 1, "y": 2 }, "b": [1, 2], "c": 3 });
+        json_merge(
+            &mut base,
+            json!({ "a": { "y": 3 }, "b": [4], "d": { "e": null } }),
+        );
+        assert_eq!(
+            base,
+            json!({ "a": { "x": 1, "y": 3 }, "b": [4], "c": 3, "d": {} })
+        );
+    }
+
+    #[test]
+    fn merge_removes_nulls() {
+        let mut base = json!({ "a": { "x": 1, "y": 2 }, "b": 1 });
+        json_merge(
+            &mut base,
+            json!({ "a": { "x": null }, "b": null, "c": null }),
+        );
+        assert_eq!(base, json!({ "a": { "y": 2 } }));
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        let base = json!({ "a": { "x": 1, "y": 2 }, "b": [1, 2], "c": 0.1 });
+        let value = json!({ "a": { "x": 1, "y": 3 }, "b": [1, 2], "c": 0.1f32, "d": true });
+        assert_eq!(
+            json_diff(&base, &value),
+            Some(json!({ "a": { "y": 3 }, "d": true }))
+        );
+        assert_eq!(json_diff(&base, &base), None);
+    }
+
+    #[test]
+    fn diff_removes_missing_keys() {
+        let base = json!({ "a": { "x": 1, "y": 2 }, "b": 1 });
+        let value = json!({ "a": { "y": 2 } });
+        let diff = json_diff(&base, &value).unwrap();
+        assert_eq!(diff, json!({ "a": { "x": null }, "b": null }));
+
+        let mut merged = base;
+        json_merge(&mut merged, diff);
+        assert_eq!(merged, value);
+    }
+}
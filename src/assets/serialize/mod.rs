@@ -1,2 +1,4 @@
+pub mod aseprite;
+pub mod boss;
 pub mod enemy;
 pub mod ldtk;
@@ -0,0 +1,151 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::assets::{
+    level::{Level, TilesetCache, build_level},
+    serialize::ldtk::{LdtkJson, Level as LdtkLevel},
+};
+
+/// Every level in an LDtk project, loaded from a `.ldtk` world file.
+///
+/// Each level is also available as a labeled sub-asset named after its identifier, so a single
+/// level can be loaded with a path like `test.ldtk#Level_0`.
+#[derive(Asset, Reflect)]
+pub struct LdtkWorld {
+    pub levels: Vec<WorldLevel>,
+}
+
+impl LdtkWorld {
+    /// Returns the levels beside the given one, skipping those above or below it in depth.
+    pub fn neighbours(&self, level: AssetId<Level>) -> impl Iterator<Item = &WorldLevel> {
+        self.levels
+            .iter()
+            .filter(move |world_level| world_level.level.id() == level)
+            .flat_map(|world_level| &world_level.neighbours)
+            .filter(|neighbour| !matches!(neighbour.dir.as_str(), "<" | ">"))
+            .filter_map(|neighbour| {
+                self.levels
+                    .iter()
+                    .find(|world_level| world_level.identifier == neighbour.identifier)
+            })
+    }
+}
+
+/// A level's place in the world.
+#[derive(Reflect)]
+pub struct WorldLevel {
+    pub identifier: String,
+    /// The position of the level's top-left corner in LDtk pixels, with y up.
+    pub world_position: IVec2,
+    /// The size of the level in LDtk pixels.
+    pub size: UVec2,
+    pub neighbours: Vec<LevelNeighbour>,
+    pub level: Handle<Level>,
+}
+
+/// A level touching or overlapping another one.
+#[derive(Reflect)]
+pub struct LevelNeighbour {
+    /// The neighbour's identifier.
+    pub identifier: String,
+    /// Where the neighbour lies: `n`, `s`, `e`, `w`, a corner like `ne`, `<` or `>` for a
+    /// different depth, or `o` for an overlap.
+    pub dir: String,
+}
+
+#[derive(TypePath)]
+pub struct LdtkWorldLoader {
+    tilesets: TilesetCache,
+}
+
+impl FromWorld for LdtkWorldLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            tilesets: world.get_resource_or_init::<TilesetCache>().clone(),
+        }
+    }
+}
+
+impl AssetLoader for LdtkWorldLoader {
+    type Asset = LdtkWorld;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        &(): &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let ldtk: LdtkJson = serde_json::from_slice(&bytes)?;
+
+        // Single-world projects keep their levels at the root, multi-world projects in `worlds`
+        let ldtk_levels: Vec<LdtkLevel> = ldtk
+            .levels
+            .into_iter()
+            .chain(ldtk.worlds.into_iter().flat_map(|world| world.levels))
+            .collect();
+
+        let identifiers: HashMap<_, _> = ldtk_levels
+            .iter()
+            .map(|level| (level.iid.clone(), level.identifier.clone()))
+            .collect();
+
+        let mut levels = Vec::with_capacity(ldtk_levels.len());
+        for ldtk_level in ldtk_levels {
+            let neighbours = ldtk_level
+                .neighbours
+                .iter()
+                .filter_map(|neighbour| {
+                    Some(LevelNeighbour {
+                        identifier: identifiers.get(&neighbour.level_iid)?.clone(),
+                        dir: neighbour.dir.clone(),
+                    })
+                })
+                .collect();
+
+            levels.push(WorldLevel {
+                identifier: ldtk_level.identifier.clone(),
+                world_position: IVec2::new(ldtk_level.world_x as _, -ldtk_level.world_y as _),
+                size: UVec2::new(ldtk_level.px_wid as _, ldtk_level.px_hei as _),
+                neighbours,
+                level: load_world_level(load_context, &self.tilesets, ldtk_level).await?,
+            });
+        }
+
+        Ok(LdtkWorld { levels })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtk"]
+    }
+}
+
+/// Adds a level as a labeled sub-asset, reading its `.ldtkl` file first if it was saved
+/// separately from the world.
+async fn load_world_level(
+    load_context: &mut LoadContext<'_>,
+    tilesets: &TilesetCache,
+    ldtk_level: LdtkLevel,
+) -> Result<Handle<Level>> {
+    let ldtk_level = match (&ldtk_level.layer_instances, &ldtk_level.external_rel_path) {
+        (None, Some(rel_path)) => {
+            let path = load_context.path().resolve_embed(rel_path)?;
+            let bytes = load_context.read_asset_bytes(path).await?;
+            serde_json::from_slice(&bytes)?
+        }
+        _ => ldtk_level,
+    };
+
+    let label = ldtk_level.identifier.clone();
+    let mut level_context = load_context.begin_labeled_asset();
+    let level = build_level(&mut level_context, tilesets, ldtk_level).await?;
+    let loaded = level_context.finish(level);
+    Ok(load_context.add_loaded_labeled_asset(label, loaded))
+}
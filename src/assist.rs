@@ -0,0 +1,107 @@
+//! Assist options that make the game easier, set from the settings menu.
+//!
+//! Each option works through the system it affects: players get a [`DamageTakenMultiplier`] and
+//! [`InfiniteJumps`], the virtual clock is slowed down, and the pause menu gets a button that
+//! triggers [`SkipLevel`]. A run played with any of them on is flagged as [`assisted`].
+//!
+//! [`assisted`]: CurrentRun::assisted
+//! [`SkipLevel`]: crate::gameplay::level::exit::SkipLevel
+
+use bevy::prelude::*;
+
+use crate::{
+    controller::InfiniteJumps,
+    gameplay::{
+        combat::DamageTakenMultiplier,
+        level::hub::CurrentRun,
+        player::{Player, death::DeathSequence},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AssistSettings>()
+        .add_observer(assist_new_player)
+        .add_systems(
+            Update,
+            (
+                (assist_players, apply_assist_time_scale)
+                    .run_if(resource_changed::<AssistSettings>),
+                flag_assisted_run.run_if(resource_exists::<CurrentRun>),
+            ),
+        );
+}
+
+pub const MIN_DAMAGE_MULTIPLIER: f32 = 0.0;
+pub const MIN_TIME_SCALE: f32 = 0.5;
+
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct AssistSettings {
+    /// Scales the damage players take, from [`MIN_DAMAGE_MULTIPLIER`] to `1.0`.
+    pub damage_multiplier: f32,
+    pub infinite_jumps: bool,
+    /// The speed of the game clock, from [`MIN_TIME_SCALE`] to `1.0`.
+    pub time_scale: f32,
+    /// Whether the pause menu has a button that triggers
+    /// [`SkipLevel`](crate::gameplay::level::exit::SkipLevel).
+    pub skip_level: bool,
+}
+
+impl Default for AssistSettings {
+    fn default() -> Self {
+        Self {
+            damage_multiplier: 1.0,
+            infinite_jumps: false,
+            time_scale: 1.0,
+            skip_level: false,
+        }
+    }
+}
+
+impl AssistSettings {
+    /// Whether any option that changes how the game plays is on. The skip level option only
+    /// flags a run once a level is actually skipped.
+    pub fn is_active(&self) -> bool {
+        self.damage_multiplier < 1.0 || self.infinite_jumps || self.time_scale < 1.0
+    }
+}
+
+fn assist_player(assist: &AssistSettings, player: &mut EntityCommands) {
+    player.insert(DamageTakenMultiplier(assist.damage_multiplier));
+    if assist.infinite_jumps {
+        player.insert(InfiniteJumps);
+    } else {
+        player.remove::<InfiniteJumps>();
+    }
+}
+
+fn assist_new_player(add: On<Add, Player>, assist: Res<AssistSettings>, mut commands: Commands) {
+    assist_player(&assist, &mut commands.entity(add.entity));
+}
+
+fn assist_players(
+    assist: Res<AssistSettings>,
+    players: Query<Entity, With<Player>>,
+    mut commands: Commands,
+) {
+    for player in &players {
+        assist_player(&assist, &mut commands.entity(player));
+    }
+}
+
+fn apply_assist_time_scale(
+    assist: Res<AssistSettings>,
+    dying: Query<(), With<DeathSequence>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    // The death sequence sets its own speed, and restores this one when it's done.
+    if dying.is_empty() {
+        time.set_relative_speed(assist.time_scale);
+    }
+}
+
+fn flag_assisted_run(assist: Res<AssistSettings>, mut run: ResMut<CurrentRun>) {
+    if assist.is_active() && !run.assisted {
+        run.assisted = true;
+    }
+}
@@ -1,12 +1,136 @@
-use bevy::{audio::Volume, prelude::*};
+//! Music, sound effects, and the relativistic pitch shifts applied to them.
+//!
+//! Sounds played by an entity are linked to it with [`EmittedBy`], which collects them in the
+//! entity's [`AudioEmitter`]. Like its sprite, what the player hears from an emitter is Doppler
+//! shifted by its radial velocity and slowed by its Lorentz factor. Music is slowed by the
+//! player's own Lorentz factor relative to the level, as though the world's clocks run slow.
+//!
+//! Music can be layered: stems played in step with the main track fade in one after another as
+//! the [`MusicIntensity`] rises, so the calmest mix plays at rest and the fullest near `c`.
+//!
+//! Sounds with a [`PositionalSound`] are played from a point in the world. They're attenuated by
+//! their distance from the [`SoundListener`], and panned by how far they are to its side.
+//!
+//! Sound effects are played through the [`SoundPool`], which drops sounds that would stack too
+//! many copies of the same clip or that come too soon after the last one.
+
+use avian2d::prelude::LinearVelocity;
+use std::time::Duration;
+
+use bevy::{
+    audio::{AudioSinkPlayback, Volume},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    gameplay::player::Player,
+    physics::{EffectsIntensity, LocalSpeedOfLight, MusicIntensity, beta, gamma, rel_add},
+};
+
+/// How far positional sounds can be from the listener and still play at full volume, in meters.
+const POSITIONAL_INNER_RADIUS: f32 = 4.0;
+/// How far positional sounds can be heard from, in meters.
+const POSITIONAL_MAX_DISTANCE: f32 = 24.0;
+/// How far to the listener's side a sound has to be to only play from that side, in meters.
+const LISTENER_PAN_WIDTH: f32 = 12.0;
+/// How long switching music tracks takes by default.
+const MUSIC_FADE_SECS: f32 = 1.5;
+/// How many copies of the same sound effect can play at once by default.
+const MAX_CONCURRENT_SOUNDS: usize = 4;
+/// How soon the same sound effect can be played again by default.
+const SOUND_COOLDOWN_SECS: f32 = 0.05;
+/// The range sounds are pitch shifted within, so they stay audible.
+const PITCH_RANGE: (f32, f32) = (0.25, 4.0);
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MusicController>()
+        .add_observer(play_music)
+        .add_observer(stop_music);
+
+    app.init_resource::<SoundPool>()
+        .add_observer(admit_pooled_sound)
+        .add_observer(release_pooled_sound);
+
     app.add_systems(
         Update,
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+        (
+            fade_music,
+            mix_music_layers,
+            apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+        )
+            .chain(),
+    );
+    app.add_systems(Update, (start_synced_music, apply_relativistic_pitch));
+    app.add_systems(
+        PostUpdate,
+        update_positional_sounds.after(TransformSystems::Propagate),
     );
 }
 
+/// Where [`PositionalSound`]s are heard from. There should only be one, on the
+/// [`PlayerCamera`](crate::gameplay::camera::PlayerCamera).
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct SoundListener {
+    /// How far to the side a sound has to be to only play from that side, in meters.
+    pub pan_width: f32,
+}
+
+impl Default for SoundListener {
+    fn default() -> Self {
+        Self {
+            pan_width: LISTENER_PAN_WIDTH,
+        }
+    }
+}
+
+/// A sound played from its position in the world, relative to the [`SoundListener`].
+///
+/// Needs a spatial [`PlaybackSettings`] so that it can be panned.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct PositionalSound {
+    /// How far the sound can be from the listener and still play at full volume, in meters.
+    pub inner_radius: f32,
+    /// How far the sound can be heard from, in meters.
+    pub max_distance: f32,
+}
+
+impl Default for PositionalSound {
+    fn default() -> Self {
+        Self {
+            inner_radius: POSITIONAL_INNER_RADIUS,
+            max_distance: POSITIONAL_MAX_DISTANCE,
+        }
+    }
+}
+
+impl PositionalSound {
+    /// How loud the sound is at `distance` from the listener, from 0 to 1.
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        let range = (self.max_distance - self.inner_radius).max(f32::EPSILON);
+        ((self.max_distance - distance) / range)
+            .clamp(0.0, 1.0)
+            .powi(2)
+    }
+}
+
+/// Links a playing sound to the entity it comes from.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship(relationship_target = AudioEmitter)]
+pub struct EmittedBy(pub Entity);
+
+/// The sounds an entity is playing, which are pitch shifted by its motion relative to the player.
+///
+/// Emitters need a [`LinearVelocity`] for their sounds to be shifted.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[relationship_target(relationship = EmittedBy)]
+pub struct AudioEmitter(Vec<Entity>);
+
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
 /// general "music" category (e.g. global background music, soundtrack).
 ///
@@ -27,6 +151,221 @@ pub fn music(handle: Handle<AudioSource>, volume: f32) -> impl Bundle {
     )
 }
 
+/// Crossfades between music tracks as they're requested with [`PlayMusic`] and [`StopMusic`].
+///
+/// Only one track plays at a time. Switching tracks fades the old one out while the new one fades
+/// in, and asking for the track that's already playing leaves it playing.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct MusicController {
+    /// How long switching tracks takes.
+    pub fade_secs: f32,
+    /// The entity playing the current track, if there is one.
+    current: Option<Entity>,
+}
+
+impl Default for MusicController {
+    fn default() -> Self {
+        Self {
+            fade_secs: MUSIC_FADE_SECS,
+            current: None,
+        }
+    }
+}
+
+/// Switches the music to `track`, at `volume`.
+#[derive(Event, Debug, Clone)]
+pub struct PlayMusic {
+    pub track: Handle<AudioSource>,
+    pub volume: f32,
+    /// Stems played in step with `track`, which fade in one after another as the
+    /// [`MusicIntensity`] rises. Each gets an even share of the intensity range, in order.
+    pub layers: Vec<Handle<AudioSource>>,
+}
+
+/// Fades the music out.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StopMusic;
+
+/// Moves a music track's volume towards `target`, and despawns it once it's faded out.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MusicFade {
+    volume: f32,
+    target: f32,
+    /// How fast the volume changes, per second.
+    rate: f32,
+}
+
+/// A stem of a layered music track, played as a child of the track. It's silent below `start`
+/// [`MusicIntensity`], and at the track's full volume above `end`.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MusicLayer {
+    start: f32,
+    end: f32,
+}
+
+impl MusicLayer {
+    fn gain(&self, intensity: f32) -> f32 {
+        ((intensity - self.start) / (self.end - self.start)).clamp(0.0, 1.0)
+    }
+}
+
+/// A layered music track that's waiting for all of its stems to load, so that they can start
+/// together.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MusicSync;
+
+impl MusicFade {
+    fn to(&mut self, target: f32, secs: f32) {
+        self.target = target;
+        self.rate = (target - self.volume).abs() / secs.max(f32::EPSILON);
+    }
+}
+
+fn play_music(
+    play: On<PlayMusic>,
+    mut controller: ResMut<MusicController>,
+    mut tracks: Query<(&AudioPlayer, &mut MusicFade)>,
+    mut commands: Commands,
+) {
+    let fade_secs = controller.fade_secs;
+    if let Some((player, mut fade)) = controller.current.and_then(|e| tracks.get_mut(e).ok()) {
+        if player.0 == play.track {
+            fade.to(play.volume, fade_secs);
+            return;
+        }
+        fade.to(0.0, fade_secs);
+    }
+
+    let mut fade = MusicFade {
+        volume: 0.0,
+        target: 0.0,
+        rate: 0.0,
+    };
+    fade.to(play.volume, fade_secs);
+    let entity = commands
+        .spawn((Name::new("Music"), music(play.track.clone(), 0.0), fade))
+        .id();
+    controller.current = Some(entity);
+
+    if play.layers.is_empty() {
+        return;
+    }
+    commands
+        .entity(entity)
+        .insert(MusicSync)
+        .entry::<PlaybackSettings>()
+        .and_modify(pause);
+    let share = 1.0 / play.layers.len() as f32;
+    for (i, stem) in play.layers.iter().enumerate() {
+        commands
+            .spawn((
+                Name::new("Music Layer"),
+                music(stem.clone(), 0.0),
+                MusicLayer {
+                    start: i as f32 * share,
+                    end: (i + 1) as f32 * share,
+                },
+                ChildOf(entity),
+            ))
+            .entry::<PlaybackSettings>()
+            .and_modify(pause);
+    }
+}
+
+/// Keeps music from playing until [`start_synced_music`] starts it.
+fn pause(mut playback: Mut<PlaybackSettings>) {
+    playback.paused = true;
+}
+
+/// Starts layered tracks once they and all of their stems have loaded.
+fn start_synced_music(
+    tracks: Query<(Entity, &AudioSink, &Children), With<MusicSync>>,
+    layers: Query<&AudioSink, With<MusicLayer>>,
+    mut commands: Commands,
+) {
+    for (entity, sink, children) in &tracks {
+        if !children.iter().all(|child| layers.contains(child)) {
+            continue;
+        }
+        sink.play();
+        for layer in layers.iter_many(children) {
+            layer.play();
+        }
+        commands.entity(entity).remove::<MusicSync>();
+    }
+}
+
+/// Sets the volume of each stem from its track's volume and the [`MusicIntensity`].
+fn mix_music_layers(
+    intensity: Res<MusicIntensity>,
+    global_volume: Res<GlobalVolume>,
+    tracks: Query<&MusicFade>,
+    mut layers: Query<(
+        &MusicLayer,
+        &ChildOf,
+        &mut PlaybackSettings,
+        Option<&mut AudioSink>,
+    )>,
+) {
+    for (layer, child_of, mut playback, sink) in &mut layers {
+        let Ok(fade) = tracks.get(child_of.parent()) else {
+            continue;
+        };
+        playback.volume = Volume::Linear(fade.volume * layer.gain(intensity.0));
+        if let Some(mut sink) = sink {
+            sink.set_volume(global_volume.volume * playback.volume);
+        }
+    }
+}
+
+fn stop_music(
+    _: On<StopMusic>,
+    mut controller: ResMut<MusicController>,
+    mut tracks: Query<&mut MusicFade>,
+) {
+    let fade_secs = controller.fade_secs;
+    if let Some(mut fade) = controller
+        .current
+        .take()
+        .and_then(|e| tracks.get_mut(e).ok())
+    {
+        fade.to(0.0, fade_secs);
+    }
+}
+
+fn fade_music(
+    // Music keeps fading while the game is paused.
+    time: Res<Time<Real>>,
+    global_volume: Res<GlobalVolume>,
+    mut tracks: Query<(
+        Entity,
+        &mut MusicFade,
+        &mut PlaybackSettings,
+        Option<&mut AudioSink>,
+    )>,
+    mut commands: Commands,
+) {
+    for (entity, mut fade, mut playback, sink) in &mut tracks {
+        if fade.volume == fade.target {
+            continue;
+        }
+        let step = fade.rate * time.delta_secs();
+        fade.volume += (fade.target - fade.volume).clamp(-step, step);
+        playback.volume = Volume::Linear(fade.volume);
+        if let Some(mut sink) = sink {
+            sink.set_volume(global_volume.volume * playback.volume);
+        }
+
+        if fade.target == 0.0 && fade.volume == 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
 /// general "sound effect" category (e.g. footsteps, the sound of a magic spell, a door opening).
 ///
@@ -35,7 +374,7 @@ pub fn music(handle: Handle<AudioSource>, volume: f32) -> impl Bundle {
 #[reflect(Component)]
 pub struct SoundEffect;
 
-/// A sound effect audio instance.
+/// A sound effect audio instance, played through the [`SoundPool`].
 pub fn sound_effect(handle: Handle<AudioSource>, volume: f32) -> impl Bundle {
     (
         AudioPlayer(handle),
@@ -44,9 +383,219 @@ pub fn sound_effect(handle: Handle<AudioSource>, volume: f32) -> impl Bundle {
             ..PlaybackSettings::DESPAWN
         },
         SoundEffect,
+        PooledSound,
+    )
+}
+
+/// Marks a sound as played through the [`SoundPool`], which despawns it straight away if it's over
+/// its sound's limits.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct PooledSound;
+
+/// How often a sound effect can be played.
+#[derive(Reflect, Debug, Clone, Copy)]
+pub struct SoundLimit {
+    /// How many copies of the sound can play at once.
+    pub max_concurrent: usize,
+    /// How long after a copy starts before the next one can.
+    pub cooldown: Duration,
+}
+
+impl Default for SoundLimit {
+    fn default() -> Self {
+        Self {
+            max_concurrent: MAX_CONCURRENT_SOUNDS,
+            cooldown: Duration::from_secs_f32(SOUND_COOLDOWN_SECS),
+        }
+    }
+}
+
+/// Keeps track of the [`PooledSound`]s playing, so that the same clip doesn't stack up when
+/// many things play it at once.
+///
+/// Each clip is limited separately, by its own [`SoundLimit`] if it has one and by
+/// [`SoundPool::default_limit`] otherwise. Sounds over their limit are dropped rather than cutting
+/// off the copies already playing.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct SoundPool {
+    pub default_limit: SoundLimit,
+    limits: HashMap<AssetId<AudioSource>, SoundLimit>,
+    playing: HashMap<AssetId<AudioSource>, PoolEntry>,
+}
+
+#[derive(Reflect, Debug, Default)]
+struct PoolEntry {
+    /// The copies of the sound that are playing.
+    sounds: Vec<Entity>,
+    /// When the last copy started, in real time.
+    last_played: Duration,
+}
+
+impl SoundPool {
+    /// Limits how often `sound` can be played, in place of the default limit.
+    pub fn set_limit(&mut self, sound: impl Into<AssetId<AudioSource>>, limit: SoundLimit) {
+        self.limits.insert(sound.into(), limit);
+    }
+
+    fn limit(&self, sound: AssetId<AudioSource>) -> SoundLimit {
+        self.limits
+            .get(&sound)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Adds `entity` to the sounds playing `sound` if it's within its limits, returning whether it
+    /// was.
+    fn admit(&mut self, sound: AssetId<AudioSource>, entity: Entity, now: Duration) -> bool {
+        let limit = self.limit(sound);
+        if let Some(entry) = self.playing.get(&sound)
+            && (entry.sounds.len() >= limit.max_concurrent
+                || now < entry.last_played + limit.cooldown)
+        {
+            return false;
+        }
+        let entry = self.playing.entry(sound).or_default();
+        entry.sounds.push(entity);
+        entry.last_played = now;
+        true
+    }
+
+    /// Removes `entity` from the sounds playing `sound`, forgetting the sound once nothing is
+    /// playing it.
+    fn release(&mut self, sound: AssetId<AudioSource>, entity: Entity) {
+        if let Some(entry) = self.playing.get_mut(&sound) {
+            entry.sounds.retain(|&e| e != entity);
+            if entry.sounds.is_empty() {
+                self.playing.remove(&sound);
+            }
+        }
+    }
+}
+
+fn admit_pooled_sound(
+    add: On<Add, PooledSound>,
+    time: Res<Time<Real>>,
+    mut pool: ResMut<SoundPool>,
+    sounds: Query<&AudioPlayer>,
+    mut commands: Commands,
+) {
+    let Ok(player) = sounds.get(add.entity) else {
+        return;
+    };
+    if !pool.admit(player.0.id(), add.entity, time.elapsed()) {
+        commands.entity(add.entity).despawn();
+    }
+}
+
+fn release_pooled_sound(
+    remove: On<Remove, PooledSound>,
+    mut pool: ResMut<SoundPool>,
+    sounds: Query<&AudioPlayer>,
+) {
+    if let Ok(player) = sounds.get(remove.entity) {
+        pool.release(player.0.id(), remove.entity);
+    }
+}
+
+/// A sound effect played from `position` in the world, panned and attenuated relative to the
+/// [`SoundListener`].
+pub fn spatial_sound_effect(
+    handle: Handle<AudioSource>,
+    volume: f32,
+    position: Vec2,
+) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings {
+            volume: Volume::Linear(volume),
+            spatial: true,
+            ..PlaybackSettings::DESPAWN
+        },
+        SoundEffect,
+        PooledSound,
+        PositionalSound::default(),
+        Transform::from_translation(position.extend(0.0)),
     )
 }
 
+/// Returns how much faster a sound from an emitter plays than it was made, given its Lorentz
+/// factor and its speed towards the player, both in the player's frame. Negative speeds are
+/// receding.
+///
+/// This is the relativistic Doppler effect in full: approaching sources are shifted up and
+/// receding ones down, and moving clocks run slow even when passing by.
+fn relativistic_pitch(gamma: f32, radial_speed: f32, c: f32) -> f32 {
+    let b = beta(radial_speed, c);
+    (1.0 / (gamma * (1.0 - b))).clamp(PITCH_RANGE.0, PITCH_RANGE.1)
+}
+
+fn apply_relativistic_pitch(
+    c: Res<LocalSpeedOfLight>,
+    intensity: Res<EffectsIntensity>,
+    player: Single<(&GlobalTransform, &LinearVelocity), With<Player>>,
+    emitters: Query<(&AudioEmitter, &GlobalTransform, &LinearVelocity)>,
+    sounds: Query<(Option<&AudioSink>, Option<&SpatialAudioSink>), Without<Music>>,
+    music: Query<&AudioSink, With<Music>>,
+) {
+    let (player_transform, player_velocity) = player.into_inner();
+    let player_position = player_transform.translation().xy();
+
+    for (emitter, transform, velocity) in &emitters {
+        // Heard by the player, so everything is measured in the player's frame.
+        let relative_velocity = rel_add(-player_velocity.0, velocity.0, c.0);
+        let towards_player = (player_position - transform.translation().xy()).normalize_or_zero();
+        let radial_speed = relative_velocity.dot(towards_player);
+        let pitch = relativistic_pitch(gamma(relative_velocity.length(), c.0), radial_speed, c.0);
+        let speed = intensity.doppler(pitch);
+
+        for (sink, spatial_sink) in sounds.iter_many(emitter.iter()) {
+            if let Some(sink) = sink {
+                sink.set_speed(speed);
+            } else if let Some(sink) = spatial_sink {
+                sink.set_speed(speed);
+            }
+        }
+    }
+
+    let music_speed = intensity.doppler(1.0 / gamma(player_velocity.length(), c.0));
+    for sink in &music {
+        sink.set_speed(music_speed);
+    }
+}
+
+fn update_positional_sounds(
+    global_volume: Res<GlobalVolume>,
+    listener: Single<(&GlobalTransform, &SoundListener)>,
+    mut sounds: Query<(
+        &GlobalTransform,
+        &PositionalSound,
+        &PlaybackSettings,
+        &mut SpatialAudioSink,
+    )>,
+) {
+    let (listener_transform, listener) = listener.into_inner();
+    let listener_position = listener_transform.translation().xy();
+
+    for (transform, sound, playback, mut sink) in &mut sounds {
+        let offset = transform.translation().xy() - listener_position;
+        let attenuation = Volume::Linear(sound.attenuation(offset.length()));
+        sink.set_volume(global_volume.volume * playback.volume * attenuation);
+        set_pan(&sink, (offset.x / listener.pan_width).clamp(-1.0, 1.0));
+    }
+}
+
+/// Pans a sound from the left (-1) to the right (1) speaker, without changing its volume.
+///
+/// Spatial sinks attenuate each ear past a unit from the emitter, so the emitter is kept within a
+/// unit of both. They also play each ear louder the farther it is from the emitter, so the ears
+/// are placed on opposite sides.
+fn set_pan(sink: &SpatialAudioSink, pan: f32) {
+    sink.set_ears_position(Vec3::X * 0.5, Vec3::NEG_X * 0.5);
+    sink.set_emitter_position(Vec3::X * pan * 0.5);
+}
+
 /// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them.
 fn apply_global_volume(
     global_volume: Res<GlobalVolume>,
@@ -6,7 +6,7 @@ use bevy::{
     sprite_render::{Material2d, Material2dPlugin},
 };
 
-use crate::{asset_tracking::LoadResource, demo::player::PlayerCamera, screens::Screen};
+use crate::{asset_tracking::LoadResource, gameplay::camera::PlayerCamera, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(Material2dPlugin::<ParallaxMaterial>::default());
@@ -0,0 +1,164 @@
+//! Loads every LDtk world in `assets/` and the enemy manifest through the game's asset loaders,
+//! without a window, and reports anything wrong with them.
+//!
+//! Exits with a nonzero status if anything failed to load or any level has warnings, so content
+//! mistakes are caught before uploading a build:
+//!
+//! ```sh
+//! cargo run --bin validate_levels
+//! ```
+
+use std::{io, path::Path};
+
+use bevy::{
+    asset::{
+        AssetMetaCheck, RecursiveDependencyLoadState, UntypedAssetId, io::file::FileAssetReader,
+    },
+    audio::AudioLoader,
+    image::{CompressedImageFormats, ImageLoader},
+    prelude::*,
+};
+use bevy_jam_7::{
+    prelude::*,
+    validation::{ValidationContext, validate_level},
+};
+
+const ASSETS_DIR: &str = "assets";
+const ENEMY_MANIFEST_PATH: &str = "enemies.json";
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            },
+            ImagePlugin::default_nearest(),
+            ContentPlugin,
+        ))
+        // The renderer usually registers this, once it knows which compressed formats the GPU
+        // supports. Tilesets are plain PNGs, so none are needed here.
+        .register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE))
+        // Enemy sounds are loaded with the manifest, but there's no audio output to play them.
+        .init_asset::<AudioSource>()
+        .init_asset_loader::<AudioLoader>()
+        .add_systems(Startup, load_content)
+        .add_systems(Update, report.run_if(content_loaded))
+        .run()
+}
+
+#[derive(Resource)]
+struct Content {
+    worlds: Vec<Handle<LdtkWorld>>,
+    enemies: Handle<EnemyManifest>,
+}
+
+impl Content {
+    fn ids(&self) -> impl Iterator<Item = UntypedAssetId> {
+        self.worlds
+            .iter()
+            .map(|handle| handle.id().untyped())
+            .chain([self.enemies.id().untyped()])
+    }
+}
+
+fn load_content(asset_server: Res<AssetServer>, mut commands: Commands) -> Result {
+    let root = FileAssetReader::get_base_path().join(ASSETS_DIR);
+    let mut paths = Vec::new();
+    find_worlds(&root, &root, &mut paths)?;
+    paths.sort();
+
+    commands.insert_resource(Content {
+        worlds: paths
+            .into_iter()
+            .map(|path| asset_server.load(path))
+            .collect(),
+        enemies: asset_server.load(ENEMY_MANIFEST_PATH),
+    });
+    Ok(())
+}
+
+/// Collects the paths of `.ldtk` files under `dir`, relative to `root`.
+fn find_worlds(root: &Path, dir: &Path, paths: &mut Vec<String>) -> io::Result<()> {
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_worlds(root, &path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "ldtk")
+            && let Ok(relative) = path.strip_prefix(root)
+        {
+            paths.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+fn content_loaded(content: Option<Res<Content>>, asset_server: Res<AssetServer>) -> bool {
+    content.is_some_and(|content| {
+        content.ids().all(|id| {
+            matches!(
+                asset_server.get_recursive_dependency_load_state(id),
+                Some(
+                    RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+                )
+            )
+        })
+    })
+}
+
+fn report(
+    content: Res<Content>,
+    asset_server: Res<AssetServer>,
+    worlds: Res<Assets<LdtkWorld>>,
+    levels: Res<Assets<Level>>,
+    manifests: Res<Assets<EnemyManifest>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let mut problems = 0;
+
+    for id in content.ids() {
+        if let Some(RecursiveDependencyLoadState::Failed(error)) =
+            asset_server.get_recursive_dependency_load_state(id)
+        {
+            let path = asset_server.get_path(id).map(|path| path.to_string());
+            println!("{}: failed to load: {error}", path.unwrap_or_default());
+            problems += 1;
+        }
+    }
+
+    let player_controller = GameConfig::load().player_controller.to_world();
+    for (handle, world) in content
+        .worlds
+        .iter()
+        .filter_map(|handle| Some((handle, worlds.get(handle)?)))
+    {
+        let path = handle.path().map(|path| path.to_string());
+        for world_level in &world.levels {
+            let Some(level) = levels.get(&world_level.level) else {
+                continue;
+            };
+            let context = ValidationContext {
+                manifest: manifests.get(&content.enemies),
+                player_controller: Some(&player_controller),
+                neighbours: &world_level.neighbours,
+            };
+            for warning in validate_level(level, &context) {
+                println!(
+                    "{}#{}: {warning}",
+                    path.as_deref().unwrap_or_default(),
+                    world_level.identifier
+                );
+                problems += 1;
+            }
+        }
+    }
+
+    if problems == 0 {
+        println!("Checked {} worlds, no problems found", content.worlds.len());
+        exit.write(AppExit::Success);
+    } else {
+        println!("Found {problems} problems");
+        exit.write(AppExit::error());
+    }
+}
@@ -0,0 +1,107 @@
+//! Global settings that are read once at startup, before any plugins are built.
+//!
+//! The config lives in `assets/config.json`. Native builds read it from disk so it can be tweaked
+//! without recompiling, falling back to the copy embedded at compile time if it's missing. Web
+//! builds always use the embedded copy.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controller::CharacterController;
+
+const EMBEDDED_CONFIG: &str = include_str!("../assets/config.json");
+#[cfg(not(target_family = "wasm"))]
+const CONFIG_PATH: &str = "assets/config.json";
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Debug)]
+#[reflect(Resource)]
+pub struct GameConfig {
+    pub window: WindowConfig,
+    /// The speed of light when the game starts, outside of any zones.
+    pub speed_of_light: f32,
+    pub debug: DebugConfig,
+    /// The player's controller tunables, in tiles. See [`CharacterController::to_world`].
+    pub player_controller: CharacterController,
+    #[serde(default)]
+    pub terrain_colliders: TerrainColliderMode,
+    /// The logos shown one after another at startup, before the title screen.
+    #[serde(default = "default_splash")]
+    pub splash: Vec<SplashLogo>,
+}
+
+/// One logo of the startup splash sequence.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct SplashLogo {
+    /// The logo's image, relative to the assets folder.
+    pub image: String,
+    /// How long the logo is shown for, including its fades.
+    pub duration_secs: f32,
+    /// How long the logo takes to fade in and out.
+    pub fade_secs: f32,
+}
+
+fn default_splash() -> Vec<SplashLogo> {
+    vec![SplashLogo {
+        image: "images/splash.png".to_string(),
+        duration_secs: 1.8,
+        fade_secs: 0.6,
+    }]
+}
+
+/// How solid terrain is turned into colliders.
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TerrainColliderMode {
+    /// Merged rectangles. Characters can catch on the seams between them.
+    #[default]
+    Rectangles,
+    /// One closed polyline around each solid region, with no internal edges.
+    Outlines,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct WindowConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    pub fit_canvas_to_parent: bool,
+}
+
+/// Initial state of the dev tools. These have no effect in release builds.
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct DebugConfig {
+    pub show_inspector: bool,
+    pub show_physics_gizmos: bool,
+}
+
+impl GameConfig {
+    /// Reads the config synchronously, since it's needed before the asset server exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config can't be parsed. It ships with the game, so this is a build error.
+    pub fn load() -> Self {
+        let text = Self::read().unwrap_or_else(|| EMBEDDED_CONFIG.to_string());
+        serde_json::from_str(&text).expect("invalid game config")
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn read() -> Option<String> {
+        std::fs::read_to_string(CONFIG_PATH).ok()
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn read() -> Option<String> {
+        None
+    }
+
+    pub fn primary_window(&self) -> Window {
+        Window {
+            title: self.window.title.clone(),
+            resolution: (self.window.width, self.window.height).into(),
+            fit_canvas_to_parent: self.window.fit_canvas_to_parent,
+            ..default()
+        }
+    }
+}
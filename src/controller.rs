@@ -1,20 +1,42 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::{PausableSystems, physics::GamePhysicsLayers};
+use crate::{
+    PausableSystems,
+    physics::{
+        GamePhysicsLayers, LevelPhysics, MAX_BETA, OneWayPlatform, SpeedOfLight, SpeedOfLightZone,
+        Splash, WaterVolume, ZeroGravityZone, effective_speed_of_light,
+    },
+    units::{TilesPerSecond, TilesPerSecondSquared, WorldMeters},
+};
 
 const CASTER_SHAPE_SCALE: f32 = 0.99;
-const CASTER_MAX_DISTANCE: f32 = 0.1;
+const CASTER_MAX_DISTANCE: WorldMeters = WorldMeters::from_tiles(0.1);
+/// The maximum angle between a surface normal and the horizontal for it to count as a wall.
+const MAX_WALL_ANGLE: f32 = 0.35;
+/// Landings slower than this don't bounce, even on bouncy surfaces.
+const MIN_BOUNCE_SPEED: f32 = 2.0;
+/// How many physics timesteps a [`Knockback`] is applied over.
+const KNOCKBACK_TICKS: u32 = 4;
+/// The fraction of the remaining [`Knockback`] applied each physics timestep.
+const KNOCKBACK_DECAY: f32 = 0.5;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PreUpdate, reset_jump_state)
         .add_systems(
             FixedUpdate,
             (
+                update_zero_gravity_state,
                 update_grounded,
+                update_wall_state,
+                update_water_state,
                 apply_gravity,
                 apply_movement_damping,
+                tick_stuns,
                 apply_intents,
+                apply_dash,
+                apply_knockback,
             )
                 .chain()
                 .in_set(PausableSystems),
@@ -43,7 +65,7 @@ pub fn character_controller(
         collider,
         collision_layers,
         ShapeCaster::new(caster_shape, Vec2::ZERO, 0.0, Dir2::NEG_Y)
-            .with_max_distance(CASTER_MAX_DISTANCE)
+            .with_max_distance(CASTER_MAX_DISTANCE.0)
             // Removing this allows walking/jumping on top of enemies. Good? Bad?
             .with_query_filter(SpatialQueryFilter::from_mask(
                 GamePhysicsLayers::LevelGeometry,
@@ -51,9 +73,28 @@ pub fn character_controller(
     )
 }
 
-#[derive(Component, Reflect)]
+/// Tunables for a kinematic character.
+///
+/// Speeds and accelerations are in world units. Tunables written in tiles, like those in the
+/// [`GameConfig`], should be converted with [`to_world`] so they keep up with the tile size.
+///
+/// [`GameConfig`]: crate::config::GameConfig
+/// [`to_world`]: Self::to_world
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Debug)]
 #[reflect(Component)]
-#[require(CharacterIntent, GroundNormal, JumpState, MoveAndSlideResult)]
+#[require(
+    CharacterIntent,
+    DashState,
+    GroundNormal,
+    GroundBody,
+    GroundMaterial,
+    GroundVelocity,
+    JumpState,
+    MoveAndSlideResult,
+    WallState,
+    WaterState,
+    ZeroGravityState
+)]
 pub struct CharacterController {
     /// Acceleration applied while in the air.
     pub accel_air: f32,
@@ -107,17 +148,116 @@ pub struct CharacterController {
     /// The maximum angle on which a character can stand and be considered grounded.
     pub max_slope_angle: f32,
 
+    /// The tallest ledge that a grounded character will step up onto without jumping.
+    pub step_height: f32,
+
+    /// How far a grounded character can be pulled down to stay on the ground, e.g. when walking
+    /// down a slope or off a small step. Set to `0.0` to disable snapping.
+    pub snap_distance: f32,
+
+    /// The fraction of the ground's velocity that is added to the character's velocity when it
+    /// starts a jump.
+    ///
+    /// A value of `1.0` fully preserves the momentum of a moving platform or conveyor, while
+    /// `0.0` ignores it entirely.
+    pub ground_velocity_inheritance: f32,
+
+    /// Gravity is scaled by this while sliding down a wall.
+    ///
+    /// Values below `1.0` make walls "sticky", slowing the character's fall.
+    pub wall_slide_gravity_scale: f32,
+
+    /// The velocity a wall jump launches the character with.
+    ///
+    /// The `x` component points away from the wall, and `y` points up.
+    pub wall_jump_impulse: Vec2,
+
     /// The maximum speed that the character can accelerate itself to while on the ground.
     pub max_speed: f32,
+
+    /// The speed of a dash, as a fraction of the local [`SpeedOfLight`].
+    ///
+    /// This is capped at [`MAX_BETA`].
+    pub dash_beta: f32,
+
+    /// How long a dash lasts, in seconds.
+    pub dash_secs: f32,
+
+    /// The time between the starts of consecutive dashes, in seconds.
+    pub dash_cooldown_secs: f32,
+
+    /// Acceleration applied while swimming, both horizontally and upward while jumping.
+    pub accel_water: f32,
+
+    /// The maximum horizontal speed that the character can swim at.
+    pub max_speed_water: f32,
+
+    /// Exponential velocity falloff (per second) on both axes while swimming.
+    pub damping_water: f32,
+
+    /// Gravity is scaled by this while swimming, before the water's buoyancy is added.
+    pub water_gravity_scale: f32,
+
+    /// Acceleration from the thrusters while the jump intent is held in a [`ZeroGravityZone`].
+    pub thrust_accel: f32,
+
+    /// How quickly the character turns in a [`ZeroGravityZone`], in radians per second at full
+    /// [`movement`] intent.
+    ///
+    /// [`movement`]: CharacterIntent::movement
+    pub turn_speed: f32,
+
+    /// Exponential velocity falloff (per second) on both axes in a [`ZeroGravityZone`].
+    ///
+    /// Keep this low, so that thrusting keeps adding up towards the speed of light.
+    pub damping_zero_gravity: f32,
+}
+
+impl CharacterController {
+    /// Converts speeds and accelerations measured in tiles into world units.
+    pub fn to_world(&self) -> Self {
+        let length = |tiles| WorldMeters::from_tiles(tiles).0;
+        let speed = |tiles| TilesPerSecond(tiles).to_world();
+        let accel = |tiles| TilesPerSecondSquared(tiles).to_world();
+        Self {
+            accel_air: accel(self.accel_air),
+            accel_ground: accel(self.accel_ground),
+            decel_ground: accel(self.decel_ground),
+            jump_impulse: accel(self.jump_impulse),
+            wall_jump_impulse: Vec2::new(
+                speed(self.wall_jump_impulse.x),
+                speed(self.wall_jump_impulse.y),
+            ),
+            max_speed: speed(self.max_speed),
+            step_height: length(self.step_height),
+            snap_distance: length(self.snap_distance),
+            accel_water: accel(self.accel_water),
+            max_speed_water: speed(self.max_speed_water),
+            thrust_accel: accel(self.thrust_accel),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct CharacterIntent {
+    /// Horizontal movement from `-1.0` to `1.0`, scaling the speed the character moves at.
     pub movement: f32,
     pub jump: bool,
+    /// Whether the jump intent ended since the last tick, even if it has started again since.
+    pub jump_released: bool,
+    /// Drop down through any [`OneWayPlatform`] the character is standing on.
+    pub drop: bool,
+    /// Dash in the direction the character is facing. Must be released between dashes.
+    pub dash: bool,
 }
 
+/// Lets a character start a new jump in midair each time the jump input is pressed again.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct InfiniteJumps;
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct GroundNormal(Option<Vec2>);
@@ -128,11 +268,167 @@ impl GroundNormal {
     }
 }
 
+/// The body that a character is currently standing on, if any.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct GroundBody(Option<Entity>);
+
+/// How a surface feels to walk on. Ground without one acts like [`SurfaceMaterial::default`].
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct SurfaceMaterial {
+    /// Scales a character's ground acceleration, deceleration and damping.
+    pub friction: f32,
+    /// The fraction of a character's landing speed that it bounces back up with.
+    pub bounciness: f32,
+    /// The playback speed of footstep sounds, which also shifts their pitch.
+    pub footstep_speed: f32,
+    /// Scales the volume of footstep sounds.
+    pub footstep_volume: f32,
+    /// How much penetration a projectile needs to go through the surface instead of stopping.
+    pub hardness: f32,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 1.0,
+            bounciness: 0.0,
+            footstep_speed: 1.0,
+            footstep_volume: 1.0,
+            hardness: 1.0,
+        }
+    }
+}
+
+/// The [`SurfaceMaterial`] of the ground a character is standing on, or the last ground it stood
+/// on while airborne.
+#[derive(Component, Reflect, Default, Deref, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct GroundMaterial(SurfaceMaterial);
+
+/// The velocity of the body that a character is currently standing on.
+///
+/// This is [`Vec2::ZERO`] while airborne or standing on static geometry. Grounded characters are
+/// carried along at this velocity, on top of their own.
+#[derive(Component, Reflect, Default, Deref, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct GroundVelocity(Vec2);
+
+/// The normal of the wall a character is touching, if any.
+///
+/// Walls are only detected while the character is airborne.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct WallState(Option<Vec2>);
+
+impl WallState {
+    pub fn normal(&self) -> Option<Vec2> {
+        self.0
+    }
+
+    pub fn is_touching_wall(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+/// The [`WaterVolume`] a character is swimming in, if any.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct WaterState {
+    volume: Option<Entity>,
+    buoyancy: f32,
+}
+
+impl WaterState {
+    pub fn is_swimming(&self) -> bool {
+        self.volume.is_some()
+    }
+}
+
+/// The [`ZeroGravityZone`] a character is floating in, if any, and which way it's facing there.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct ZeroGravityState {
+    zone: Option<Entity>,
+    /// The angle the character's thrusters push it towards, counterclockwise from `+X`.
+    heading: f32,
+}
+
+impl ZeroGravityState {
+    pub fn is_floating(&self) -> bool {
+        self.zone.is_some()
+    }
+
+    /// The direction the character's thrusters push it, if it's floating.
+    pub fn heading(&self) -> Option<Vec2> {
+        self.zone.map(|_| Vec2::from_angle(self.heading))
+    }
+}
+
+/// The state of a character's dash ability.
+///
+/// While dashing, the character ignores gravity and damping, and can't be killed.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct DashState {
+    /// Seconds left in the current dash.
+    remaining: f32,
+    /// Seconds until the next dash can start.
+    cooldown: f32,
+    direction: f32,
+    /// The direction of the last horizontal movement intent.
+    facing: f32,
+    /// Whether the dash intent has been released since the last dash.
+    released: bool,
+}
+
+impl DashState {
+    pub fn is_dashing(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+/// Pushes a character with a velocity change that's applied over a few physics timesteps,
+/// strongest at first. Knocked back characters ignore their intents.
+///
+/// Works on any body with a [`LinearVelocity`], including characters moved by the
+/// [`movement`](crate::gameplay::movement) controllers.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Knockback {
+    /// The part of the velocity change that's still to be applied.
+    remaining: Vec2,
+    ticks: u32,
+}
+
+impl Knockback {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            remaining: velocity,
+            ticks: KNOCKBACK_TICKS,
+        }
+    }
+}
+
+/// Keeps a character from acting on its intents until the timer runs out.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Stunned(pub Timer);
+
+impl Stunned {
+    pub fn from_seconds(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 struct JumpState {
     normal: Option<Vec2>,
     ticks: u32,
+    /// Whether the jump intent has been released since the last jump.
+    released: bool,
 }
 
 fn reset_jump_state(
@@ -154,36 +450,231 @@ fn reset_jump_state(
     }
 }
 
-fn update_grounded(mut controllers: Query<(&CharacterController, &ShapeHits, &mut GroundNormal)>) {
-    for (controller, hits, mut ground_norm) in &mut controllers {
-        ground_norm.0 = hits
+fn update_grounded(
+    mut controllers: Query<(
+        &CharacterController,
+        &CharacterIntent,
+        &ShapeHits,
+        &ZeroGravityState,
+        &mut GroundNormal,
+        &mut GroundBody,
+        &mut GroundVelocity,
+        &mut GroundMaterial,
+        &mut LinearVelocity,
+    )>,
+    colliders: Query<&ColliderOf>,
+    bodies: Query<&LinearVelocity, Without<CharacterController>>,
+    materials: Query<&SurfaceMaterial>,
+    platforms: Query<(), With<OneWayPlatform>>,
+) {
+    for (
+        controller,
+        intent,
+        hits,
+        zero_gravity,
+        mut ground_norm,
+        mut ground_body,
+        mut ground_vel,
+        mut ground_material,
+        mut velocity,
+    ) in &mut controllers
+    {
+        // Floating characters are never grounded, even when touching a surface.
+        let ground_hit = hits
+            .iter()
+            .filter(|_| !zero_gravity.is_floating())
+            .find(|hit| {
+                // Platforms only count as ground when landed on from above.
+                if platforms.contains(hit.entity) && (intent.drop || hit.distance <= 0.0) {
+                    return false;
+                }
+                hit.normal1.angle_to(Vec2::Y).abs() < controller.max_slope_angle
+            });
+
+        let was_grounded = ground_norm.is_grounded();
+        ground_norm.0 = ground_hit.map(|hit| hit.normal1);
+        ground_body.0 =
+            ground_hit.map(|hit| colliders.get(hit.entity).map_or(hit.entity, |c| c.body));
+        ground_vel.0 = ground_body
+            .0
+            .and_then(|body| bodies.get(body).ok())
+            .map_or(Vec2::ZERO, |v| v.0);
+
+        let Some(hit) = ground_hit else {
+            continue;
+        };
+        ground_material.0 = materials
+            .get(hit.entity)
+            .or_else(|_| materials.get(ground_body.0.unwrap()))
+            .copied()
+            .unwrap_or_default();
+
+        // Bounce off the ground instead of landing on it
+        let landing_speed = -velocity.dot(hit.normal1);
+        if !was_grounded && ground_material.bounciness > 0.0 && landing_speed > MIN_BOUNCE_SPEED {
+            velocity.0 += (1.0 + ground_material.bounciness) * landing_speed * hit.normal1;
+            ground_norm.0 = None;
+            ground_body.0 = None;
+            ground_vel.0 = Vec2::ZERO;
+        }
+    }
+}
+
+fn update_wall_state(
+    spatial_query: SpatialQuery,
+    mut controllers: Query<(
+        Entity,
+        &ShapeCaster,
+        &Position,
+        &Rotation,
+        &GroundNormal,
+        &ZeroGravityState,
+        &mut WallState,
+    )>,
+    platforms: Query<(), With<OneWayPlatform>>,
+) {
+    let config = ShapeCastConfig::from_max_distance(CASTER_MAX_DISTANCE.0);
+    for (entity, caster, position, rotation, ground_norm, zero_gravity, mut wall_state) in
+        &mut controllers
+    {
+        if ground_norm.is_grounded() || zero_gravity.is_floating() {
+            wall_state.0 = None;
+            continue;
+        }
+
+        let filter = SpatialQueryFilter::from_mask(GamePhysicsLayers::LevelGeometry)
+            .with_excluded_entities([entity]);
+        wall_state.0 = [Dir2::NEG_X, Dir2::X].into_iter().find_map(|direction| {
+            // One-way platforms never act as walls.
+            let hit = spatial_query.cast_shape_predicate(
+                &caster.shape,
+                position.0,
+                rotation.as_radians(),
+                direction,
+                &config,
+                &filter,
+                &|entity| !platforms.contains(entity),
+            )?;
+            (hit.normal1.angle_to(-direction.as_vec2()).abs() < MAX_WALL_ANGLE)
+                .then_some(hit.normal1)
+        });
+    }
+}
+
+fn update_water_state(
+    volumes: Query<(Entity, &WaterVolume, &GlobalTransform)>,
+    mut controllers: Query<(Entity, &Position, &LinearVelocity, &mut WaterState)>,
+    mut commands: Commands,
+) {
+    for (entity, position, velocity, mut water) in &mut controllers {
+        let current = volumes.iter().find(|(_, volume, transform)| {
+            volume.contains(transform.translation().xy(), position.0)
+        });
+
+        let next = WaterState {
+            volume: current.map(|(volume, ..)| volume),
+            buoyancy: current.map_or(0.0, |(_, volume, _)| volume.buoyancy),
+        };
+        if next.volume != water.volume {
+            commands.trigger(Splash {
+                entity,
+                volume: next.volume.or(water.volume).unwrap(),
+                position: position.0,
+                velocity: velocity.0,
+                entered: next.is_swimming(),
+            });
+        }
+        water.set_if_neq(next);
+    }
+}
+
+fn update_zero_gravity_state(
+    zones: Query<(Entity, &ZeroGravityZone, &GlobalTransform)>,
+    mut controllers: Query<(&Position, &mut ZeroGravityState)>,
+) {
+    for (position, mut state) in &mut controllers {
+        let zone = zones
             .iter()
-            .find(|hit| hit.normal1.angle_to(Vec2::Y).abs() < controller.max_slope_angle)
-            .map(|hit| hit.normal1);
+            .find(|(_, zone, transform)| zone.contains(transform.translation().xy(), position.0))
+            .map(|(entity, ..)| entity);
+
+        if zone.is_some() && !state.is_floating() {
+            // Start out facing up, the way the character was standing.
+            state.heading = std::f32::consts::FRAC_PI_2;
+        }
+        state.zone = zone;
     }
 }
 
 fn apply_gravity(
     time: Res<Time>,
     gravity: Res<Gravity>,
-    mut query: Query<(&GroundNormal, &mut LinearVelocity), With<CharacterController>>,
+    mut query: Query<(
+        &CharacterController,
+        &GroundNormal,
+        &WallState,
+        &WaterState,
+        &ZeroGravityState,
+        &DashState,
+        &mut LinearVelocity,
+    )>,
 ) {
     let g = gravity.0 * time.delta_secs();
-    for (ground_normal, mut velocity) in &mut query {
-        if !ground_normal.is_grounded() {
-            velocity.0 += g;
+    for (controller, ground_normal, wall_state, water, zero_gravity, dash, mut velocity) in
+        &mut query
+    {
+        if ground_normal.is_grounded() || zero_gravity.is_floating() || dash.is_dashing() {
+            continue;
         }
+
+        if water.is_swimming() {
+            velocity.0 +=
+                controller.water_gravity_scale * g + Vec2::Y * water.buoyancy * time.delta_secs();
+            continue;
+        }
+
+        let sliding = wall_state.is_touching_wall() && velocity.y < 0.0;
+        velocity.0 += if sliding {
+            controller.wall_slide_gravity_scale * g
+        } else {
+            g
+        };
     }
 }
 
 fn apply_movement_damping(
     time: Res<Time>,
-    mut query: Query<(&CharacterController, &GroundNormal, &mut LinearVelocity)>,
+    physics: Res<LevelPhysics>,
+    mut query: Query<(
+        &CharacterController,
+        &GroundNormal,
+        &GroundMaterial,
+        &WaterState,
+        &ZeroGravityState,
+        &DashState,
+        &mut LinearVelocity,
+    )>,
 ) {
-    let dt = time.delta_secs();
-    for (controller, ground_norm, mut velocity) in &mut query {
+    let dt = time.delta_secs() * physics.damping_scale;
+    for (controller, ground_norm, ground_material, water, zero_gravity, dash, mut velocity) in
+        &mut query
+    {
+        if dash.is_dashing() {
+            continue;
+        }
+
+        if zero_gravity.is_floating() {
+            velocity.0 *= 1.0 / (1.0 + controller.damping_zero_gravity * dt);
+            continue;
+        }
+
+        if water.is_swimming() {
+            velocity.0 *= 1.0 / (1.0 + controller.damping_water * dt);
+            continue;
+        }
+
         let damping = if ground_norm.is_grounded() {
-            controller.damping_ground
+            controller.damping_ground * ground_material.friction
         } else {
             controller.damping_air
         };
@@ -197,11 +688,56 @@ fn apply_intents(
         &CharacterIntent,
         &CharacterController,
         &GroundNormal,
+        &GroundVelocity,
+        &GroundMaterial,
+        &WallState,
+        &WaterState,
+        &mut ZeroGravityState,
         &mut LinearVelocity,
         &mut JumpState,
+        Has<InfiniteJumps>,
+        Has<Knockback>,
+        Has<Stunned>,
     )>,
 ) {
-    for (intent, controller, ground_norm, mut velocity, mut jump_state) in &mut intents {
+    for (
+        intent,
+        controller,
+        ground_norm,
+        ground_vel,
+        ground_material,
+        wall_state,
+        water,
+        mut zero_gravity,
+        mut velocity,
+        mut jump_state,
+        infinite_jumps,
+        knocked_back,
+        stunned,
+    ) in &mut intents
+    {
+        if !intent.jump || intent.jump_released {
+            jump_state.released = true;
+        }
+
+        // Being hit cuts jumps short.
+        if knocked_back || stunned {
+            jump_state.normal = None;
+            continue;
+        }
+
+        if zero_gravity.is_floating() {
+            // Turn with the movement intent, and thrust forward with the jump intent.
+            jump_state.normal = None;
+            zero_gravity.heading -= intent.movement * controller.turn_speed * time.delta_secs();
+            if intent.jump
+                && let Some(heading) = zero_gravity.heading()
+            {
+                velocity.0 += controller.thrust_accel * time.delta_secs() * heading;
+            }
+            continue;
+        }
+
         if let Some(normal) = ground_norm.0 {
             // Ground
             let accel = if intent.movement == 0.0 {
@@ -210,7 +746,7 @@ fn apply_intents(
                 controller.accel_ground
             };
 
-            let dv = accel * time.delta_secs();
+            let dv = accel * ground_material.friction * time.delta_secs();
             let cur_speed = velocity.x;
             let req_speed = intent.movement * controller.max_speed;
 
@@ -226,7 +762,37 @@ fn apply_intents(
             // Start jumping
             if intent.jump && jump_state.ticks == 0 {
                 jump_state.normal = Some(normal);
+                jump_state.released = false;
+                velocity.0 += controller.ground_velocity_inheritance * ground_vel.0;
+            }
+        } else if water.is_swimming() {
+            // Swim
+            let dv = controller.accel_water * time.delta_secs();
+            let diff = intent.movement * controller.max_speed_water - velocity.x;
+            velocity.x += diff.clamp(-dv, dv);
+            if intent.jump {
+                velocity.y += dv;
             }
+        } else if let Some(wall_normal) = wall_state.normal()
+            && intent.jump
+            && jump_state.released
+        {
+            // Wall jump
+            let away = wall_normal.x.signum();
+            velocity.0 = Vec2::new(
+                away * controller.wall_jump_impulse.x,
+                controller.wall_jump_impulse.y,
+            );
+            jump_state.released = false;
+            // Don't add the regular jump impulse on top.
+            jump_state.normal = None;
+            jump_state.ticks = controller.jump_max_ticks.max(controller.jump_min_ticks);
+        } else if infinite_jumps && intent.jump && jump_state.released {
+            // Air jump, as though from flat ground
+            velocity.y = velocity.y.max(0.0);
+            jump_state.normal = Some(Vec2::Y);
+            jump_state.ticks = 0;
+            jump_state.released = false;
         } else {
             // Air
             velocity.x += intent.movement * controller.accel_air * time.delta_secs();
@@ -245,9 +811,98 @@ fn apply_intents(
     }
 }
 
+fn apply_dash(
+    time: Res<Time>,
+    global_c: Res<SpeedOfLight>,
+    zones: Query<(&SpeedOfLightZone, &GlobalTransform)>,
+    mut controllers: Query<(
+        &CharacterController,
+        &CharacterIntent,
+        &Position,
+        &ZeroGravityState,
+        &mut DashState,
+        &mut LinearVelocity,
+        Has<Stunned>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (controller, intent, position, zero_gravity, mut dash, mut velocity, stunned) in
+        &mut controllers
+    {
+        if intent.movement != 0.0 {
+            dash.facing = intent.movement.signum();
+        }
+        if !intent.dash {
+            dash.released = true;
+        }
+        dash.cooldown = (dash.cooldown - dt).max(0.0);
+
+        // Start dashing. There's nothing to push off from in zero gravity.
+        if intent.dash
+            && dash.released
+            && dash.cooldown == 0.0
+            && !dash.is_dashing()
+            && !stunned
+            && !zero_gravity.is_floating()
+        {
+            dash.remaining = controller.dash_secs;
+            dash.cooldown = controller.dash_cooldown_secs;
+            dash.direction = if dash.facing == 0.0 { 1.0 } else { dash.facing };
+            dash.released = false;
+        }
+
+        if !dash.is_dashing() {
+            continue;
+        }
+
+        let c = effective_speed_of_light(&global_c, &zones, position.0);
+        velocity.0 = Vec2::new(dash.direction * controller.dash_beta.min(MAX_BETA) * c, 0.0);
+
+        dash.remaining = (dash.remaining - dt).max(0.0);
+        if !dash.is_dashing() {
+            // Don't carry the dash's speed into regular movement.
+            velocity.x = velocity
+                .x
+                .clamp(-controller.max_speed, controller.max_speed);
+        }
+    }
+}
+
+fn tick_stuns(time: Res<Time>, mut stunned: Query<(Entity, &mut Stunned)>, mut commands: Commands) {
+    for (entity, mut stun) in &mut stunned {
+        if stun.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn apply_knockback(
+    mut knocked_back: Query<(Entity, &mut Knockback, &mut LinearVelocity)>,
+    mut commands: Commands,
+) {
+    for (entity, mut knockback, mut velocity) in &mut knocked_back {
+        knockback.ticks = knockback.ticks.saturating_sub(1);
+        let dv = if knockback.ticks == 0 {
+            knockback.remaining
+        } else {
+            KNOCKBACK_DECAY * knockback.remaining
+        };
+        velocity.0 += dv;
+        knockback.remaining -= dv;
+
+        if knockback.ticks == 0 {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
-struct MoveAndSlideResult(Option<MoveAndSlideOutput>);
+struct MoveAndSlideResult {
+    output: Option<MoveAndSlideOutput>,
+    /// The ground velocity that was added on top of the character's own for this move.
+    carried: Vec2,
+}
 
 fn handle_collisions(
     time: Res<Time>,
@@ -260,31 +915,159 @@ fn handle_collisions(
             &Rotation,
             &Position,
             &LinearVelocity,
+            Option<&CharacterController>,
+            Option<&CharacterIntent>,
+            Option<(&GroundNormal, &GroundBody, &GroundVelocity)>,
             &mut MoveAndSlideResult,
         ),
         With<CustomPositionIntegration>,
     >,
+    platforms: Query<(), With<OneWayPlatform>>,
 ) {
-    for (entity, collider, rotation, position, velocity, mut result) in &mut controllers {
-        if velocity.0 == Vec2::ZERO {
+    let config = MoveAndSlideConfig::default();
+    for (entity, collider, rotation, position, velocity, controller, intent, ground, mut result) in
+        &mut controllers
+    {
+        let dropping = intent.is_some_and(|intent| intent.drop);
+        let grounded = ground.is_some_and(|(normal, ..)| normal.is_grounded());
+
+        // Ride along with whatever is underfoot. The ground itself is ignored for this move, since
+        // it will have moved out of the way by the end of the step.
+        let (ground_body, carried) = ground
+            .map(|(_, body, velocity)| (body.0, velocity.0))
+            .unwrap_or_default();
+        let total_velocity = velocity.0 + carried;
+        if total_velocity == Vec2::ZERO {
             continue;
         }
 
-        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
-        let out = move_and_slide.move_and_slide(
+        let filter = SpatialQueryFilter::from_excluded_entities(
+            std::iter::once(entity).chain(ground_body.filter(|_| carried != Vec2::ZERO)),
+        );
+        let mut out = move_and_slide.move_and_slide(
             collider,
             position.0,
             rotation.as_radians(),
-            velocity.0,
+            total_velocity,
             time.delta(),
-            &MoveAndSlideConfig::default(),
+            &config,
             &filter,
-            |_hit| {
-                // collisions.insert(hit.entity);
-                MoveAndSlideHitResponse::Accept
+            |hit| {
+                if !platforms.contains(hit.entity) {
+                    return MoveAndSlideHitResponse::Accept;
+                }
+
+                // Pass through platforms from below, when already inside them, or on purpose.
+                if dropping
+                    || hit.intersects()
+                    || !OneWayPlatform::should_collide(hit.normal.as_vec2(), *hit.velocity)
+                {
+                    MoveAndSlideHitResponse::Ignore
+                } else {
+                    MoveAndSlideHitResponse::Accept
+                }
             },
         );
-        result.0 = Some(out);
+
+        if let Some(controller) = controller
+            && grounded
+            && !dropping
+        {
+            let shape = (collider, rotation.as_radians());
+            let step = StepContext {
+                move_and_slide: &move_and_slide,
+                shape,
+                filter: &filter,
+                config: &config,
+                max_slope_angle: controller.max_slope_angle,
+            };
+
+            // Step up onto small ledges that stopped horizontal movement.
+            let desired = total_velocity.x * time.delta_secs();
+            let moved = out.position.x - position.x;
+            if controller.step_height > 0.0
+                && desired != 0.0
+                && moved.abs() < 0.5 * desired.abs()
+                && let Some(stepped) = step.step_up(position.0, desired, controller.step_height)
+                && (stepped.x - position.x).abs() > moved.abs()
+            {
+                out.position = stepped;
+                out.projected_velocity = Vec2::new(total_velocity.x, 0.0);
+            }
+
+            // Stick to the ground when walking down slopes, but not when jumping off it.
+            if controller.snap_distance > 0.0
+                && out.projected_velocity.y <= carried.y
+                && let Some(distance) = step.ground_distance(out.position, controller.snap_distance)
+            {
+                out.position.y -= distance;
+            }
+        }
+
+        result.output = Some(out);
+        result.carried = carried;
+    }
+}
+
+/// Shape casts for stepping and snapping a grounded character.
+struct StepContext<'a, 'w, 's> {
+    move_and_slide: &'a MoveAndSlide<'w, 's>,
+    shape: (&'a Collider, f32),
+    filter: &'a SpatialQueryFilter,
+    config: &'a MoveAndSlideConfig,
+    max_slope_angle: f32,
+}
+
+impl StepContext<'_, '_, '_> {
+    /// Returns the distance down to walkable ground within `max_distance`, if there is any.
+    fn ground_distance(&self, position: Vec2, max_distance: f32) -> Option<f32> {
+        let (collider, rotation) = self.shape;
+        let hit = self.move_and_slide.cast_move(
+            collider,
+            position,
+            rotation,
+            Vec2::NEG_Y * max_distance,
+            self.config.skin_width,
+            self.filter,
+        )?;
+        (hit.normal1.angle_to(Vec2::Y).abs() < self.max_slope_angle).then_some(hit.distance)
+    }
+
+    /// Tries to move `dx` horizontally by first lifting the character by up to `step_height`.
+    ///
+    /// Returns the new position if the character lands on walkable ground.
+    fn step_up(&self, position: Vec2, dx: f32, step_height: f32) -> Option<Vec2> {
+        let (collider, rotation) = self.shape;
+        let skin_width = self.config.skin_width;
+
+        let lift = self
+            .move_and_slide
+            .cast_move(
+                collider,
+                position,
+                rotation,
+                Vec2::Y * step_height,
+                skin_width,
+                self.filter,
+            )
+            .map_or(step_height, |hit| hit.distance);
+        let raised = position + Vec2::Y * lift;
+
+        let forward = self
+            .move_and_slide
+            .cast_move(
+                collider,
+                raised,
+                rotation,
+                Vec2::X * dx,
+                skin_width,
+                self.filter,
+            )
+            .map_or(dx, |hit| hit.distance * dx.signum());
+        let ahead = raised + Vec2::X * forward;
+
+        let drop = self.ground_distance(ahead, lift + skin_width)?;
+        Some(ahead - Vec2::Y * drop)
     }
 }
 
@@ -292,9 +1075,10 @@ fn apply_move_and_slide(
     mut controllers: Query<(&mut MoveAndSlideResult, &mut Position, &mut LinearVelocity)>,
 ) {
     for (mut result, mut position, mut velocity) in &mut controllers {
-        if let Some(out) = result.0.take() {
+        if let Some(out) = result.output.take() {
             position.0 = out.position;
-            velocity.0 = out.projected_velocity;
+            // Keep the character's velocity relative to the ground it was carried by.
+            velocity.0 = out.projected_velocity - std::mem::take(&mut result.carried);
         }
     }
 }
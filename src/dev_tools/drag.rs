@@ -0,0 +1,75 @@
+//! Click and drag physics bodies around with the mouse.
+//!
+//! Picking goes through [`CursorWorldPosition`], which follows the length-contracted camera
+//! projection, so what's grabbed is what's under the cursor even while the level is scaled.
+
+use avian2d::prelude::*;
+use bevy::{
+    input::common_conditions::{input_just_pressed, input_just_released, input_pressed},
+    prelude::*,
+};
+
+use crate::{gameplay::camera::cursor::CursorWorldPosition, screens::Screen};
+
+const DRAG_BUTTON: MouseButton = MouseButton::Middle;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Dragging>().add_systems(
+        Update,
+        (
+            start_drag.run_if(input_just_pressed(DRAG_BUTTON)),
+            drag.run_if(input_pressed(DRAG_BUTTON)),
+            stop_drag.run_if(input_just_released(DRAG_BUTTON)),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The body being dragged, and where it was grabbed relative to its center.
+#[derive(Resource, Default)]
+struct Dragging(Option<(Entity, Vec2)>);
+
+fn start_drag(
+    cursor: Res<CursorWorldPosition>,
+    spatial_query: SpatialQuery,
+    colliders: Query<&ColliderOf>,
+    bodies: Query<(&RigidBody, &Position)>,
+    mut dragging: ResMut<Dragging>,
+) {
+    let Some(cursor) = cursor.0 else {
+        return;
+    };
+
+    // Terrain is static, and is never what you meant to grab.
+    dragging.0 = spatial_query
+        .point_intersections(cursor, &SpatialQueryFilter::default())
+        .into_iter()
+        .map(|collider| colliders.get(collider).map_or(collider, |c| c.body))
+        .find_map(|body| {
+            let (rigid_body, position) = bodies.get(body).ok()?;
+            (!rigid_body.is_static()).then(|| (body, position.0 - cursor))
+        });
+}
+
+fn drag(
+    cursor: Res<CursorWorldPosition>,
+    dragging: Res<Dragging>,
+    mut bodies: Query<(&mut Position, Option<&mut LinearVelocity>)>,
+) {
+    let (Some(cursor), Some((body, offset))) = (cursor.0, dragging.0) else {
+        return;
+    };
+    let Ok((mut position, velocity)) = bodies.get_mut(body) else {
+        return;
+    };
+
+    position.0 = cursor + offset;
+    if let Some(mut velocity) = velocity {
+        velocity.0 = Vec2::ZERO;
+    }
+}
+
+fn stop_drag(mut dragging: ResMut<Dragging>) {
+    dragging.0 = None;
+}
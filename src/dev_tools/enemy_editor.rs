@@ -0,0 +1,267 @@
+//! An egui panel for tweaking enemy definitions at runtime.
+//!
+//! Edits are applied to the loaded [`Enemy`] assets and any live enemies immediately, and can be
+//! written back to the manifest on disk.
+
+use avian2d::prelude::Collider;
+use bevy::{input::common_conditions::input_toggle_active, prelude::*};
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    animation::Animation,
+    assets::{
+        enemy::{Enemy, EnemyManifest, enemy_animation},
+        serialize::enemy::{self as de, ColliderShape, MovementKind},
+    },
+    gameplay::enemies::{EnemyHandle, ai::AiParams},
+};
+
+const EDITOR_TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<EnemyEditorStatus>().add_systems(
+        EguiPrimaryContextPass,
+        enemy_editor_ui.run_if(input_toggle_active(false, EDITOR_TOGGLE_KEY)),
+    );
+}
+
+/// The result of the last save, displayed at the bottom of the panel.
+#[derive(Resource, Default)]
+struct EnemyEditorStatus(Option<String>);
+
+fn enemy_editor_ui(
+    mut contexts: EguiContexts,
+    mut status: ResMut<EnemyEditorStatus>,
+    asset_server: Res<AssetServer>,
+    mut manifests: ResMut<Assets<EnemyManifest>>,
+    mut enemies: ResMut<Assets<Enemy>>,
+    mut animations: ResMut<Assets<Animation>>,
+    mut live_enemies: Query<(Entity, &EnemyHandle, &Children, &mut Sprite, &mut AiParams)>,
+    mut live_colliders: Query<(&mut Collider, &mut Transform)>,
+    mut commands: Commands,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    egui::Window::new("Enemy Editor").show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let manifest_ids = manifests.ids().collect::<Vec<_>>();
+            for manifest_id in manifest_ids {
+                // Edit the source definitions without flagging the manifest as modified, which
+                // would otherwise trigger a reload every frame.
+                let Some(manifest) = manifests.get_mut_untracked(manifest_id) else {
+                    continue;
+                };
+
+                let mut labels = manifest.source.enemies.keys().cloned().collect::<Vec<_>>();
+                labels.sort();
+
+                for label in labels {
+                    let mut def = match manifest.source.resolve(&label) {
+                        Ok(def) => def,
+                        Err(error) => {
+                            ui.colored_label(egui::Color32::RED, format!("{label}: {error}"));
+                            continue;
+                        }
+                    };
+                    if !enemy_def_ui(ui, &label, &mut def) {
+                        continue;
+                    }
+                    if let Err(error) = manifest.source.set_resolved(&label, &def) {
+                        warn!("Failed to update enemy {label:?}: {error}");
+                        continue;
+                    }
+
+                    // Apply the edit to the loaded asset and everything spawned from it.
+                    let Some(handle) = manifest.enemies.get(&label) else {
+                        continue;
+                    };
+                    let Some(enemy) = enemies.get_mut(handle) else {
+                        continue;
+                    };
+                    apply_enemy_def(&def, enemy, &mut animations);
+
+                    for (entity, enemy_handle, children, mut sprite, mut ai) in &mut live_enemies {
+                        if enemy_handle.id() != handle.id() {
+                            continue;
+                        }
+
+                        sprite.custom_size = Some(enemy.size);
+                        commands.entity(entity).insert(enemy.movement.clone());
+                        *ai = enemy.ai.clone();
+
+                        let mut iter = live_colliders.iter_many_mut(children);
+                        while let Some((mut collider, mut transform)) = iter.fetch_next() {
+                            *collider = enemy.collider.clone();
+                            transform.translation = enemy.collider_offset.extend(0.0);
+                        }
+                    }
+                }
+
+                #[cfg(not(target_family = "wasm"))]
+                if ui.button("Save manifest").clicked() {
+                    status.0 = Some(match save_manifest(&asset_server, manifest_id, manifest) {
+                        Ok(path) => format!("Saved to {}", path.display()),
+                        Err(error) => format!("Failed to save manifest: {error}"),
+                    });
+                }
+            }
+
+            if let Some(status) = &status.0 {
+                ui.label(status);
+            }
+        });
+    });
+
+    Ok(())
+}
+
+/// Draws editable fields for a single enemy definition. Returns `true` if anything changed.
+fn enemy_def_ui(ui: &mut egui::Ui, label: &str, def: &mut de::Enemy) -> bool {
+    let mut changed = false;
+
+    egui::CollapsingHeader::new(format!("{} ({label})", def.name))
+        .id_salt(label)
+        .show(ui, |ui| {
+            ui.label("Size");
+            changed |= vec2_ui(ui, &mut def.size);
+
+            ui.separator();
+            ui.label("Collider");
+            match &mut def.collider.shape {
+                ColliderShape::Rectangle { width, height } => {
+                    changed |= drag(ui, "width", width, 0.01);
+                    changed |= drag(ui, "height", height, 0.01);
+                }
+                ColliderShape::Capsule { radius, height } => {
+                    changed |= drag(ui, "radius", radius, 0.01);
+                    changed |= drag(ui, "height", height, 0.01);
+                }
+            }
+            ui.label("offset");
+            changed |= vec2_ui(ui, &mut def.collider.offset);
+
+            ui.separator();
+            ui.label("Movement");
+            let movement = &mut def.movement;
+            ui.horizontal(|ui| {
+                for (kind, name) in [
+                    (MovementKind::Walking, "walking"),
+                    (MovementKind::Flying, "flying"),
+                ] {
+                    changed |= ui.radio_value(&mut movement.kind, kind, name).changed();
+                }
+            });
+            changed |= drag(ui, "max_speed", &mut movement.max_speed, 0.1);
+            match movement.kind {
+                MovementKind::Walking => {
+                    changed |= drag(ui, "accel_ground", &mut movement.accel_ground, 0.1);
+                    changed |= drag(ui, "accel_air", &mut movement.accel_air, 0.01);
+                    changed |= drag(ui, "jump_strength", &mut movement.jump_strength, 0.1);
+                    changed |= drag(ui, "damping_air", &mut movement.damping_factor_air, 0.01);
+                    changed |= drag(
+                        ui,
+                        "damping_ground",
+                        &mut movement.damping_factor_ground,
+                        0.01,
+                    );
+                    changed |= drag(ui, "max_slope_angle", &mut movement.max_slope_angle, 0.01);
+                }
+                MovementKind::Flying => {
+                    changed |= drag(ui, "accel", &mut movement.accel_flying, 0.1);
+                    changed |= drag(ui, "hover_amplitude", &mut movement.hover_amplitude, 0.01);
+                    changed |= drag(
+                        ui,
+                        "hover_period_secs",
+                        &mut movement.hover_period_secs,
+                        0.01,
+                    );
+                    changed |= drag(ui, "dive_speed", &mut movement.dive_speed, 0.1);
+                }
+            }
+
+            ui.separator();
+            ui.label("AI");
+            let ai = &mut def.ai;
+            changed |= drag(ui, "aggro_range", &mut ai.aggro_range, 0.1);
+            changed |= drag(ui, "attack_range", &mut ai.attack_range, 0.1);
+            changed |= drag(ui, "give_up_secs", &mut ai.give_up_secs, 0.1);
+            changed |= drag(ui, "flee_health", &mut ai.flee_health, 0.01);
+
+            ui.separator();
+            ui.label("Animations");
+            let mut names = def.atlas_animations.keys().cloned().collect::<Vec<_>>();
+            names.sort();
+            for name in names {
+                let anim = def.atlas_animations.get_mut(&name).unwrap();
+                ui.horizontal(|ui| {
+                    ui.label(&name);
+                    changed |= ui.add(egui::DragValue::new(&mut anim.start)).changed();
+                    changed |= ui.add(egui::DragValue::new(&mut anim.end)).changed();
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut anim.frame_millis).suffix("ms"))
+                        .changed();
+                });
+            }
+        });
+
+    changed
+}
+
+fn drag(ui: &mut egui::Ui, label: &str, value: &mut f32, speed: f32) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(value).speed(speed)).changed()
+    })
+    .inner
+}
+
+fn vec2_ui(ui: &mut egui::Ui, value: &mut Vec2) -> bool {
+    ui.horizontal(|ui| {
+        let x = ui.add(egui::DragValue::new(&mut value.x).speed(0.01));
+        let y = ui.add(egui::DragValue::new(&mut value.y).speed(0.01));
+        x.changed() || y.changed()
+    })
+    .inner
+}
+
+fn apply_enemy_def(def: &de::Enemy, enemy: &mut Enemy, animations: &mut Assets<Animation>) {
+    enemy.size = def.size;
+    enemy.collider = def.collider.shape.into();
+    enemy.collider_offset = def.collider.offset;
+    enemy.movement = def.movement.into();
+    enemy.ai = def.ai.into();
+
+    for (name, anim) in &def.atlas_animations {
+        if let Some(handle) = enemy.animation(name)
+            && let Err(error) = animations.insert(handle, enemy_animation(anim))
+        {
+            warn!("Failed to update {name:?} animation: {error}");
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn save_manifest(
+    asset_server: &AssetServer,
+    id: AssetId<EnemyManifest>,
+    manifest: &EnemyManifest,
+) -> Result<std::path::PathBuf, BevyError> {
+    use bevy::asset::io::file::FileAssetReader;
+    use serde::Serialize;
+
+    let asset_path = asset_server
+        .get_path(id)
+        .ok_or("manifest was not loaded from a file")?;
+    let path = FileAssetReader::get_base_path()
+        .join("assets")
+        .join(asset_path.path());
+
+    // Match the 4-space indentation of the hand-written manifest.
+    let mut bytes = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+    manifest.source.serialize(&mut serializer)?;
+
+    std::fs::write(&path, bytes)?;
+    Ok(path)
+}
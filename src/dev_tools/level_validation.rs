@@ -0,0 +1,126 @@
+//! Checks loaded levels for mistakes that wouldn't stop them loading, and lists them in an egui
+//! panel along with any levels that failed to load outright.
+//!
+//! Levels are checked whenever they or the enemy manifest are (re)loaded, so fixing a level in
+//! LDtk clears its warnings.
+
+use bevy::{asset::AssetLoadFailedEvent, platform::collections::HashMap, prelude::*};
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{
+    assets::{enemy::EnemyManifest, level::Level, world::LdtkWorld},
+    config::GameConfig,
+    gameplay::level::LevelAssets,
+    validation::{LevelWarning, ValidationContext, validate_level},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelDiagnostics>()
+        .add_systems(
+            Update,
+            (
+                record_load_failures,
+                validate_levels.run_if(
+                    on_message::<AssetEvent<Level>>.or(on_message::<AssetEvent<EnemyManifest>>),
+                ),
+            ),
+        )
+        .add_systems(
+            EguiPrimaryContextPass,
+            level_diagnostics_ui
+                .run_if(|diagnostics: Res<LevelDiagnostics>| !diagnostics.is_empty()),
+        );
+}
+
+#[derive(Resource, Default)]
+struct LevelDiagnostics {
+    /// Warnings for each level that has any, by level name.
+    warnings: HashMap<String, Vec<LevelWarning>>,
+    /// Errors from levels and worlds that failed to load, by asset path.
+    failures: HashMap<String, String>,
+}
+
+impl LevelDiagnostics {
+    fn is_empty(&self) -> bool {
+        self.warnings.is_empty() && self.failures.is_empty()
+    }
+}
+
+fn record_load_failures(
+    mut level_failures: MessageReader<AssetLoadFailedEvent<Level>>,
+    mut world_failures: MessageReader<AssetLoadFailedEvent<LdtkWorld>>,
+    mut diagnostics: ResMut<LevelDiagnostics>,
+) {
+    let failures = level_failures
+        .read()
+        .map(|event| (&event.path, &event.error))
+        .chain(
+            world_failures
+                .read()
+                .map(|event| (&event.path, &event.error)),
+        );
+    for (path, error) in failures {
+        error!("Failed to load level {path}: {error}");
+        diagnostics
+            .failures
+            .insert(path.to_string(), error.to_string());
+    }
+}
+
+fn validate_levels(
+    level_assets: Res<LevelAssets>,
+    config: Res<GameConfig>,
+    manifests: Res<Assets<EnemyManifest>>,
+    worlds: Res<Assets<LdtkWorld>>,
+    levels: Res<Assets<Level>>,
+    asset_server: Res<AssetServer>,
+    mut diagnostics: ResMut<LevelDiagnostics>,
+) {
+    let player_controller = config.player_controller.to_world();
+    let neighbours: HashMap<_, _> = worlds
+        .iter()
+        .flat_map(|(_, world)| &world.levels)
+        .map(|world_level| (world_level.level.id(), world_level.neighbours.as_slice()))
+        .collect();
+
+    diagnostics.warnings.clear();
+    for (id, level) in levels.iter() {
+        let context = ValidationContext {
+            manifest: manifests.get(&level_assets.enemies),
+            player_controller: Some(&player_controller),
+            neighbours: neighbours.get(&id).copied().unwrap_or_default(),
+        };
+        let warnings = validate_level(level, &context);
+        for warning in &warnings {
+            warn!("Level {:?}: {warning}", level.name);
+        }
+        if !warnings.is_empty() {
+            diagnostics.warnings.insert(level.name.clone(), warnings);
+        }
+        // A level that loads again has been fixed.
+        if let Some(path) = asset_server.get_path(id) {
+            diagnostics.failures.remove(&path.to_string());
+        }
+    }
+}
+
+fn level_diagnostics_ui(mut contexts: EguiContexts, diagnostics: Res<LevelDiagnostics>) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    egui::Window::new("Level Diagnostics").show(ctx, |ui| {
+        for (path, error) in &diagnostics.failures {
+            ui.strong(path);
+            ui.colored_label(egui::Color32::RED, error);
+            ui.separator();
+        }
+        for (name, warnings) in &diagnostics.warnings {
+            ui.strong(name);
+            for warning in warnings {
+                ui.colored_label(egui::Color32::YELLOW, warning.to_string());
+            }
+            ui.separator();
+        }
+    });
+
+    Ok(())
+}
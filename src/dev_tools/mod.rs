@@ -0,0 +1,176 @@
+//! Development tools for the game. This plugin is only enabled in dev builds.
+
+use avian2d::prelude::{PhysicsDebugPlugin, PhysicsGizmos};
+use bevy::{
+    dev_tools::states::log_transitions,
+    input::common_conditions::{input_just_pressed, input_toggle_active},
+    prelude::*,
+};
+use bevy_inspector_egui::{
+    bevy_egui::EguiPlugin,
+    quick::{
+        AssetInspectorPlugin, FilterQueryInspectorPlugin, ResourceInspectorPlugin,
+        WorldInspectorPlugin,
+    },
+};
+
+mod drag;
+mod enemy_editor;
+mod level_validation;
+mod physics_recorder;
+mod render_stats;
+#[cfg(feature = "dev_native")]
+mod shader_reload;
+mod system_audit;
+
+use crate::{
+    background::ParallaxMaterial,
+    config::GameConfig,
+    gameplay::{
+        camera::cursor::CursorWorldPosition,
+        combat::explosion::{Explosion, explosion},
+        enemies::EnemyHandle,
+        player::{Player, death::KillPlayer},
+    },
+    physics::{GravityWell, RelativityConfig, SpeedOfLight},
+    screens::Screen,
+};
+
+const INSPECTOR_TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+const UI_DEBUG_TOGGLE_KEY: KeyCode = KeyCode::F1;
+const PHYSICS_DEBUG_TOGGLE_KEY: KeyCode = KeyCode::F2;
+const SPAWN_EXPLOSION_KEY: KeyCode = KeyCode::F4;
+const KILL_PLAYERS_KEY: KeyCode = KeyCode::F11;
+const DESPAWN_ENEMIES_KEY: KeyCode = KeyCode::F12;
+
+pub(super) fn plugin(app: &mut App) {
+    let debug = app.world().resource::<GameConfig>().debug.clone();
+    let show_inspector = || input_toggle_active(debug.show_inspector, INSPECTOR_TOGGLE_KEY);
+
+    // World inspector
+    app.add_plugins((
+        EguiPlugin::default(),
+        WorldInspectorPlugin::default().run_if(show_inspector()),
+        ResourceInspectorPlugin::<SpeedOfLight>::new().run_if(show_inspector()),
+        ResourceInspectorPlugin::<RelativityConfig>::new().run_if(show_inspector()),
+        AssetInspectorPlugin::<ParallaxMaterial>::new().run_if(show_inspector()),
+        FilterQueryInspectorPlugin::<With<Player>>::new().run_if(show_inspector()),
+    ));
+
+    // Enemy editor
+    app.add_plugins(enemy_editor::plugin);
+
+    // Report problems with loaded levels
+    app.add_plugins(level_validation::plugin);
+
+    // Drag bodies around with the mouse
+    app.add_plugins(drag::plugin);
+
+    // Record and draw recent physics states
+    app.add_plugins(physics_recorder::plugin);
+
+    // Count sprites, batches and tilemap chunks drawn each frame
+    app.add_plugins(render_stats::plugin);
+
+    // Roll back shader edits that fail to compile
+    #[cfg(feature = "dev_native")]
+    app.add_plugins(shader_reload::plugin);
+
+    // Make sure gameplay systems stop while paused
+    app.add_plugins(system_audit::plugin);
+
+    // Physics
+    app.add_plugins(PhysicsDebugPlugin)
+        .insert_gizmo_config(
+            PhysicsGizmos {
+                axis_lengths: None,
+                ..default()
+            },
+            GizmoConfig {
+                enabled: debug.show_physics_gizmos,
+                ..default()
+            },
+        )
+        .add_systems(
+            Update,
+            (
+                toggle_physics_gizmos.run_if(input_just_pressed(PHYSICS_DEBUG_TOGGLE_KEY)),
+                draw_gravity_wells,
+            ),
+        );
+
+    // Log `Screen` state transitions.
+    app.add_systems(Update, log_transitions::<Screen>);
+
+    // Toggle the debug overlay for UI.
+    app.add_systems(
+        Update,
+        toggle_debug_ui.run_if(input_just_pressed(UI_DEBUG_TOGGLE_KEY)),
+    );
+
+    // Blow up whatever is under the cursor
+    app.add_systems(
+        Update,
+        spawn_explosion_at_cursor
+            .run_if(in_state(Screen::Gameplay).and(input_just_pressed(SPAWN_EXPLOSION_KEY))),
+    );
+
+    // Kill all players
+    app.add_systems(
+        Update,
+        kill_all_players.run_if(input_just_pressed(KILL_PLAYERS_KEY)),
+    );
+
+    // Kill all enemies
+    app.add_systems(
+        Update,
+        despawn_all_enemies.run_if(input_just_pressed(DESPAWN_ENEMIES_KEY)),
+    );
+}
+
+fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
+    options.toggle();
+}
+
+fn toggle_physics_gizmos(mut store: ResMut<GizmoConfigStore>) {
+    let (config, _) = store.config_mut::<PhysicsGizmos>();
+    config.enabled = !config.enabled;
+}
+
+/// Draws rings at the edge of each [`GravityWell`]'s reach and where its pull is 4x and 16x as
+/// strong.
+fn draw_gravity_wells(
+    wells: Query<(&GravityWell, &GlobalTransform)>,
+    mut gizmos: Gizmos<PhysicsGizmos>,
+) {
+    for (well, transform) in &wells {
+        let center = transform.translation().xy();
+        for (fraction, alpha) in [(1.0, 0.3), (0.5, 0.6), (0.25, 1.0)] {
+            gizmos.circle_2d(
+                center,
+                well.radius * fraction,
+                Color::srgb(0.6, 0.4, 0.9).with_alpha(alpha),
+            );
+        }
+    }
+}
+
+fn spawn_explosion_at_cursor(cursor: Res<CursorWorldPosition>, mut commands: Commands) {
+    let Some(position) = cursor.0 else {
+        return;
+    };
+
+    commands.spawn(explosion(position, Explosion::new(3.0, 1.0, 5.0)));
+}
+
+fn kill_all_players(players: Query<Entity, With<Player>>, mut commands: Commands) {
+    for player in &players {
+        commands.trigger(KillPlayer { player });
+    }
+}
+
+fn despawn_all_enemies(enemies: Query<Entity, With<EnemyHandle>>, mut commands: Commands) {
+    for enemy in &enemies {
+        commands.entity(enemy).try_despawn();
+    }
+}
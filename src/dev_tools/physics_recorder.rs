@@ -0,0 +1,208 @@
+//! Records the recent physics state of chosen bodies and draws it as trails, to look into
+//! tunneling, jump arcs and length contraction glitches after they happen.
+//!
+//! Players are recorded from the start. Other bodies are added or removed by pointing at them and
+//! pressing [`RECORD_TOGGLE_KEY`]. Pausing the game freezes the recording, since it is sampled on
+//! the fixed timestep.
+
+use std::collections::VecDeque;
+
+use avian2d::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    gameplay::{camera::cursor::CursorWorldPosition, player::Player},
+    physics::LorentzFactor,
+    screens::Screen,
+};
+
+const TRAILS_TOGGLE_KEY: KeyCode = KeyCode::F6;
+const RECORD_TOGGLE_KEY: KeyCode = KeyCode::F7;
+
+/// How far back recordings go, in seconds.
+const RECORD_SECS: f32 = 5.0;
+/// How often trails are labeled with the age of the sample, in seconds.
+const LABEL_INTERVAL_SECS: f32 = 0.5;
+/// Velocities are drawn at every nth sample, scaled down by this much.
+const VELOCITY_STRIDE: usize = 8;
+const VELOCITY_SCALE: f32 = 0.1;
+/// The Lorentz factor drawn fully red.
+const MAX_GAMMA: f32 = 3.0;
+const LABEL_FONT_SIZE: f32 = 24.0;
+/// Labels are laid out at [`LABEL_FONT_SIZE`] and scaled down to a fraction of a tile.
+const LABEL_SCALE: f32 = 1.0 / 64.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TrailLabels>()
+        .add_observer(record_players)
+        .add_systems(
+            FixedPostUpdate,
+            record_physics_samples.after(PhysicsSystems::Writeback),
+        )
+        .add_systems(
+            Update,
+            (
+                toggle_trails.run_if(input_just_pressed(TRAILS_TOGGLE_KEY)),
+                toggle_recording_at_cursor
+                    .run_if(in_state(Screen::Gameplay).and(input_just_pressed(RECORD_TOGGLE_KEY))),
+                draw_physics_trails,
+            )
+                .chain(),
+        );
+}
+
+/// The recent physics state of a body, oldest first.
+#[derive(Component, Default)]
+struct PhysicsRecording(VecDeque<PhysicsSample>);
+
+struct PhysicsSample {
+    /// Fixed time elapsed when the sample was taken, in seconds.
+    time: f32,
+    position: Vec2,
+    velocity: Vec2,
+    gamma: Vec2,
+}
+
+/// Whether trails are drawn, and the labels pooled for them.
+#[derive(Resource, Default)]
+struct TrailLabels {
+    visible: bool,
+    labels: Vec<Entity>,
+}
+
+fn record_players(add: On<Add, Player>, mut commands: Commands) {
+    commands
+        .entity(add.entity)
+        .insert(PhysicsRecording::default());
+}
+
+fn record_physics_samples(
+    time: Res<Time>,
+    mut bodies: Query<(
+        &mut PhysicsRecording,
+        &Position,
+        &LinearVelocity,
+        Option<&LorentzFactor>,
+    )>,
+) {
+    let now = time.elapsed_secs();
+    for (mut recording, position, velocity, gamma) in &mut bodies {
+        recording.0.push_back(PhysicsSample {
+            time: now,
+            position: position.0,
+            velocity: velocity.0,
+            gamma: gamma.map_or(Vec2::ONE, |g| g.0),
+        });
+        while recording
+            .0
+            .front()
+            .is_some_and(|sample| now - sample.time > RECORD_SECS)
+        {
+            recording.0.pop_front();
+        }
+    }
+}
+
+fn toggle_trails(mut trails: ResMut<TrailLabels>) {
+    trails.visible = !trails.visible;
+}
+
+fn toggle_recording_at_cursor(
+    cursor: Res<CursorWorldPosition>,
+    spatial_query: SpatialQuery,
+    colliders: Query<&ColliderOf>,
+    recordings: Query<Has<PhysicsRecording>, With<RigidBody>>,
+    mut commands: Commands,
+) {
+    let Some(cursor) = cursor.0 else {
+        return;
+    };
+
+    let Some((body, recorded)) = spatial_query
+        .point_intersections(cursor, &SpatialQueryFilter::default())
+        .into_iter()
+        .map(|collider| colliders.get(collider).map_or(collider, |c| c.body))
+        .find_map(|body| Some((body, recordings.get(body).ok()?)))
+    else {
+        return;
+    };
+
+    if recorded {
+        commands.entity(body).remove::<PhysicsRecording>();
+    } else {
+        commands.entity(body).insert(PhysicsRecording::default());
+    }
+}
+
+fn draw_physics_trails(
+    trails: ResMut<TrailLabels>,
+    recordings: Query<&PhysicsRecording>,
+    mut labels: Query<(&mut Text2d, &mut Transform, &mut Visibility)>,
+    fixed_time: Res<Time<Fixed>>,
+    mut gizmos: Gizmos,
+    mut commands: Commands,
+) {
+    let trails = trails.into_inner();
+    let now = fixed_time.elapsed_secs();
+    let mut marks = Vec::new();
+
+    if trails.visible {
+        for recording in &recordings {
+            let samples = &recording.0;
+            for (a, b) in samples.iter().zip(samples.iter().skip(1)) {
+                // Whiter for slower, redder for more contracted
+                let gamma = b.gamma.max_element();
+                let t = ((gamma - 1.0) / (MAX_GAMMA - 1.0)).clamp(0.0, 1.0);
+                let color = Color::WHITE.mix(&Color::srgb(1.0, 0.1, 0.1), t);
+                gizmos.line_2d(a.position, b.position, color);
+
+                // Mark each interval the trail crosses
+                let interval = (b.time / LABEL_INTERVAL_SECS).floor();
+                if interval > (a.time / LABEL_INTERVAL_SECS).floor() {
+                    gizmos.circle_2d(b.position, 0.1, color);
+                    marks.push((
+                        b.position,
+                        format!("-{:.1}s  γ{gamma:.2}", now - interval * LABEL_INTERVAL_SECS),
+                    ));
+                }
+            }
+            for sample in samples.iter().step_by(VELOCITY_STRIDE) {
+                gizmos.arrow_2d(
+                    sample.position,
+                    sample.position + sample.velocity * VELOCITY_SCALE,
+                    Color::srgb(0.3, 0.8, 1.0),
+                );
+            }
+        }
+    }
+
+    // Labels are spawned as needed and hidden when unused
+    while trails.labels.len() < marks.len() {
+        trails.labels.push(
+            commands
+                .spawn((
+                    Name::new("Physics Trail Label"),
+                    Text2d::default(),
+                    TextFont::from_font_size(LABEL_FONT_SIZE),
+                    Transform::from_scale(Vec3::splat(LABEL_SCALE)),
+                    Visibility::Hidden,
+                ))
+                .id(),
+        );
+    }
+    for (i, &label) in trails.labels.iter().enumerate() {
+        let Ok((mut text, mut transform, mut visibility)) = labels.get_mut(label) else {
+            continue;
+        };
+        match marks.get(i) {
+            Some((position, mark)) => {
+                text.0.clone_from(mark);
+                transform.translation = (position + Vec2::new(0.0, 0.3)).extend(10.0);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
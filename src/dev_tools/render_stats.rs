@@ -0,0 +1,119 @@
+//! A panel counting what gets drawn each frame, to measure the effect of batching and culling.
+//!
+//! Sprites are batched by image in draw order, so every change of image between sprites sorted by
+//! depth is counted as a batch break.
+
+use bevy::{
+    camera::visibility::VisibilitySystems,
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, RegisterDiagnostic},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    sprite_render::TilemapChunk,
+};
+
+const RENDER_STATS_TOGGLE_KEY: KeyCode = KeyCode::F5;
+
+const SPRITES: DiagnosticPath = DiagnosticPath::const_new("render/sprites");
+const SPRITE_BATCHES: DiagnosticPath = DiagnosticPath::const_new("render/sprite_batches");
+const TILEMAP_CHUNKS: DiagnosticPath = DiagnosticPath::const_new("render/tilemap_chunks");
+const MESHES: DiagnosticPath = DiagnosticPath::const_new("render/meshes_2d");
+
+const STATS: [(&str, DiagnosticPath); 4] = [
+    ("Sprites", SPRITES),
+    ("Sprite batches", SPRITE_BATCHES),
+    ("Tilemap chunks", TILEMAP_CHUNKS),
+    ("2D meshes", MESHES),
+];
+
+pub(super) fn plugin(app: &mut App) {
+    for (_, path) in STATS {
+        app.register_diagnostic(Diagnostic::new(path));
+    }
+
+    app.add_systems(Startup, spawn_render_stats_panel);
+    app.add_systems(
+        Update,
+        (
+            toggle_render_stats.run_if(input_just_pressed(RENDER_STATS_TOGGLE_KEY)),
+            update_render_stats_panel,
+        ),
+    );
+    app.add_systems(
+        PostUpdate,
+        measure_render_stats.after(VisibilitySystems::CheckVisibility),
+    );
+}
+
+#[derive(Component)]
+struct RenderStatsPanel;
+
+fn spawn_render_stats_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Render Stats"),
+        RenderStatsPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(8),
+            right: px(8),
+            padding: UiRect::all(px(6)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        Text::default(),
+        TextFont::from_font_size(14.0),
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_render_stats(mut panel: Single<&mut Visibility, With<RenderStatsPanel>>) {
+    panel.toggle_visible_hidden();
+}
+
+fn measure_render_stats(
+    sprites: Query<(&Sprite, &GlobalTransform, &ViewVisibility)>,
+    tilemap_chunks: Query<&ViewVisibility, With<TilemapChunk>>,
+    meshes: Query<&ViewVisibility, With<Mesh2d>>,
+    mut diagnostics: Diagnostics,
+) {
+    let mut visible_sprites: Vec<_> = sprites
+        .iter()
+        .filter(|(.., visibility)| visibility.get())
+        .map(|(sprite, transform, _)| (transform.translation().z, sprite.image.id()))
+        .collect();
+    visible_sprites.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let sprite_batches = visible_sprites.chunk_by(|a, b| a.1 == b.1).count();
+    let visible = |visibility: &ViewVisibility| visibility.get();
+
+    diagnostics.add_measurement(&SPRITES, || visible_sprites.len() as f64);
+    diagnostics.add_measurement(&SPRITE_BATCHES, || sprite_batches as f64);
+    diagnostics.add_measurement(&TILEMAP_CHUNKS, || {
+        tilemap_chunks.iter().filter(|v| visible(v)).count() as f64
+    });
+    diagnostics.add_measurement(&MESHES, || {
+        meshes.iter().filter(|v| visible(v)).count() as f64
+    });
+}
+
+fn update_render_stats_panel(
+    store: Res<DiagnosticsStore>,
+    panel: Single<(&mut Text, &Visibility), With<RenderStatsPanel>>,
+) {
+    let (mut text, visibility) = panel.into_inner();
+    if visibility == Visibility::Hidden {
+        return;
+    }
+
+    text.0 = STATS
+        .iter()
+        .map(|(label, path)| {
+            let value = store
+                .get(path)
+                .and_then(Diagnostic::smoothed)
+                .unwrap_or_default();
+            format!("{label}: {value:.0}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
@@ -0,0 +1,196 @@
+//! Safer shader hot reloading.
+//!
+//! Pipeline compile errors are collected from the render world and listed in an egui panel. When
+//! a reloaded shader breaks a pipeline, the last version that compiled is restored so the game
+//! keeps drawing while the file is fixed.
+
+use std::time::Duration;
+
+use bevy::{
+    platform::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+    },
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSystems,
+        render_resource::{CachedPipelineState, PipelineCache, PipelineDescriptor},
+    },
+    shader::Shader,
+};
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// How long a reloaded shader must go without breaking a pipeline to count as good.
+const CONFIRM_DELAY: Duration = Duration::from_secs(1);
+
+pub(super) fn plugin(app: &mut App) {
+    let errors = PipelineErrors::default();
+    app.insert_resource(errors.clone())
+        .init_resource::<ShaderFallback>()
+        .add_systems(
+            Update,
+            (
+                track_shader_versions.run_if(on_message::<AssetEvent<Shader>>),
+                fall_back_on_errors,
+            )
+                .chain(),
+        )
+        .add_systems(
+            EguiPrimaryContextPass,
+            shader_errors_ui.run_if(|fallback: Res<ShaderFallback>| !fallback.failures.is_empty()),
+        );
+
+    if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app
+            .insert_resource(errors)
+            .add_systems(Render, collect_pipeline_errors.after(RenderSystems::Render));
+    }
+}
+
+/// A pipeline that failed to compile, and the shaders it was built from.
+struct PipelineError {
+    shaders: Vec<AssetId<Shader>>,
+    message: String,
+}
+
+/// The pipelines currently failing to compile, shared between the main and render worlds.
+#[derive(Resource, Clone, Default)]
+struct PipelineErrors(Arc<Mutex<Vec<PipelineError>>>);
+
+#[derive(Resource, Default)]
+struct ShaderFallback {
+    /// The last version of each shader that didn't break any pipelines.
+    last_good: HashMap<AssetId<Shader>, Shader>,
+    /// Reloaded shaders that haven't proven themselves yet, and when they were reloaded.
+    unconfirmed: HashMap<AssetId<Shader>, Duration>,
+    /// Shaders being restored to their last good version, whose next reload should be ignored.
+    restoring: HashSet<AssetId<Shader>>,
+    /// Errors from reloads that were rolled back, shown until the shader is fixed.
+    failures: HashMap<AssetId<Shader>, String>,
+}
+
+fn collect_pipeline_errors(pipeline_cache: Res<PipelineCache>, errors: Res<PipelineErrors>) {
+    let current = pipeline_cache
+        .pipelines()
+        .filter_map(|pipeline| {
+            let CachedPipelineState::Err(error) = &pipeline.state else {
+                return None;
+            };
+            let (label, shaders) = match &pipeline.descriptor {
+                PipelineDescriptor::RenderPipelineDescriptor(descriptor) => (
+                    &descriptor.label,
+                    std::iter::once(descriptor.vertex.shader.id())
+                        .chain(descriptor.fragment.as_ref().map(|f| f.shader.id()))
+                        .collect(),
+                ),
+                PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                    (&descriptor.label, vec![descriptor.shader.id()])
+                }
+            };
+            Some(PipelineError {
+                shaders,
+                message: format!(
+                    "{}: {error}",
+                    label.as_deref().unwrap_or("unnamed pipeline")
+                ),
+            })
+        })
+        .collect();
+
+    *errors.0.lock().unwrap() = current;
+}
+
+fn track_shader_versions(
+    mut events: MessageReader<AssetEvent<Shader>>,
+    shaders: Res<Assets<Shader>>,
+    time: Res<Time<Real>>,
+    mut fallback: ResMut<ShaderFallback>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Added { id } => {
+                if let Some(shader) = shaders.get(id) {
+                    fallback.last_good.insert(id, shader.clone());
+                }
+            }
+            AssetEvent::Modified { id } => {
+                if !fallback.restoring.remove(&id) {
+                    fallback.unconfirmed.insert(id, time.elapsed());
+                }
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                fallback.last_good.remove(&id);
+                fallback.unconfirmed.remove(&id);
+                fallback.failures.remove(&id);
+            }
+            AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+}
+
+fn fall_back_on_errors(
+    errors: Res<PipelineErrors>,
+    time: Res<Time<Real>>,
+    mut shaders: ResMut<Assets<Shader>>,
+    mut fallback: ResMut<ShaderFallback>,
+) {
+    let fallback = &mut *fallback;
+    let errors = errors.0.lock().unwrap();
+
+    // Roll back reloads that broke a pipeline
+    for error in errors.iter() {
+        for id in &error.shaders {
+            if fallback.unconfirmed.remove(id).is_none() {
+                continue;
+            }
+            let Some(last_good) = fallback.last_good.get(id) else {
+                continue;
+            };
+            error!(
+                "Shader reload failed, restoring the last good version. {}",
+                error.message
+            );
+            fallback.failures.insert(*id, error.message.clone());
+            fallback.restoring.insert(*id);
+            let _ = shaders.insert(*id, last_good.clone());
+        }
+    }
+
+    // Keep reloads that have compiled cleanly for long enough
+    let now = time.elapsed();
+    let confirmed: Vec<_> = fallback
+        .unconfirmed
+        .iter()
+        .filter(|&(_, &reloaded_at)| now - reloaded_at >= CONFIRM_DELAY)
+        .map(|(&id, _)| id)
+        .collect();
+    for id in confirmed {
+        fallback.unconfirmed.remove(&id);
+        fallback.failures.remove(&id);
+        if let Some(shader) = shaders.get(id) {
+            fallback.last_good.insert(id, shader.clone());
+        }
+    }
+}
+
+fn shader_errors_ui(
+    mut contexts: EguiContexts,
+    fallback: Res<ShaderFallback>,
+    asset_server: Res<AssetServer>,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+
+    egui::Window::new("Shader Errors").show(ctx, |ui| {
+        ui.label("These reloads failed to compile. The last good versions are in use.");
+        for (id, message) in &fallback.failures {
+            let path = asset_server
+                .get_path(*id)
+                .map_or_else(|| format!("{id:?}"), |path| path.to_string());
+            ui.separator();
+            ui.strong(path);
+            ui.colored_label(egui::Color32::RED, message);
+        }
+    });
+
+    Ok(())
+}
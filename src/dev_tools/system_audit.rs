@@ -0,0 +1,51 @@
+//! A startup check that gameplay systems can be paused.
+//!
+//! A gameplay system added to `Update` without [`PausableSystems`] keeps running while the game is
+//! paused, which is easy to miss. Once the `Update` schedule has been built, this looks for
+//! systems from the gameplay modules that aren't in the set and fails a debug assertion listing
+//! them.
+
+use bevy::prelude::*;
+
+use crate::PausableSystems;
+
+/// Module paths whose `Update` systems must be pausable.
+const GAMEPLAY_MODULES: &[&str] = &[
+    "bevy_jam_7::controller::",
+    "bevy_jam_7::gameplay::",
+    "bevy_jam_7::physics::",
+];
+
+pub(super) fn plugin(app: &mut App) {
+    // `Update` is built the first time it runs, so check afterwards.
+    app.add_systems(Last, audit_pausable_systems.run_if(run_once));
+}
+
+fn audit_pausable_systems(schedules: Res<Schedules>) {
+    let Some(update) = schedules.get(Update) else {
+        return;
+    };
+    let (Ok(pausable), Ok(systems)) = (
+        update.graph().systems_in_set(PausableSystems.intern()),
+        update.systems(),
+    ) else {
+        warn!("Couldn't audit the `Update` schedule before it was built");
+        return;
+    };
+
+    let unpausable = systems
+        .filter(|(key, _)| !pausable.contains(key))
+        .map(|(_, system)| system.name().to_string())
+        .filter(|name| {
+            GAMEPLAY_MODULES
+                .iter()
+                .any(|module| name.starts_with(module))
+        })
+        .collect::<Vec<_>>();
+
+    debug_assert!(
+        unpausable.is_empty(),
+        "These gameplay systems aren't in `PausableSystems`, so they'll run while paused:\n{}",
+        unpausable.join("\n"),
+    );
+}
@@ -0,0 +1,42 @@
+//! The world position under the mouse cursor, for aiming and dev tools.
+
+use bevy::{camera::ScalingMode, input::InputSystems, prelude::*, window::PrimaryWindow};
+
+use crate::gameplay::camera::PlayerCamera;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CursorWorldPosition>()
+        .add_systems(PreUpdate, update_cursor_world_position.after(InputSystems));
+}
+
+/// Where the cursor is in the world, as seen by the [`PlayerCamera`]. `None` while the cursor is
+/// outside the window.
+#[derive(Resource, Reflect, Default, Deref, Debug, Clone, Copy)]
+#[reflect(Resource)]
+pub struct CursorWorldPosition(pub Option<Vec2>);
+
+fn update_cursor_world_position(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &Projection, &GlobalTransform), With<PlayerCamera>>,
+    mut cursor: ResMut<CursorWorldPosition>,
+) {
+    let (camera, projection, transform) = camera.into_inner();
+    cursor.0 = window
+        .cursor_position()
+        .and_then(|position| match projection {
+            // Length contraction rescales the projection on fixed updates, but the camera only
+            // recomputes its matrices in `PostUpdate`, so `viewport_to_world_2d` would be a frame
+            // behind. Work from the projection itself instead.
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed { width, height },
+                scale,
+                viewport_origin,
+                ..
+            }) => {
+                let uv = position / window.size();
+                let local = (uv - *viewport_origin) * Vec2::new(*width, -*height) * *scale;
+                Some(transform.transform_point(local.extend(0.0)).xy())
+            }
+            _ => camera.viewport_to_world_2d(transform, position).ok(),
+        });
+}
@@ -0,0 +1,174 @@
+//! The camera that follows the player, and effects applied to it.
+//!
+//! The camera doesn't stay locked to the player. Its [`CameraRig`] lets the player move around a
+//! deadzone without moving the camera, only follows jumps once the player lands on a new
+//! platform, looks ahead in the direction the player is running, and eases into place rather
+//! than snapping. The [`zone`] module keeps it inside the level, and lets areas of the level
+//! change its zoom or lock it in place.
+
+pub mod cursor;
+pub mod shake;
+pub mod zone;
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    controller::GroundNormal,
+    gameplay::{
+        camera::{shake::CameraShake, zone::CameraConstraints},
+        player::Player,
+    },
+    math::exp_smooth,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((cursor::plugin, shake::plugin, zone::plugin));
+
+    // Update camera position
+    app.add_systems(
+        PostUpdate,
+        update_player_camera_position.before(TransformSystems::Propagate),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+#[require(CameraShake, CameraRig, CameraConstraints)]
+pub struct PlayerCamera;
+
+/// How the [`PlayerCamera`] follows the player. Distances are in world units, and rates are per
+/// second as in [`exp_smooth`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct CameraRig {
+    /// Half the size of the box around the camera's focus that the player can move within
+    /// without moving the focus.
+    pub deadzone: Vec2,
+    /// How far ahead the camera looks, as seconds of the player's horizontal velocity.
+    pub lookahead_secs: f32,
+    pub max_lookahead: f32,
+    /// How quickly the lookahead catches up when the player changes speed or direction.
+    pub lookahead_rate: f32,
+    /// How quickly the camera eases towards its focus, horizontally and vertically.
+    pub damping: Vec2,
+    /// Whether the focus only moves vertically when the player lands on a platform, or leaves the
+    /// deadzone. Otherwise it follows every jump.
+    pub platform_snapping: bool,
+    /// How far the player can be from the camera before it jumps straight to them, such as after
+    /// spawning in a new level.
+    pub snap_distance: f32,
+    /// How quickly the zoom eases towards the one set by the [`CameraConstraints`].
+    pub zoom_rate: f32,
+    /// Where the camera is trying to center, before lookahead. `None` until there's a player.
+    focus: Option<Vec2>,
+    lookahead: f32,
+    zoom: f32,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            deadzone: Vec2::new(1.0, 2.5),
+            lookahead_secs: 0.25,
+            max_lookahead: 4.0,
+            lookahead_rate: 2.0,
+            damping: Vec2::new(8.0, 4.0),
+            platform_snapping: true,
+            snap_distance: 30.0,
+            zoom_rate: 2.0,
+            focus: None,
+            lookahead: 0.0,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl CameraRig {
+    /// How far the camera is zoomed out, on top of the projection's own scale. The
+    /// [`physics`](crate::physics) module applies it to the projection along with length
+    /// contraction.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Moves the focus to keep a player at `position` framed, and returns where the camera should
+    /// be after `dt` seconds, starting from `camera`. The camera stays within the `constraints`
+    /// for a view `half_size` across.
+    fn update(
+        &mut self,
+        camera: Vec2,
+        position: Vec2,
+        velocity: Vec2,
+        grounded: bool,
+        constraints: &CameraConstraints,
+        half_size: Vec2,
+        dt: f32,
+    ) -> Vec2 {
+        self.zoom = exp_smooth(self.zoom, constraints.zoom, self.zoom_rate, dt);
+
+        let Some(mut focus) = self
+            .focus
+            .filter(|_| camera.distance(position) < self.snap_distance)
+        else {
+            self.focus = Some(position);
+            self.lookahead = 0.0;
+            return constraints.apply(position, half_size);
+        };
+
+        focus.x = focus
+            .x
+            .clamp(position.x - self.deadzone.x, position.x + self.deadzone.x);
+        if grounded || !self.platform_snapping {
+            focus.y = position.y;
+        }
+        focus.y = focus
+            .y
+            .clamp(position.y - self.deadzone.y, position.y + self.deadzone.y);
+        self.focus = Some(focus);
+
+        let lookahead =
+            (velocity.x * self.lookahead_secs).clamp(-self.max_lookahead, self.max_lookahead);
+        self.lookahead = exp_smooth(self.lookahead, lookahead, self.lookahead_rate, dt);
+
+        let target = constraints.apply(focus + Vec2::X * self.lookahead, half_size);
+        Vec2::new(
+            exp_smooth(camera.x, target.x, self.damping.x, dt),
+            exp_smooth(camera.y, target.y, self.damping.y, dt),
+        )
+    }
+}
+
+pub fn update_player_camera_position(
+    time: Res<Time>,
+    player: Single<
+        (&GlobalTransform, &LinearVelocity, &GroundNormal),
+        (With<Player>, Without<PlayerCamera>),
+    >,
+    camera: Single<
+        (
+            &mut CameraRig,
+            &CameraConstraints,
+            &Projection,
+            &mut Transform,
+        ),
+        (With<PlayerCamera>, Without<Player>),
+    >,
+) {
+    let (player, velocity, ground) = player.into_inner();
+    let (mut rig, constraints, projection, mut transform) = camera.into_inner();
+    let half_size = match projection {
+        Projection::Orthographic(projection) => projection.area.half_size(),
+        _ => Vec2::ZERO,
+    };
+    let position = rig.update(
+        transform.translation.xy(),
+        player.translation().xy(),
+        velocity.0,
+        ground.is_grounded(),
+        constraints,
+        half_size,
+        time.delta_secs(),
+    );
+    transform.translation = position.extend(transform.translation.z);
+}
@@ -0,0 +1,71 @@
+//! Screen shake for the [`PlayerCamera`].
+//!
+//! The camera's position is set by whichever system is following something, so the shake is
+//! added on top of that late in [`PostUpdate`] and taken off again at the start of the next frame.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::gameplay::{
+    camera::{PlayerCamera, update_player_camera_position},
+    player::spectator::follow_spectator_target,
+};
+
+/// The offset of the camera at full trauma, in world units.
+const MAX_SHAKE_OFFSET: f32 = 0.6;
+/// How much trauma is lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(PreUpdate, remove_camera_shake);
+    app.add_systems(
+        PostUpdate,
+        apply_camera_shake
+            .after(update_player_camera_position)
+            .after(follow_spectator_target)
+            .before(TransformSystems::Propagate),
+    );
+}
+
+/// Shakes the camera it's on. Add trauma with [`add_trauma`]; it wears off over time.
+///
+/// [`add_trauma`]: Self::add_trauma
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct CameraShake {
+    /// Between `0.0` and `1.0`. The shake grows with the square of this, so small amounts of
+    /// trauma are subtle.
+    pub trauma: f32,
+    offset: Vec2,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+fn remove_camera_shake(mut cameras: Query<(&mut CameraShake, &mut Transform), With<PlayerCamera>>) {
+    for (mut shake, mut transform) in &mut cameras {
+        transform.translation -= shake.offset.extend(0.0);
+        shake.offset = Vec2::ZERO;
+    }
+}
+
+fn apply_camera_shake(
+    time: Res<Time>,
+    mut cameras: Query<(&mut CameraShake, &mut Transform), With<PlayerCamera>>,
+) {
+    let rng = &mut rand::rng();
+    for (mut shake, mut transform) in &mut cameras {
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+
+        let strength = MAX_SHAKE_OFFSET * shake.trauma.powi(2);
+        shake.offset =
+            strength * Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0));
+        shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_secs()).max(0.0);
+        transform.translation += shake.offset.extend(0.0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Limits on the [`PlayerCamera`], from the current level and camera zones placed in LDtk.
+//!
+//! The camera's view is kept inside the bounds of the [`CurrentLevel`]. A `Camera_Zone` can also
+//! zoom the camera in or out while a player is inside it, and lock it to the zone's center on
+//! either axis, which suits arenas like boss fights.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    assets::level::{LdtkEntitySpawn, Level, RegisterLdtkEntity},
+    gameplay::{
+        camera::{PlayerCamera, update_player_camera_position},
+        level::CurrentLevel,
+        player::Player,
+    },
+    units::TILE_SIZE,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity("Camera_Zone", camera_zone);
+
+    app.add_systems(
+        PostUpdate,
+        update_camera_constraints.before(update_player_camera_position),
+    );
+}
+
+/// Where the [`PlayerCamera`] can go, and how far it's zoomed. Kept up to date with the current
+/// level and the [`CameraZone`] the player is in.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct CameraConstraints {
+    /// The area the camera's view has to stay within, in world coordinates.
+    pub bounds: Option<Rect>,
+    /// Where the camera is held on each axis, if that axis is locked.
+    pub lock: (Option<f32>, Option<f32>),
+    /// The zoom the camera eases towards. `1.0` is normal, and larger values show more.
+    pub zoom: f32,
+}
+
+impl Default for CameraConstraints {
+    fn default() -> Self {
+        Self {
+            bounds: None,
+            lock: (None, None),
+            zoom: 1.0,
+        }
+    }
+}
+
+impl CameraConstraints {
+    /// Keeps a camera centered at `position` with a view `half_size` across within the bounds,
+    /// and on any locked axes. A view bigger than the bounds is centered on them.
+    pub fn apply(&self, position: Vec2, half_size: Vec2) -> Vec2 {
+        let mut position = position;
+        if let Some(bounds) = self.bounds {
+            let min = bounds.min + half_size;
+            let max = bounds.max - half_size;
+            let center = bounds.center();
+            position = Vec2::new(
+                if min.x <= max.x {
+                    position.x.clamp(min.x, max.x)
+                } else {
+                    center.x
+                },
+                if min.y <= max.y {
+                    position.y.clamp(min.y, max.y)
+                } else {
+                    center.y
+                },
+            );
+        }
+        Vec2::new(
+            self.lock.0.unwrap_or(position.x),
+            self.lock.1.unwrap_or(position.y),
+        )
+    }
+}
+
+/// An area that changes how the [`PlayerCamera`] behaves while a player is inside it.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct CameraZone {
+    /// Half the size of the zone, in world units.
+    pub half_size: Vec2,
+    pub zoom: f32,
+    pub lock_x: bool,
+    pub lock_y: bool,
+}
+
+/// The fields of `Camera_Zone` LDtk entities.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CameraZoneFields {
+    #[serde(default = "CameraZoneFields::default_zoom")]
+    zoom: f32,
+    #[serde(rename = "Lock_X", default)]
+    lock_x: bool,
+    #[serde(rename = "Lock_Y", default)]
+    lock_y: bool,
+}
+
+impl CameraZoneFields {
+    fn default_zoom() -> f32 {
+        1.0
+    }
+}
+
+fn camera_zone(fields: CameraZoneFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    (
+        Name::new("Camera Zone"),
+        CameraZone {
+            half_size: entity.rect.half_size(),
+            zoom: fields.zoom,
+            lock_x: fields.lock_x,
+            lock_y: fields.lock_y,
+        },
+        Transform::from_translation(entity.rect.center().extend(0.0)),
+    )
+}
+
+fn update_camera_constraints(
+    level: Option<Single<(&CurrentLevel, &GlobalTransform)>>,
+    levels: Res<Assets<Level>>,
+    zones: Query<(&CameraZone, &GlobalTransform)>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    mut constraints: Single<&mut CameraConstraints, With<PlayerCamera>>,
+) {
+    let bounds = level.and_then(|level| {
+        let (handle, transform) = level.into_inner();
+        let level = levels.get(&**handle)?;
+        let min = transform.translation().xy();
+        Some(Rect::from_corners(
+            min,
+            min + level.grid_size.as_vec2() * TILE_SIZE.0,
+        ))
+    });
+
+    // Zones only apply while there's a player in them to follow.
+    let zone = player.and_then(|player| {
+        let position = player.translation().xy();
+        zones.iter().find(|(zone, transform)| {
+            let offset = position - transform.translation().xy();
+            offset.abs().cmple(zone.half_size).all()
+        })
+    });
+    let (lock, zoom) = match zone {
+        Some((zone, transform)) => {
+            let center = transform.translation().xy();
+            (
+                (
+                    zone.lock_x.then_some(center.x),
+                    zone.lock_y.then_some(center.y),
+                ),
+                zone.zoom,
+            )
+        }
+        None => ((None, None), 1.0),
+    };
+
+    constraints.bounds = bounds;
+    constraints.lock = lock;
+    constraints.zoom = zoom;
+}
@@ -0,0 +1,209 @@
+//! Explosions that hurt and push everything nearby.
+//!
+//! Spawn one with [`explosion`]; it goes off immediately, damaging anything with [`Health`] and
+//! knocking bodies away with a falloff towards the edge of its radius. Explosions given a sound
+//! play it from their center.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    audio::spatial_sound_effect,
+    controller::CharacterController,
+    gameplay::{
+        camera::shake::CameraShake,
+        combat::{DamageEvent, Health},
+    },
+    math::exp_smooth,
+    screens::Screen,
+};
+
+const SPARK_COLOR: Color = Color::srgb(1.0, 0.65, 0.2);
+const SPARK_SIZE: f32 = 0.2;
+const SPARK_LIFETIME_SECS: f32 = 0.5;
+const SPARK_DRAG: f32 = 4.0;
+const SPARK_COUNT: usize = 24;
+/// Camera trauma added by an explosion with a radius of one unit. Bigger explosions shake more.
+const TRAUMA_PER_RADIUS: f32 = 0.2;
+const SOUND_VOLUME: f32 = 0.8;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(detonate);
+    app.add_systems(
+        Update,
+        (
+            tick_explosions.in_set(AppSystems::TickTimers),
+            update_sparks.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// An explosion centered on the entity. Its sparks are spawned as children, and it despawns once
+/// they've burnt out.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Explosion {
+    pub radius: f32,
+    /// The damage dealt at the center.
+    pub damage: f32,
+    /// The impulse applied to bodies at the center.
+    pub impulse: f32,
+    /// Played from the center when the explosion goes off.
+    pub sound: Option<Handle<AudioSource>>,
+    lifetime: Timer,
+}
+
+impl Explosion {
+    pub fn new(radius: f32, damage: f32, impulse: f32) -> Self {
+        Self {
+            radius,
+            damage,
+            impulse,
+            sound: None,
+            lifetime: Timer::from_seconds(SPARK_LIFETIME_SECS, TimerMode::Once),
+        }
+    }
+
+    pub fn with_sound(mut self, sound: Handle<AudioSource>) -> Self {
+        self.sound = Some(sound);
+        self
+    }
+
+    /// How much of the explosion's damage and impulse is felt at `distance` from its center.
+    pub fn falloff(&self, distance: f32) -> f32 {
+        (1.0 - distance / self.radius).clamp(0.0, 1.0)
+    }
+}
+
+/// An [`Explosion`] at `position`.
+pub fn explosion(position: Vec2, explosion: Explosion) -> impl Bundle {
+    (
+        Name::new("Explosion"),
+        explosion,
+        Transform::from_translation(position.extend(2.0)),
+        Visibility::default(),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Spark {
+    velocity: Vec2,
+}
+
+fn detonate(
+    add: On<Add, Explosion>,
+    explosions: Query<(&Explosion, &Transform)>,
+    spatial_query: SpatialQuery,
+    collider_of: Query<&ColliderOf>,
+    transforms: Query<&GlobalTransform>,
+    healths: Query<(), With<Health>>,
+    mut bodies: Query<(Forces, &RigidBody, Has<CharacterController>)>,
+    mut cameras: Query<&mut CameraShake>,
+    mut commands: Commands,
+) {
+    let Ok((explosion, transform)) = explosions.get(add.entity) else {
+        return;
+    };
+    let center = transform.translation.xy();
+
+    // Colliders can be children of their bodies, so hit each body only once.
+    let mut hit = spatial_query
+        .shape_intersections(
+            &Collider::circle(explosion.radius),
+            center,
+            0.0,
+            &SpatialQueryFilter::default(),
+        )
+        .into_iter()
+        .map(|collider| collider_of.get(collider).map_or(collider, |c| c.body))
+        .collect::<Vec<_>>();
+    hit.sort_unstable();
+    hit.dedup();
+
+    for entity in hit {
+        let Ok(target) = transforms.get(entity) else {
+            continue;
+        };
+        let offset = target.translation().xy() - center;
+        let falloff = explosion.falloff(offset.length());
+        if falloff <= 0.0 {
+            continue;
+        }
+
+        if healths.contains(entity) {
+            commands.trigger(DamageEvent {
+                target: entity,
+                amount: explosion.damage * falloff,
+                // Bodies are pushed by the blast below instead.
+                knockback: Vec2::ZERO,
+            });
+        }
+
+        if let Ok((mut forces, rigid_body, is_character)) = bodies.get_mut(entity)
+            && (rigid_body.is_dynamic() || is_character)
+        {
+            // Push straight up when right on top of the explosion.
+            let direction = offset.try_normalize().unwrap_or(Vec2::Y);
+            forces.apply_linear_impulse(direction * explosion.impulse * falloff);
+        }
+    }
+
+    for mut shake in &mut cameras {
+        shake.add_trauma(TRAUMA_PER_RADIUS * explosion.radius);
+    }
+
+    // Not a child, so the sound outlasts the sparks.
+    if let Some(sound) = &explosion.sound {
+        commands.spawn(spatial_sound_effect(sound.clone(), SOUND_VOLUME, center));
+    }
+
+    let rng = &mut rand::rng();
+    commands.entity(add.entity).with_children(|parent| {
+        for _ in 0..SPARK_COUNT {
+            let direction = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+            let speed = rng.random_range(0.5..1.5) * explosion.radius * SPARK_DRAG;
+            parent.spawn((
+                Name::new("Spark"),
+                Spark {
+                    velocity: direction * speed,
+                },
+                Sprite::from_color(SPARK_COLOR, Vec2::splat(SPARK_SIZE)),
+                Transform::default(),
+            ));
+        }
+    });
+}
+
+fn tick_explosions(
+    time: Res<Time>,
+    mut explosions: Query<(Entity, &mut Explosion)>,
+    mut commands: Commands,
+) {
+    for (entity, mut explosion) in &mut explosions {
+        if explosion.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn update_sparks(
+    time: Res<Time>,
+    explosions: Query<&Explosion>,
+    mut sparks: Query<(&ChildOf, &mut Spark, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+    for (child_of, mut spark, mut transform, mut sprite) in &mut sparks {
+        spark.velocity = exp_smooth(spark.velocity, Vec2::ZERO, SPARK_DRAG, dt);
+        transform.translation += (spark.velocity * dt).extend(0.0);
+
+        if let Ok(explosion) = explosions.get(child_of.parent()) {
+            sprite.color = SPARK_COLOR.with_alpha(1.0 - explosion.lifetime.fraction());
+        }
+    }
+}
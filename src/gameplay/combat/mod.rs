@@ -0,0 +1,355 @@
+//! Health, damage, and death.
+//!
+//! Anything with [`Health`] can be hurt by triggering a [`DamageEvent`] on it. Entities with
+//! [`Damage`] hurt whatever they touch, unless it's a stomper landing on them from above. Damage
+//! that an entity survives triggers [`Hurt`] on it, and can knock it back and stun it.
+//! When an entity's health runs out, [`Died`] is triggered on it; entities with [`DespawnOnDeath`]
+//! are then removed, while others (like the player) handle their own deaths.
+//!
+//! Weapons that fire projectiles and explosions that damage everything nearby live in submodules.
+
+pub mod explosion;
+pub mod projectile;
+pub mod stomp;
+
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    controller::{DashState, Knockback, Stunned},
+    gameplay::combat::stomp::Stomper,
+    screens::Screen,
+};
+
+/// How hard contact damage knocks its target away from the source.
+pub(super) const CONTACT_KNOCKBACK_SPEED: f32 = 8.0;
+/// How long a knocked back target is stunned for.
+const HIT_STUN_SECS: f32 = 0.2;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((explosion::plugin, projectile::plugin, stomp::plugin));
+
+    app.add_observer(apply_damage)
+        .add_observer(deal_contact_damage)
+        .add_observer(despawn_on_death);
+
+    app.add_systems(
+        Update,
+        (tick_invulnerability, tick_hit_flashes)
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (apply_hazards, deal_ongoing_contact_damage)
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Hit points. UI can read [`current`] and [`max`] directly.
+///
+/// [`current`]: Self::current
+/// [`max`]: Self::max
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    /// How long the entity is [`Invulnerability`] after taking non-lethal damage.
+    pub hit_invulnerability_secs: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            hit_invulnerability_secs: 0.0,
+        }
+    }
+
+    pub fn with_hit_invulnerability(mut self, secs: f32) -> Self {
+        self.hit_invulnerability_secs = secs;
+        self
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Scales the damage an entity takes from every source.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct DamageTakenMultiplier(pub f32);
+
+/// Contact damage dealt to anything with [`Health`] that this entity's body touches.
+///
+/// Damage is dealt when a contact starts, and again whenever the target's [`Invulnerability`] runs
+/// out while the contact lasts. Targets need [`CollidingEntities`] for the latter.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Damage(pub f32);
+
+/// Terrain, like spikes or lava, that hurts and knocks back anything with [`Health`] touching it.
+///
+/// Unlike [`Damage`], this keeps hurting for as long as the contact lasts, whenever the target
+/// isn't [`Invulnerability`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(CollidingEntities)]
+pub struct Hazard {
+    pub damage: f32,
+    /// The speed targets are knocked away from the hazard at.
+    pub knockback: f32,
+}
+
+/// Protects an entity from [`DamageEvent`]s until the timer runs out.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Invulnerability(pub Timer);
+
+impl Invulnerability {
+    pub fn from_seconds(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// Washes an entity's sprite out to white for a moment after it's hit.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct HitFlash(pub Timer);
+
+impl HitFlash {
+    pub fn from_seconds(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+
+    /// How white the sprite is, fading from `1.0` to `0.0` as the timer runs out.
+    pub fn intensity(&self) -> f32 {
+        self.0.fraction_remaining()
+    }
+}
+
+/// Despawns the entity when it [`Died`].
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct DespawnOnDeath;
+
+/// Hurts an entity with [`Health`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    #[event_target]
+    pub target: Entity,
+    pub amount: f32,
+    /// The velocity the target is knocked back with, if it survives the damage. Targets that
+    /// are knocked back are also [`Stunned`] for a moment.
+    pub knockback: Vec2,
+}
+
+/// Triggered on an entity when it takes damage, but its [`Health`] doesn't run out.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Hurt {
+    #[event_target]
+    pub entity: Entity,
+}
+
+/// Triggered on an entity when its [`Health`] runs out.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Died {
+    #[event_target]
+    pub entity: Entity,
+}
+
+fn apply_damage(
+    damage: On<DamageEvent>,
+    mut targets: Query<(
+        &mut Health,
+        Has<Invulnerability>,
+        Option<&DashState>,
+        Option<&DamageTakenMultiplier>,
+    )>,
+    mut commands: Commands,
+) {
+    let Ok((mut health, invulnerable, dash, multiplier)) = targets.get_mut(damage.target) else {
+        return;
+    };
+    // Dashing grants i-frames.
+    if invulnerable || health.is_dead() || dash.is_some_and(DashState::is_dashing) {
+        return;
+    }
+
+    health.current -= damage.amount * multiplier.map_or(1.0, |m| m.0);
+    if health.is_dead() {
+        commands.trigger(Died {
+            entity: damage.target,
+        });
+        return;
+    }
+
+    commands.trigger(Hurt {
+        entity: damage.target,
+    });
+    if damage.knockback != Vec2::ZERO {
+        commands.entity(damage.target).insert((
+            Knockback::new(damage.knockback),
+            Stunned::from_seconds(HIT_STUN_SECS),
+        ));
+    }
+    if health.hit_invulnerability_secs > 0.0 {
+        commands
+            .entity(damage.target)
+            .insert(Invulnerability::from_seconds(
+                health.hit_invulnerability_secs,
+            ));
+    }
+}
+
+/// Mostly sideways and a little up, away from `from`, so knockback on flat ground doesn't just
+/// push targets into the floor.
+pub(super) fn knockback_direction(from: Vec2, to: Vec2) -> Vec2 {
+    let away = if to.x < from.x { -1.0 } else { 1.0 };
+    Vec2::new(away, 0.5).normalize()
+}
+
+fn deal_contact_damage(
+    collision: On<CollisionStart>,
+    dealers: Query<(&Damage, &GlobalTransform)>,
+    targets: Query<&GlobalTransform, (With<Health>, Without<Stomper>)>,
+    mut commands: Commands,
+) {
+    let (Some(body1), Some(body2)) = (collision.body1, collision.body2) else {
+        return;
+    };
+
+    for (source, target) in [(body1, body2), (body2, body1)] {
+        contact_damage(source, target, &dealers, &targets, &mut commands);
+    }
+}
+
+/// Contacts only deal damage when they start, which a target ignores while it's invulnerable, so
+/// targets still touching something with [`Damage`] when their [`Invulnerability`] runs out are
+/// hurt again here.
+fn deal_ongoing_contact_damage(
+    mut vulnerable: RemovedComponents<Invulnerability>,
+    colliding: Query<&CollidingEntities>,
+    colliders: Query<&ColliderOf>,
+    dealers: Query<(&Damage, &GlobalTransform)>,
+    targets: Query<&GlobalTransform, (With<Health>, Without<Stomper>)>,
+    mut commands: Commands,
+) {
+    for target in vulnerable.read() {
+        let Ok(colliding) = colliding.get(target) else {
+            continue;
+        };
+        // Touching several sources at once only counts as one hit.
+        let source = colliding
+            .iter()
+            .map(|&collider| colliders.get(collider).map_or(collider, |c| c.body))
+            .find(|&source| dealers.contains(source));
+        if let Some(source) = source {
+            contact_damage(source, target, &dealers, &targets, &mut commands);
+        }
+    }
+}
+
+/// Hurts `target` with the contact [`Damage`] of `source`, if it deals any and the target can be
+/// hurt by it.
+fn contact_damage(
+    source: Entity,
+    target: Entity,
+    dealers: &Query<(&Damage, &GlobalTransform)>,
+    // Stompers have their contacts resolved by direction instead.
+    targets: &Query<&GlobalTransform, (With<Health>, Without<Stomper>)>,
+    commands: &mut Commands,
+) {
+    if let Ok((damage, source_transform)) = dealers.get(source)
+        && let Ok(target_transform) = targets.get(target)
+    {
+        let direction = knockback_direction(
+            source_transform.translation().xy(),
+            target_transform.translation().xy(),
+        );
+        commands.trigger(DamageEvent {
+            target,
+            amount: damage.0,
+            knockback: direction * CONTACT_KNOCKBACK_SPEED,
+        });
+    }
+}
+
+fn apply_hazards(
+    hazards: Query<(&Hazard, &CollidingEntities, &GlobalTransform)>,
+    colliders: Query<&ColliderOf>,
+    mut targets: Query<
+        (&GlobalTransform, &mut LinearVelocity, Option<&DashState>),
+        (With<Health>, Without<Invulnerability>),
+    >,
+    mut commands: Commands,
+) {
+    // Touching several hazards at once only counts as one hit.
+    let mut hit = HashSet::new();
+    for (hazard, colliding, hazard_transform) in &hazards {
+        for &collider in colliding.iter() {
+            let target = colliders.get(collider).map_or(collider, |c| c.body);
+            if hit.contains(&target) {
+                continue;
+            }
+            let Ok((transform, mut velocity, dash)) = targets.get_mut(target) else {
+                continue;
+            };
+            if dash.is_some_and(DashState::is_dashing) {
+                continue;
+            }
+            hit.insert(target);
+
+            // Mostly straight up or down, so wide strips of spikes don't fling targets sideways.
+            // Targets are stopped first, so falling hard onto a hazard still bounces off it.
+            let offset = transform.translation().xy() - hazard_transform.translation().xy();
+            let direction = Vec2::new(0.5 * offset.x.signum(), offset.y.signum()).normalize();
+            velocity.0 = Vec2::ZERO;
+            commands.trigger(DamageEvent {
+                target,
+                amount: hazard.damage,
+                knockback: direction * hazard.knockback,
+            });
+        }
+    }
+}
+
+fn despawn_on_death(
+    died: On<Died>,
+    despawnable: Query<(), With<DespawnOnDeath>>,
+    mut commands: Commands,
+) {
+    if despawnable.contains(died.entity) {
+        commands.entity(died.entity).try_despawn();
+    }
+}
+
+fn tick_invulnerability(
+    time: Res<Time>,
+    mut entities: Query<(Entity, &mut Invulnerability)>,
+    mut commands: Commands,
+) {
+    for (entity, mut invulnerability) in &mut entities {
+        if invulnerability.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<Invulnerability>();
+        }
+    }
+}
+
+fn tick_hit_flashes(
+    time: Res<Time>,
+    mut entities: Query<(Entity, &mut HitFlash)>,
+    mut commands: Commands,
+) {
+    for (entity, mut flash) in &mut entities {
+        if flash.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Projectiles fired by the player and enemies.
+//!
+//! Anything with a [`Weapon`] can fire by triggering [`FireWeapon`] on itself. Projectiles are
+//! fast sensors that damage the first thing with [`Health`] they touch, and are kept below the
+//! speed of light so they length-contract like everything else.
+//!
+//! Surfaces stop projectiles, unless they glance off or go through. A projectile with bounces left
+//! ricochets off surfaces it hits at a shallow enough angle, which is steeper the bouncier the
+//! [`SurfaceMaterial`] is. Otherwise, it goes through surfaces softer than its penetration, losing
+//! that much penetration each time.
+
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_6};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    controller::SurfaceMaterial,
+    gameplay::combat::{DamageEvent, Health},
+    physics::{
+        LorentzFactor, SpeedOfLight, SpeedOfLightLimit, SpeedOfLightZone, effective_speed_of_light,
+        rel_add,
+    },
+    screens::Screen,
+};
+
+const PROJECTILE_SIZE: Vec2 = Vec2::splat(0.3);
+const PROJECTILE_COLOR: Color = Color::srgb(1.0, 0.9, 0.4);
+const PROJECTILE_LIFETIME_SECS: f32 = 3.0;
+/// How hard projectiles knock their targets along the way they were flying.
+const PROJECTILE_KNOCKBACK_SPEED: f32 = 4.0;
+/// The steepest angle, measured from the surface, that projectiles ricochet at off surfaces that
+/// aren't bouncy at all. Perfectly bouncy surfaces ricochet them at any angle.
+const RICOCHET_MAX_ANGLE: f32 = FRAC_PI_6;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(fire_weapon).add_observer(hit_target);
+
+    app.add_systems(
+        Update,
+        (tick_weapons, tick_projectiles)
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Lets an entity fire [`Projectile`]s with [`FireWeapon`].
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Weapon {
+    /// The muzzle speed, relative to the entity firing.
+    pub speed: f32,
+    pub damage: f32,
+    /// The layers of the fired projectiles, which decide what they can hit.
+    pub layers: CollisionLayers,
+    /// The fired projectiles' image. Without one, they're plain squares.
+    pub image: Option<Handle<Image>>,
+    /// How many times the fired projectiles can ricochet.
+    pub bounces: u32,
+    /// How much [`SurfaceMaterial::hardness`] the fired projectiles can go through.
+    pub penetration: f32,
+    cooldown: Timer,
+}
+
+impl Weapon {
+    pub fn new(speed: f32, damage: f32, cooldown_secs: f32, layers: CollisionLayers) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown_secs, TimerMode::Once);
+        // Ready to fire straight away.
+        cooldown.finish();
+        Self {
+            speed,
+            damage,
+            layers,
+            image: None,
+            bounces: 0,
+            penetration: 0.0,
+            cooldown,
+        }
+    }
+
+    pub fn with_image(mut self, image: Handle<Image>) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn with_bounces(mut self, bounces: u32) -> Self {
+        self.bounces = bounces;
+        self
+    }
+
+    pub fn with_penetration(mut self, penetration: f32) -> Self {
+        self.penetration = penetration;
+        self
+    }
+}
+
+/// Fires the entity's [`Weapon`] in `direction`, if it isn't cooling down.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct FireWeapon {
+    #[event_target]
+    pub entity: Entity,
+    pub direction: Dir2,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Projectile {
+    /// The entity that fired this.
+    pub owner: Entity,
+    pub damage: f32,
+    /// How many more times this can ricochet.
+    pub bounces: u32,
+    /// How much more [`SurfaceMaterial::hardness`] this can go through.
+    pub penetration: f32,
+    lifetime: Timer,
+}
+
+/// What happens to a [`Projectile`] that hits a surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SurfaceHit {
+    /// Glances off, with the given new velocity.
+    Ricochet(Vec2),
+    /// Goes through.
+    Penetrate,
+    /// Stops against it.
+    Stop,
+}
+
+impl Projectile {
+    /// Decides what happens when this hits a surface with the given `normal` and `material`
+    /// while moving at `velocity`, using up a bounce or penetration if it's needed.
+    fn hit_surface(
+        &mut self,
+        velocity: Vec2,
+        normal: Vec2,
+        material: &SurfaceMaterial,
+    ) -> SurfaceHit {
+        // The angle between the path and the surface, from 0 when skimming along it to 90 degrees
+        // when hitting it head on.
+        let angle = (-velocity.normalize_or_zero().dot(normal))
+            .clamp(0.0, 1.0)
+            .asin();
+        let max_angle = RICOCHET_MAX_ANGLE + (FRAC_PI_2 - RICOCHET_MAX_ANGLE) * material.bounciness;
+
+        if self.bounces > 0 && angle <= max_angle {
+            self.bounces -= 1;
+            SurfaceHit::Ricochet(velocity - 2.0 * velocity.dot(normal) * normal)
+        } else if material.hardness < self.penetration {
+            self.penetration -= material.hardness;
+            SurfaceHit::Penetrate
+        } else {
+            SurfaceHit::Stop
+        }
+    }
+}
+
+/// A projectile fired by `owner` from its `weapon`. The weapon's `layers` decide what it can hit.
+pub fn projectile(owner: Entity, weapon: &Weapon, position: Vec2, velocity: Vec2) -> impl Bundle {
+    let sprite = match weapon.image.clone() {
+        Some(image) => Sprite {
+            image,
+            custom_size: Some(PROJECTILE_SIZE),
+            ..default()
+        },
+        None => Sprite::from_color(PROJECTILE_COLOR, PROJECTILE_SIZE),
+    };
+
+    (
+        Name::new("Projectile"),
+        Projectile {
+            owner,
+            damage: weapon.damage,
+            bounces: weapon.bounces,
+            penetration: weapon.penetration,
+            lifetime: Timer::from_seconds(PROJECTILE_LIFETIME_SECS, TimerMode::Once),
+        },
+        (
+            RigidBody::Dynamic,
+            // Sensors don't contribute mass, so it has to be given explicitly.
+            Mass(0.1),
+            GravityScale(0.0),
+            LinearVelocity(velocity),
+            SweptCcd::default(),
+        ),
+        (
+            Collider::rectangle(PROJECTILE_SIZE.x, PROJECTILE_SIZE.y),
+            Sensor,
+            CollisionEventsEnabled,
+            weapon.layers,
+        ),
+        SpeedOfLightLimit,
+        LorentzFactor::default(),
+        sprite,
+        // Point images along the direction of travel.
+        Transform::from_translation(position.extend(1.0))
+            .with_rotation(Quat::from_rotation_z(velocity.to_angle())),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+fn fire_weapon(
+    fire: On<FireWeapon>,
+    global_c: Res<SpeedOfLight>,
+    zones: Query<(&SpeedOfLightZone, &GlobalTransform)>,
+    mut shooters: Query<(&mut Weapon, &Position, Option<&LinearVelocity>)>,
+    mut commands: Commands,
+) {
+    let Ok((mut weapon, position, velocity)) = shooters.get_mut(fire.entity) else {
+        return;
+    };
+    if !weapon.cooldown.is_finished() {
+        return;
+    }
+    weapon.cooldown.reset();
+
+    // Add the shooter's velocity relativistically, so a projectile fired while moving near the
+    // speed of light still can't outrun it.
+    let c = effective_speed_of_light(&global_c, &zones, position.0);
+    let shooter_velocity = velocity.map_or(Vec2::ZERO, |v| v.0);
+    let velocity = rel_add(shooter_velocity, fire.direction * weapon.speed, c);
+
+    commands.spawn(projectile(fire.entity, &weapon, position.0, velocity));
+}
+
+fn hit_target(
+    collision: On<CollisionStart>,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut projectiles: Query<(
+        &mut Projectile,
+        &Position,
+        &mut LinearVelocity,
+        &mut Rotation,
+    )>,
+    targets: Query<(), With<Health>>,
+    materials: Query<&SurfaceMaterial>,
+    mut commands: Commands,
+) {
+    // Collision events are triggered once for each collider that has them enabled, so only
+    // handle the one aimed at the projectile.
+    let Ok((mut projectile, position, mut velocity, mut rotation)) =
+        projectiles.get_mut(collision.collider1)
+    else {
+        return;
+    };
+
+    let target = collision.body2.unwrap_or(collision.collider2);
+    if target == projectile.owner {
+        return;
+    }
+
+    if targets.contains(target) {
+        commands.trigger(DamageEvent {
+            target,
+            amount: projectile.damage,
+            knockback: velocity.normalize_or_zero() * PROJECTILE_KNOCKBACK_SPEED,
+        });
+        commands.entity(collision.collider1).try_despawn();
+        return;
+    }
+
+    // Anything else is a surface.
+    let material = materials
+        .get(collision.collider2)
+        .or_else(|_| materials.get(target))
+        .copied()
+        .unwrap_or_default();
+    let normal = surface_normal(
+        &spatial_query,
+        collision.collider2,
+        position.0,
+        velocity.0,
+        time.delta_secs(),
+    );
+    match projectile.hit_surface(velocity.0, normal, &material) {
+        SurfaceHit::Ricochet(bounced) => {
+            velocity.0 = bounced;
+            // Keep images pointing along the direction of travel.
+            *rotation = Rotation::radians(bounced.to_angle());
+        }
+        SurfaceHit::Penetrate => {}
+        SurfaceHit::Stop => commands.entity(collision.collider1).try_despawn(),
+    }
+}
+
+/// Finds the normal of the `surface` that a projectile at `position` ran into while moving at
+/// `velocity`, by casting a ray back along its path. Sensors like projectiles don't get contact
+/// normals of their own.
+///
+/// Falls back to facing straight back along the path, as if the surface was hit head on.
+fn surface_normal(
+    spatial_query: &SpatialQuery,
+    surface: Entity,
+    position: Vec2,
+    velocity: Vec2,
+    dt: f32,
+) -> Vec2 {
+    let Ok(direction) = Dir2::new(velocity) else {
+        return Vec2::ZERO;
+    };
+    // Far enough back to be out of the surface again, even after a whole step inside it.
+    let back = velocity.length() * dt + PROJECTILE_SIZE.length();
+    spatial_query
+        .cast_ray_predicate(
+            position - direction * back,
+            direction,
+            2.0 * back,
+            true,
+            &SpatialQueryFilter::default(),
+            &|entity| entity == surface,
+        )
+        .map(|hit| hit.normal)
+        .filter(|normal| *normal != Vec2::ZERO)
+        .unwrap_or(-direction.as_vec2())
+}
+
+fn tick_weapons(time: Res<Time>, mut weapons: Query<&mut Weapon>) {
+    for mut weapon in &mut weapons {
+        weapon.cooldown.tick(time.delta());
+    }
+}
+
+fn tick_projectiles(
+    time: Res<Time>,
+    mut projectiles: Query<(Entity, &mut Projectile)>,
+    mut commands: Commands,
+) {
+    for (entity, mut projectile) in &mut projectiles {
+        if projectile.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Stomping on enemies from above.
+//!
+//! A [`Stomper`] that lands on top of something with contact [`Damage`] hurts it and bounces off,
+//! instead of taking the contact damage itself. Touching it from any other side hurts the stomper
+//! as usual. Contacts are told apart by their normal: a stomp is a contact whose normal points
+//! mostly down from the stomper, made while it isn't moving up relative to its target.
+
+use avian2d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::combat::{
+        CONTACT_KNOCKBACK_SPEED, Damage, DamageEvent, Invulnerability, knockback_direction,
+    },
+    screens::Screen,
+};
+
+/// How far down a contact normal has to point for the contact to be a stomp, about 45 degrees
+/// from straight down.
+const STOMP_MIN_NORMAL_Y: f32 = 0.7;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(resolve_stomps);
+
+    app.add_systems(
+        Update,
+        resolve_ongoing_stomps
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Lets an entity stomp on things with contact [`Damage`] by landing on them.
+///
+/// Contact damage between stompers and the things they touch is dealt here rather than by the
+/// usual contact damage. Like contact damage, it's dealt when a contact starts and again whenever
+/// the stomper's [`Invulnerability`] runs out while the contact lasts.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(CollidingEntities)]
+pub struct Stomper {
+    /// The damage dealt to whatever is stomped on.
+    pub damage: f32,
+    /// The upward speed the stomper bounces off with, in world units.
+    pub bounce_speed: f32,
+}
+
+fn resolve_stomps(collision: On<CollisionStart>, mut contacts: StompContacts) {
+    let (Some(body1), Some(body2)) = (collision.body1, collision.body2) else {
+        return;
+    };
+    let (collider1, collider2) = (collision.collider1, collision.collider2);
+
+    contacts.resolve(body1, collider1, body2, collider2);
+    contacts.resolve(body2, collider2, body1, collider1);
+}
+
+/// Contacts are only resolved when they start, and getting hurt by one leaves a stomper
+/// invulnerable for a while, so stompers still touching something with [`Damage`] when their
+/// [`Invulnerability`] runs out have the contact resolved again here.
+fn resolve_ongoing_stomps(
+    mut vulnerable: RemovedComponents<Invulnerability>,
+    colliding: Query<&CollidingEntities, With<Stomper>>,
+    colliders: Query<&ColliderOf>,
+    mut contacts: StompContacts,
+) {
+    for stomper in vulnerable.read() {
+        let Ok(colliding) = colliding.get(stomper) else {
+            continue;
+        };
+        // Touching several targets at once only counts as one contact.
+        let target = colliding.iter().find_map(|&collider| {
+            let body = colliders.get(collider).map_or(collider, |c| c.body);
+            contacts.targets.contains(body).then_some((body, collider))
+        });
+        if let Some((target, target_collider)) = target {
+            contacts.resolve(stomper, stomper, target, target_collider);
+        }
+    }
+}
+
+/// Resolves contacts between [`Stomper`]s and the things with [`Damage`] they touch.
+#[derive(SystemParam)]
+struct StompContacts<'w, 's> {
+    stompers: Query<
+        'w,
+        's,
+        (
+            &'static Stomper,
+            &'static GlobalTransform,
+            &'static mut LinearVelocity,
+        ),
+    >,
+    targets: Query<
+        'w,
+        's,
+        (
+            &'static Damage,
+            &'static GlobalTransform,
+            Option<&'static LinearVelocity>,
+        ),
+        Without<Stomper>,
+    >,
+    sensors: Query<'w, 's, (), With<Sensor>>,
+    collisions: Collisions<'w>,
+    commands: Commands<'w, 's>,
+}
+
+impl StompContacts<'_, '_> {
+    /// Either stomps on `target` or hurts `stomper`, if `stomper` is a [`Stomper`] and `target`
+    /// has contact [`Damage`].
+    fn resolve(
+        &mut self,
+        stomper: Entity,
+        stomper_collider: Entity,
+        target: Entity,
+        target_collider: Entity,
+    ) {
+        let Ok((stomp, stomper_transform, mut velocity)) = self.stompers.get_mut(stomper) else {
+            return;
+        };
+        let Ok((damage, target_transform, target_velocity)) = self.targets.get(target) else {
+            return;
+        };
+        let stomper_position = stomper_transform.translation().xy();
+        let target_position = target_transform.translation().xy();
+
+        // Manifold normals point from the pair's first collider to its second, so they're flipped
+        // to point away from the stomper. Without a manifold, the offset between bodies will do.
+        let normal = self
+            .collisions
+            .get(stomper_collider, target_collider)
+            .and_then(|pair| {
+                let normal = pair.manifolds.first()?.normal;
+                Some(if pair.collider1 == stomper_collider {
+                    normal
+                } else {
+                    -normal
+                })
+            })
+            .unwrap_or_else(|| (target_position - stomper_position).normalize_or_zero());
+        let relative_velocity = velocity.y - target_velocity.map_or(0.0, |v| v.y);
+        // Sensors, like the hitboxes of attacks, can't be stood on.
+        let solid = !self.sensors.contains(target_collider);
+
+        if solid && normal.y <= -STOMP_MIN_NORMAL_Y && relative_velocity <= 0.0 {
+            velocity.y = velocity.y.max(stomp.bounce_speed);
+            self.commands.trigger(DamageEvent {
+                target,
+                amount: stomp.damage,
+                knockback: Vec2::ZERO,
+            });
+        } else {
+            let direction = knockback_direction(target_position, stomper_position);
+            self.commands.trigger(DamageEvent {
+                target: stomper,
+                amount: damage.0,
+                knockback: direction * CONTACT_KNOCKBACK_SPEED,
+            });
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Enemy behavior, driven by a small state machine per enemy.
+//!
+//! Enemies walk back and forth within their [`Patrol`] area, turning at walls and edges found by
+//! their [probes](super::probes), until they see a player within their aggro range. They then
+//! chase the player, stop to shoot once close enough, and run away when badly hurt. Enemies
+//! without a [`RangedAttack`] never stop, and rely on contact damage. Level geometry blocks their
+//! sight, and a chasing enemy that loses sight of its target heads for where it was last seen
+//! before giving up. The ranges come from each enemy's manifest entry, as [`AiParams`].
+//!
+//! Ranged enemies shoot at the nearest player they can see within range, whatever they're doing.
+//!
+//! Flying enemies go through the same states, but hover at their patrol height, fly straight at
+//! their target, and dive at it instead of standing still to attack.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    PausableSystems,
+    gameplay::{
+        combat::{Health, projectile::FireWeapon},
+        enemies::probes::{EdgeAhead, WallAhead, update_forward_probes},
+        movement::{FlightIntent, MovementIntent},
+        player::Player,
+    },
+    physics::{GamePhysicsLayers, ProperTime},
+    screens::Screen,
+};
+
+/// Chance per frame that an attacking enemy fires, before its weapon cooldown.
+const FIRE_CHANCE: f64 = 0.02;
+/// How close a chasing enemy needs to get to where it's headed.
+const ARRIVE_DISTANCE: f32 = 0.5;
+/// How much higher than a chasing enemy its destination needs to be for it to jump.
+const JUMP_HEIGHT: f32 = 1.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (update_ai_states, steer_enemies, fire_at_targets)
+            .chain()
+            .after(update_forward_probes)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// How an enemy reacts to players, from its manifest entry.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(AiState)]
+pub struct AiParams {
+    /// How close a visible player needs to be for the enemy to notice them.
+    pub aggro_range: f32,
+    /// How close a visible target needs to be for the enemy to stop and shoot at it.
+    pub attack_range: f32,
+    /// How long the enemy keeps chasing a target it can't see.
+    pub give_up_secs: f32,
+    /// The fraction of its health at or below which the enemy runs away instead of fighting.
+    pub flee_health: f32,
+}
+
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub enum AiState {
+    /// Walking back and forth within the enemy's [`Patrol`] area, turning around at walls and
+    /// edges.
+    #[default]
+    Patrol,
+    /// Heading for where the target was last seen.
+    Chase {
+        target: Entity,
+        last_seen: Vec2,
+        /// How long since the target was last seen.
+        lost_secs: f32,
+    },
+    /// Standing still and shooting at a visible target.
+    Attack { target: Entity },
+    /// Running away from the target until out of its aggro range.
+    Flee { target: Entity },
+}
+
+/// Lets an enemy shoot its [`Weapon`](crate::gameplay::combat::projectile::Weapon) at players.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct RangedAttack {
+    /// How close a visible player needs to be to be shot at.
+    pub range: f32,
+}
+
+/// Keeps an enemy within [`radius`] of where it spawned, in level coordinates. Enemies that
+/// wander freely have an infinite radius.
+///
+/// [`radius`]: Self::radius
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Patrol {
+    pub origin: Vec2,
+    pub radius: f32,
+}
+
+/// Whether nothing in the level geometry blocks the straight line between two points.
+fn in_line_of_sight(spatial_query: &SpatialQuery, from: Vec2, to: Vec2) -> bool {
+    let Ok(direction) = Dir2::new(to - from) else {
+        return true;
+    };
+    spatial_query
+        .cast_ray(
+            from,
+            direction,
+            from.distance(to),
+            true,
+            &SpatialQueryFilter::from_mask(GamePhysicsLayers::LevelGeometry),
+        )
+        .is_none()
+}
+
+fn update_ai_states(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    players: Query<(Entity, &Position), With<Player>>,
+    mut enemies: Query<(
+        &AiParams,
+        &Position,
+        &Health,
+        Option<&ProperTime>,
+        Has<RangedAttack>,
+        &mut AiState,
+    )>,
+) {
+    for (params, position, health, proper_time, ranged, mut state) in &mut enemies {
+        let delta = proper_time.map_or(time.delta_secs(), |t| t.delta().as_secs_f32());
+        let distance_to = |target: Entity| {
+            players
+                .get(target)
+                .map_or(f32::INFINITY, |(_, p)| p.0.distance(position.0))
+        };
+
+        // The nearest player within aggro range that this enemy can see.
+        let seen = players
+            .iter()
+            .filter(|(_, player)| player.0.distance(position.0) < params.aggro_range)
+            .filter(|(_, player)| in_line_of_sight(&spatial_query, position.0, player.0))
+            .min_by(|(_, a), (_, b)| {
+                a.0.distance_squared(position.0)
+                    .total_cmp(&b.0.distance_squared(position.0))
+            })
+            .map(|(player, at)| (player, at.0));
+        let hurt = health.current <= params.flee_health * health.max;
+
+        let next = match (*state, seen) {
+            (_, Some((target, _))) if hurt => AiState::Flee { target },
+            (_, Some((target, at))) if ranged && at.distance(position.0) < params.attack_range => {
+                AiState::Attack { target }
+            }
+            (_, Some((target, at))) => AiState::Chase {
+                target,
+                last_seen: at,
+                lost_secs: 0.0,
+            },
+            // Fleeing enemies keep running until they're out of range, even out of sight.
+            (AiState::Flee { target }, None) if distance_to(target) < params.aggro_range => {
+                AiState::Flee { target }
+            }
+            (AiState::Attack { target }, None) => AiState::Chase {
+                target,
+                last_seen: players.get(target).map_or(position.0, |(_, p)| p.0),
+                lost_secs: 0.0,
+            },
+            (
+                AiState::Chase {
+                    target,
+                    last_seen,
+                    lost_secs,
+                },
+                None,
+            ) if lost_secs + delta < params.give_up_secs => AiState::Chase {
+                target,
+                last_seen,
+                lost_secs: lost_secs + delta,
+            },
+            (AiState::Patrol, None) => AiState::Patrol,
+            _ => AiState::default(),
+        };
+        state.set_if_neq(next);
+    }
+}
+
+fn steer_enemies(
+    players: Query<&Position, With<Player>>,
+    mut enemies: Query<(
+        &AiState,
+        &mut MovementIntent,
+        &Position,
+        &Transform,
+        Option<&Patrol>,
+        Option<&EdgeAhead>,
+        Option<&WallAhead>,
+        Option<&mut FlightIntent>,
+    )>,
+) {
+    for (state, mut intent, position, transform, patrol, edge, wall, flight) in &mut enemies {
+        let blocked = edge.is_some_and(|edge| edge.0) || wall.is_some_and(|wall| wall.0);
+        intent.jump = false;
+
+        match *state {
+            AiState::Patrol => {
+                if intent.direction == 0.0 {
+                    intent.direction = 1.0;
+                }
+
+                // Turn around at walls and edges.
+                if blocked {
+                    intent.direction = -intent.direction;
+                }
+
+                // Head back once past the edge of the patrol
+                if let Some(patrol) = patrol {
+                    let offset = transform.translation.x - patrol.origin.x;
+                    if offset.abs() > patrol.radius {
+                        intent.direction = -offset.signum();
+                    }
+                }
+            }
+            AiState::Chase { last_seen, .. } => {
+                let offset = last_seen - position.0;
+                intent.direction = if offset.x.abs() < ARRIVE_DISTANCE {
+                    0.0
+                } else {
+                    offset.x.signum()
+                };
+                intent.jump = offset.y > JUMP_HEIGHT;
+
+                // Only drop off an edge to follow a target below.
+                if edge.is_some_and(|edge| edge.0) && offset.y > -JUMP_HEIGHT {
+                    intent.direction = 0.0;
+                }
+            }
+            AiState::Attack { .. } => {
+                intent.direction = 0.0;
+            }
+            AiState::Flee { target } => {
+                if let Ok(target) = players.get(target) {
+                    intent.direction = if position.x < target.x { -1.0 } else { 1.0 };
+                }
+                // Cornered
+                if blocked {
+                    intent.direction = 0.0;
+                }
+            }
+        }
+
+        if let Some(mut flight) = flight {
+            let target = |target| players.get(target).map(|target| target.0);
+            flight.set_if_neq(match *state {
+                AiState::Patrol => FlightIntent::Hover(patrol.map(|patrol| patrol.origin.y)),
+                AiState::Chase { last_seen, .. } => FlightIntent::Pursue(last_seen),
+                AiState::Attack { target: entity } => {
+                    target(entity).map_or(FlightIntent::Hover(None), FlightIntent::Dive)
+                }
+                // Fly straight away from the target.
+                AiState::Flee { target: entity } => target(entity)
+                    .map_or(FlightIntent::Hover(None), |target| {
+                        FlightIntent::Pursue(2.0 * position.0 - target)
+                    }),
+            });
+        }
+    }
+}
+
+fn fire_at_targets(
+    spatial_query: SpatialQuery,
+    enemies: Query<(
+        Entity,
+        &RangedAttack,
+        &AiState,
+        &Position,
+        Option<&ProperTime>,
+    )>,
+    players: Query<&Position, With<Player>>,
+    mut commands: Commands,
+) {
+    for (enemy, attack, state, position, proper_time) in &enemies {
+        // The nearest player in range that this enemy can see. An attacking enemy already knows
+        // who it's after.
+        let target = match *state {
+            AiState::Attack { target } => players.get(target).ok(),
+            _ => players
+                .iter()
+                .filter(|player| player.0.distance(position.0) < attack.range)
+                .filter(|player| in_line_of_sight(&spatial_query, position.0, player.0))
+                .min_by(|a, b| {
+                    a.0.distance_squared(position.0)
+                        .total_cmp(&b.0.distance_squared(position.0))
+                }),
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        // Dilated enemies react more slowly.
+        let rate = proper_time.map_or(1.0, ProperTime::rate) as f64;
+        if let Ok(direction) = Dir2::new(target.0 - position.0)
+            && rand::rng().random_bool(FIRE_CHANCE * rate)
+        {
+            commands.trigger(FireWeapon {
+                entity: enemy,
+                direction,
+            });
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Bosses: enemies with a larger health pool, that fight in phases.
+//!
+//! A `Boss` LDtk entity names a [`Boss`] asset, and is replaced by the boss once the asset has
+//! loaded. Each phase of a boss is an enemy definition from the manifest, swapped in once the
+//! boss's health drops to the phase's threshold, so a phase can change anything from AI ranges to
+//! animations. Phases only ever advance, even if the boss is healed.
+//!
+//! The health of the boss being fought is shown in a bar along the bottom of the screen, and
+//! [`BossDefeated`] is triggered when it dies.
+
+use bevy::{asset::RecursiveDependencyLoadState, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    PausableSystems,
+    assets::{
+        boss::Boss,
+        enemy::Enemy,
+        level::{EnemySpawn, LdtkEntitySpawn, RegisterLdtkEntity},
+    },
+    gameplay::{
+        combat::{Died, Health},
+        enemies::{EnemyHandle, RefreshEnemy, enemy_bundle},
+        level::state::LevelIid,
+    },
+    screens::Screen,
+    theme::{palette::*, prelude::*},
+};
+
+const HEALTH_BAR_WIDTH: f32 = 480.0;
+const HEALTH_BAR_HEIGHT: f32 = 12.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity("Boss", boss_spawner)
+        .add_observer(load_boss)
+        .add_observer(defeat_boss)
+        .add_systems(OnEnter(Screen::Gameplay), spawn_boss_health_bar);
+
+    app.add_systems(
+        Update,
+        (spawn_bosses, update_boss_phases, update_boss_health_bar)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The [`Boss`] asset a boss was spawned from.
+#[derive(Component, Reflect, Debug, Deref)]
+#[reflect(Component)]
+pub struct BossHandle(pub Handle<Boss>);
+
+/// The index of a boss's current phase in [`Boss::phases`].
+#[derive(Component, Reflect, Debug, Default, Deref)]
+#[reflect(Component)]
+pub struct BossPhase(pub usize);
+
+/// Triggered when a boss dies.
+#[derive(Event, Debug, Clone)]
+pub struct BossDefeated {
+    pub entity: Entity,
+    pub boss: Handle<Boss>,
+}
+
+/// Waits for a [`Boss`] to load, then spawns it in its own place.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BossSpawner {
+    path: String,
+    position: Vec2,
+    facing: f32,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BossHealthBar;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BossNameLabel;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BossHealthFill;
+
+/// The fields of `Boss` LDtk entities.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BossSpawnerFields {
+    /// The boss asset's path, like `bosses/mushroom_king.boss.json`.
+    boss: String,
+    #[serde(rename = "Face_Left", default)]
+    face_left: bool,
+}
+
+fn boss_spawner(fields: BossSpawnerFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    (
+        Name::new(format!("Boss Spawner: {}", fields.boss)),
+        BossSpawner {
+            path: fields.boss,
+            position: entity.position,
+            facing: if fields.face_left { -1.0 } else { 1.0 },
+        },
+        // Replaying a defeated boss removes its spawner, so it isn't fought again.
+        LevelIid(entity.iid.clone()),
+        Transform::from_translation(entity.position.extend(0.0)),
+    )
+}
+
+fn load_boss(
+    add: On<Add, BossSpawner>,
+    spawners: Query<&BossSpawner>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    if let Ok(spawner) = spawners.get(add.entity) {
+        let handle = asset_server.load(&spawner.path);
+        commands.entity(add.entity).insert(BossHandle(handle));
+    }
+}
+
+fn spawn_bosses(
+    spawners: Query<(Entity, &BossSpawner, &BossHandle, &LevelIid, &ChildOf)>,
+    asset_server: Res<AssetServer>,
+    bosses: Res<Assets<Boss>>,
+    enemies: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    for (entity, spawner, handle, iid, child_of) in &spawners {
+        match asset_server.get_recursive_dependency_load_state(&handle.0) {
+            Some(RecursiveDependencyLoadState::Loaded) => {}
+            Some(RecursiveDependencyLoadState::Failed(error)) => {
+                warn!("Failed to load boss {:?}: {error}", spawner.path);
+                commands.entity(entity).despawn();
+                continue;
+            }
+            _ => continue,
+        }
+        commands.entity(entity).despawn();
+
+        let Some(boss) = bosses.get(&handle.0) else {
+            continue;
+        };
+        let first = &boss.phases[0].enemy;
+        let Some(enemy) = enemies.get(first) else {
+            continue;
+        };
+        let spawn = EnemySpawn {
+            iid: iid.0.clone(),
+            label: boss.name.clone(),
+            position: spawner.position,
+            facing: spawner.facing,
+            patrol_radius: None,
+        };
+
+        info!("Spawned boss {:?}", boss.name);
+        // Inserted separately, to replace the health and name of a regular enemy
+        commands
+            .spawn((
+                enemy_bundle(first.clone(), enemy, &spawn),
+                ChildOf(child_of.parent()),
+            ))
+            .insert((
+                Name::new(format!("Boss: {}", boss.name)),
+                BossHandle(handle.0.clone()),
+                BossPhase::default(),
+                Health::new(boss.max_health),
+                iid.clone(),
+            ));
+    }
+}
+
+fn update_boss_phases(
+    bosses: Res<Assets<Boss>>,
+    mut query: Query<(
+        Entity,
+        &BossHandle,
+        &Health,
+        &mut BossPhase,
+        &mut EnemyHandle,
+    )>,
+    mut commands: Commands,
+) {
+    for (entity, handle, health, mut phase, mut enemy) in &mut query {
+        let Some(boss) = bosses.get(&handle.0) else {
+            continue;
+        };
+        let next = boss.phase_at(health.current / health.max);
+        if next <= phase.0 {
+            continue;
+        }
+
+        info!("Boss {:?} entered phase {}", boss.name, next + 1);
+        phase.0 = next;
+        enemy.0 = boss.phases[next].enemy.clone();
+        commands.trigger(RefreshEnemy { entity });
+    }
+}
+
+fn defeat_boss(died: On<Died>, bosses: Query<&BossHandle>, mut commands: Commands) {
+    if let Ok(handle) = bosses.get(died.entity) {
+        commands.trigger(BossDefeated {
+            entity: died.entity,
+            boss: handle.0.clone(),
+        });
+    }
+}
+
+fn spawn_boss_health_bar(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Boss Health Bar"),
+        BossHealthBar,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(24),
+            width: percent(100),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: px(4),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (widget::label(""), BossNameLabel),
+            (
+                Name::new("Bar"),
+                Node {
+                    width: px(HEALTH_BAR_WIDTH),
+                    height: px(HEALTH_BAR_HEIGHT),
+                    ..default()
+                },
+                BackgroundColor(BOSS_HEALTH_BACKGROUND),
+                children![(
+                    Name::new("Fill"),
+                    BossHealthFill,
+                    Node {
+                        width: percent(100),
+                        height: percent(100),
+                        ..default()
+                    },
+                    BackgroundColor(BOSS_HEALTH_FILL),
+                )],
+            ),
+        ],
+    ));
+}
+
+fn update_boss_health_bar(
+    bosses: Res<Assets<Boss>>,
+    fought: Query<(&BossHandle, &Health)>,
+    mut bar: Single<&mut Visibility, With<BossHealthBar>>,
+    mut name: Single<&mut Text, With<BossNameLabel>>,
+    mut fill: Single<&mut Node, With<BossHealthFill>>,
+) {
+    let Some((boss, health)) = fought
+        .iter()
+        .find_map(|(handle, health)| Some((bosses.get(&handle.0)?, health)))
+    else {
+        bar.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    bar.set_if_neq(Visibility::Inherited);
+    if name.0 != boss.name {
+        name.0.clone_from(&boss.name);
+    }
+    let width = percent(100.0 * (health.current / health.max).clamp(0.0, 1.0));
+    if fill.width != width {
+        fill.width = width;
+    }
+}
@@ -0,0 +1,129 @@
+//! Enemy hit flashes, deaths, and loot.
+//!
+//! Enemies flash white whenever they're hurt. When one dies it becomes a [`Corpse`]: it stops
+//! thinking and fighting, falls through everything but the terrain, and plays its death animation
+//! once before it's despawned. Enemies with a loot table in their manifest entry drop it as they
+//! die.
+
+use avian2d::prelude::CollisionLayers;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    animation::Animation,
+    assets::enemy::Enemy,
+    gameplay::{
+        combat::{Damage, Died, HitFlash, Hurt, projectile::Weapon},
+        enemies::{
+            EnemyHandle,
+            ai::{AiParams, AiState, RangedAttack},
+        },
+        level::pickups::loot_drop,
+        movement::{FlightIntent, MovementIntent},
+    },
+    physics::{GamePhysicsLayersExt, ProperTime},
+    screens::Screen,
+};
+
+const HIT_FLASH_SECS: f32 = 0.15;
+/// How long corpses without a death animation last.
+const CORPSE_SECS: f32 = 0.3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(flash_on_hurt).add_observer(kill_enemy);
+
+    app.add_systems(
+        Update,
+        despawn_corpses
+            .in_set(AppSystems::TickTimers)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// A dead enemy, despawned once its death animation has played.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Corpse(pub Timer);
+
+fn flash_on_hurt(hurt: On<Hurt>, enemies: Query<(), With<EnemyHandle>>, mut commands: Commands) {
+    if enemies.contains(hurt.entity) {
+        commands
+            .entity(hurt.entity)
+            .insert(HitFlash::from_seconds(HIT_FLASH_SECS));
+    }
+}
+
+fn kill_enemy(
+    died: On<Died>,
+    mut enemies: Query<(
+        &EnemyHandle,
+        &Children,
+        &Transform,
+        &ChildOf,
+        &mut MovementIntent,
+        Option<&mut FlightIntent>,
+    )>,
+    colliders: Query<(), With<CollisionLayers>>,
+    assets: Res<Assets<Enemy>>,
+    animations: Res<Assets<Animation>>,
+    mut commands: Commands,
+) {
+    let Ok((handle, children, transform, child_of, mut intent, flight)) =
+        enemies.get_mut(died.entity)
+    else {
+        return;
+    };
+    let enemy = assets.get(&handle.0);
+
+    let lifetime = enemy
+        .and_then(|enemy| animations.get(enemy.death_anim.as_ref()?))
+        .map_or(CORPSE_SECS, |anim| anim.duration().as_secs_f32());
+    *intent = MovementIntent::default();
+    if let Some(mut flight) = flight {
+        *flight = FlightIntent::Hover(None);
+    }
+    commands
+        .entity(died.entity)
+        .remove::<(AiParams, AiState, Weapon, RangedAttack, Damage)>()
+        .insert((
+            Corpse(Timer::from_seconds(lifetime, TimerMode::Once)),
+            HitFlash::from_seconds(HIT_FLASH_SECS),
+        ));
+
+    // Collision layers are immutable, so they're replaced.
+    for &child in children {
+        if colliders.contains(child) {
+            commands.entity(child).insert(CollisionLayers::corpse());
+        }
+    }
+
+    let Some(table) = enemy.and_then(|enemy| enemy.loot.as_ref()) else {
+        return;
+    };
+    let position = transform.translation.xy();
+    let rng = &mut rand::rng();
+    for (item, count) in table.roll(rng) {
+        for _ in 0..count {
+            commands.spawn((
+                loot_drop(item, position, rng),
+                // Drops belong to the level, like the enemy they came from.
+                ChildOf(child_of.parent()),
+            ));
+        }
+    }
+}
+
+fn despawn_corpses(
+    time: Res<Time>,
+    mut corpses: Query<(Entity, &mut Corpse, Option<&ProperTime>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut corpse, proper_time) in &mut corpses {
+        // On the same clock as the death animation
+        let delta = proper_time.map_or(time.delta(), ProperTime::delta);
+        if corpse.0.tick(delta).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
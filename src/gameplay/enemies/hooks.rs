@@ -0,0 +1,70 @@
+//! Spawn-time customization of enemies based on their manifest tags.
+//!
+//! Gameplay modules register hooks for the tags they care about with
+//! [`EnemyHooksAppExt::add_enemy_tag_hook`], so enemy spawning doesn't need to know about every
+//! enemy feature.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{assets::enemy::Enemy, gameplay::enemies::EnemyHandle};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<EnemyTagHooks>()
+        .add_observer(apply_enemy_tag_hooks);
+}
+
+/// A function that customizes a newly spawned enemy.
+pub type EnemyTagHook = Box<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+#[derive(Resource, Default)]
+pub struct EnemyTagHooks(HashMap<String, Vec<EnemyTagHook>>);
+
+pub trait EnemyHooksAppExt {
+    /// Registers a hook that will be run for every spawned enemy with the given manifest tag.
+    fn add_enemy_tag_hook(
+        &mut self,
+        tag: impl Into<String>,
+        hook: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl EnemyHooksAppExt for App {
+    fn add_enemy_tag_hook(
+        &mut self,
+        tag: impl Into<String>,
+        hook: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<EnemyTagHooks>()
+            .0
+            .entry(tag.into())
+            .or_default()
+            .push(Box::new(hook));
+        self
+    }
+}
+
+fn apply_enemy_tag_hooks(
+    add: On<Add, EnemyHandle>,
+    hooks: Res<EnemyTagHooks>,
+    handles: Query<&EnemyHandle>,
+    enemies: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    let Ok(handle) = handles.get(add.entity) else {
+        return;
+    };
+    let Some(enemy) = enemies.get(&**handle) else {
+        return;
+    };
+
+    let mut entity = commands.entity(add.entity);
+    for hook in enemy
+        .tags
+        .iter()
+        .filter_map(|tag| hooks.0.get(tag))
+        .flatten()
+    {
+        hook(&mut entity);
+    }
+}
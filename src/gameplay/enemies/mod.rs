@@ -0,0 +1,355 @@
+//! Hostile characters, spawned from the enemy manifest.
+//!
+//! Levels spawn enemies with [`enemies_vec`] or [`enemy`]. Enemies whose manifest entry has an
+//! attack get a [`Weapon`] as they spawn. How enemies behave once spawned is up to the [`ai`]
+//! module, and [`hooks`] lets other modules customize enemies by manifest tag. Bosses are enemies
+//! too, spawned by the [`boss`] module. The [`sounds`] module plays each enemy's sounds, and the
+//! [`death`] module turns dead enemies into corpses that drop their loot.
+
+pub mod ai;
+pub mod boss;
+pub mod death;
+pub mod hooks;
+pub mod probes;
+pub mod sounds;
+
+use avian2d::prelude::{Collider, CollisionLayers, LinearVelocity, ShapeCaster};
+use bevy::prelude::*;
+
+use crate::{
+    PausableSystems,
+    animation::{AnimationPlayer, AnimationPlayerState, FrameBox, FrameBoxKind},
+    assets::{
+        enemy::{Enemy, EnemyManifest},
+        level::{EnemySpawn, Level},
+    },
+    gameplay::{
+        combat::{Damage, Health, projectile::Weapon},
+        enemies::{
+            ai::{AiParams, Patrol, RangedAttack},
+            death::Corpse,
+            probes::ForwardProbes,
+        },
+        level::state::LevelIid,
+        movement::{GroundNormal, MovementIntent, ground_caster, movement_controller},
+    },
+    physics::{DopplerSprite, GamePhysicsLayersExt, ProperTime, SpeedOfLightLimit},
+    screens::Screen,
+};
+
+const ENEMY_MAX_HEALTH: f32 = 2.0;
+const ENEMY_CONTACT_DAMAGE: f32 = 1.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        ai::plugin,
+        boss::plugin,
+        death::plugin,
+        hooks::plugin,
+        probes::plugin,
+        sounds::plugin,
+    ));
+
+    app.add_observer(arm_enemy).add_observer(refresh_enemy);
+
+    app.add_systems(
+        Update,
+        update_enemy_animations
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+
+    #[cfg(feature = "dev_native")]
+    {
+        app.add_plugins(hot_reload::plugin);
+    }
+}
+
+#[derive(Component, Reflect, Deref)]
+#[reflect(Component)]
+pub struct EnemyHandle(Handle<Enemy>);
+
+/// Reapplies an enemy's definition to it in place, after its [`EnemyHandle`] or the definition
+/// itself changes.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct RefreshEnemy {
+    #[event_target]
+    pub entity: Entity,
+}
+
+/// Every enemy spawned in `level` whose definition is loaded.
+pub fn enemies_vec(
+    enemy_manifest: &EnemyManifest,
+    enemies: &Assets<Enemy>,
+    level: &Level,
+) -> Vec<impl Bundle> {
+    level
+        .enemy_spawns
+        .iter()
+        .filter_map(|spawn| {
+            let enemy = enemy(enemy_manifest, enemies, spawn)?;
+            Some((enemy, LevelIid(spawn.iid.clone())))
+        })
+        .collect::<Vec<_>>()
+}
+
+/// An enemy spawned from its manifest entry, or `None` if its label is unknown or its asset isn't
+/// loaded.
+pub fn enemy(
+    enemy_manifest: &EnemyManifest,
+    enemies: &Assets<Enemy>,
+    spawn: &EnemySpawn,
+) -> Option<impl Bundle + use<>> {
+    let Some(handle) = enemy_manifest.enemies.get(&spawn.label) else {
+        warn!("Unknown enemy label: {:?}", spawn.label);
+        return None;
+    };
+
+    let enemy = enemies.get(handle)?;
+    Some(enemy_bundle(handle.clone(), enemy, spawn))
+}
+
+/// An enemy spawned from an already loaded definition.
+fn enemy_bundle(handle: Handle<Enemy>, enemy: &Enemy, spawn: &EnemySpawn) -> impl Bundle + use<> {
+    (
+        Name::new(format!("Enemy: {}", enemy.name)),
+        EnemyHandle(handle),
+        ProperTime::default(),
+        DopplerSprite,
+        SpeedOfLightLimit,
+        Health::new(ENEMY_MAX_HEALTH),
+        Damage(ENEMY_CONTACT_DAMAGE),
+        Sprite {
+            image: enemy.atlas.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: enemy.atlas_layout.clone(),
+                index: 0,
+            }),
+            custom_size: Some(enemy.size),
+            ..default()
+        },
+        AnimationPlayer::from(enemy.idle_anim.clone()),
+        Transform::from_translation((spawn.position - enemy.collider_offset).extend(0.0)),
+        movement_controller(
+            enemy.movement.clone(),
+            enemy.collider.clone(),
+            enemy.collider_offset,
+            CollisionLayers::enemy(),
+        ),
+        MovementIntent {
+            direction: spawn.facing,
+            jump: true,
+        },
+        (
+            enemy.ai.clone(),
+            ForwardProbes::default(),
+            Patrol {
+                origin: spawn.position - enemy.collider_offset,
+                radius: spawn.patrol_radius.unwrap_or(f32::INFINITY),
+            },
+        ),
+    )
+}
+
+/// The weapon for an enemy's attack, if it has one.
+fn enemy_weapon(enemy: &Enemy) -> Option<(Weapon, RangedAttack)> {
+    let attack = enemy.attack.as_ref()?;
+    let mut weapon = Weapon::new(
+        attack.speed,
+        attack.damage,
+        attack.cooldown_secs,
+        CollisionLayers::enemy_projectile(),
+    )
+    .with_bounces(attack.bounces)
+    .with_penetration(attack.penetration);
+    if let Some(image) = &attack.projectile {
+        weapon = weapon.with_image(image.clone());
+    }
+    Some((
+        weapon,
+        RangedAttack {
+            range: attack.range,
+        },
+    ))
+}
+
+fn arm_enemy(
+    add: On<Add, EnemyHandle>,
+    handles: Query<&EnemyHandle>,
+    enemies: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    let Some(enemy) = handles
+        .get(add.entity)
+        .ok()
+        .and_then(|handle| enemies.get(&handle.0))
+    else {
+        return;
+    };
+    if let Some(weapon) = enemy_weapon(enemy) {
+        commands.entity(add.entity).insert(weapon);
+    }
+    // Touching the hitbox deals the enemy's contact damage, like touching the enemy itself.
+    if enemy.hitboxes {
+        commands.spawn((
+            Name::new("Hitbox"),
+            FrameBox::new(FrameBoxKind::Hitbox),
+            CollisionLayers::enemy_hitbox(),
+            ChildOf(add.entity),
+        ));
+    }
+}
+
+fn refresh_enemy(
+    refresh: On<RefreshEnemy>,
+    assets: Res<Assets<Enemy>>,
+    mut enemies: Query<(
+        &EnemyHandle,
+        &Children,
+        &mut AiParams,
+        &mut ShapeCaster,
+        &mut Sprite,
+        &mut AnimationPlayerState,
+    )>,
+    mut colliders: Query<
+        (&mut Collider, &mut Transform),
+        (Without<EnemyHandle>, Without<FrameBox>),
+    >,
+    mut commands: Commands,
+) {
+    let Ok((handle, children, mut ai, mut caster, mut sprite, mut animation_state)) =
+        enemies.get_mut(refresh.entity)
+    else {
+        return;
+    };
+    let Some(enemy) = assets.get(&handle.0) else {
+        return;
+    };
+
+    // Reinserted rather than mutated, in case the enemy changed archetype or gained or lost its
+    // attack
+    let mut entity = commands.entity(refresh.entity);
+    entity.insert(enemy.movement.clone());
+    match enemy_weapon(enemy) {
+        Some(weapon) => entity.insert(weapon),
+        None => entity.remove::<(Weapon, RangedAttack)>(),
+    };
+    *ai = enemy.ai.clone();
+    *caster = ground_caster(enemy.collider.clone(), enemy.collider_offset);
+
+    sprite.image = enemy.atlas.clone();
+    sprite.custom_size = Some(enemy.size);
+    if let Some(atlas) = sprite.texture_atlas.as_mut() {
+        atlas.layout = enemy.atlas_layout.clone();
+    }
+    // Restart the current animation, in case its frames changed
+    animation_state.restart();
+
+    let mut iter = colliders.iter_many_mut(children);
+    while let Some((mut collider, mut transform)) = iter.fetch_next() {
+        *collider = enemy.collider.clone();
+        transform.translation = enemy.collider_offset.extend(transform.translation.z);
+    }
+}
+
+fn update_enemy_animations(
+    assets: Res<Assets<Enemy>>,
+    mut player_query: Query<(
+        &EnemyHandle,
+        &MovementIntent,
+        Option<&GroundNormal>,
+        Option<&LinearVelocity>,
+        Has<Corpse>,
+        &mut Sprite,
+        &mut AnimationPlayer,
+    )>,
+) {
+    for (handle, intent, ground_norm, velocity, dead, mut sprite, mut animation) in
+        &mut player_query
+    {
+        let Some(enemy) = assets.get(&handle.0) else {
+            continue;
+        };
+
+        if intent.direction != 0.0 {
+            sprite.flip_x = intent.direction < 0.0;
+        }
+
+        let next_anim = if dead {
+            enemy.death_anim.as_ref().unwrap_or(&enemy.idle_anim)
+        } else if ground_norm.is_none_or(GroundNormal::is_grounded) {
+            if intent.direction == 0.0 {
+                &enemy.idle_anim
+            } else {
+                &enemy.walk_anim
+            }
+        } else {
+            let v = velocity.map_or(-1.0, |v| v.y);
+            if v.abs() < 0.5 {
+                &enemy.peak_anim
+            } else if v > 0.0 {
+                &enemy.jump_anim
+            } else {
+                &enemy.fall_anim
+            }
+        };
+
+        if next_anim.id() != animation.animation.id() {
+            animation.animation = next_anim.clone();
+        }
+    }
+}
+
+#[cfg(feature = "dev_native")]
+mod hot_reload {
+    use bevy::{asset::AssetEventSystems, platform::collections::HashSet};
+
+    use super::*;
+
+    pub fn plugin(app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            reload_enemies
+                .run_if(on_message::<AssetEvent<Enemy>>.or(on_message::<AssetEvent<EnemyManifest>>))
+                .after(AssetEventSystems),
+        );
+    }
+
+    /// Refreshes live enemies in place when their definitions change, including when the whole
+    /// manifest is reloaded.
+    fn reload_enemies(
+        mut enemy_events: MessageReader<AssetEvent<Enemy>>,
+        mut manifest_events: MessageReader<AssetEvent<EnemyManifest>>,
+        assets: Res<Assets<Enemy>>,
+        manifests: Res<Assets<EnemyManifest>>,
+        enemies: Query<(Entity, &EnemyHandle)>,
+        mut commands: Commands,
+    ) {
+        let mut modified: HashSet<AssetId<Enemy>> = enemy_events
+            .read()
+            .filter_map(|ev| match *ev {
+                AssetEvent::Modified { id } => Some(id),
+                _ => None,
+            })
+            .collect();
+        for ev in manifest_events.read() {
+            if let &AssetEvent::Modified { id } = ev
+                && let Some(manifest) = manifests.get(id)
+            {
+                modified.extend(manifest.enemies.values().map(Handle::id));
+            }
+        }
+
+        for &id in &modified {
+            if let Some(enemy) = assets.get(id) {
+                info!("Reloading enemy {:?}", enemy.name);
+            }
+        }
+
+        for (entity, handle) in &enemies {
+            if modified.contains(&handle.0.id()) {
+                commands.trigger(RefreshEnemy { entity });
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Probes that look ahead of a walking character for walls and drops.
+//!
+//! Characters with [`ForwardProbes`] shape cast their ground caster's shape in the direction
+//! they're walking, and ray cast down just past their front edge. The results are stored in
+//! [`WallAhead`] and [`EdgeAhead`], for any AI to read.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    PausableSystems,
+    gameplay::movement::{GroundNormal, MovementController, MovementIntent},
+    physics::{GamePhysicsLayers, OneWayPlatform},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_forward_probes
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Probes ahead of a character, in the direction of its [`MovementIntent`].
+///
+/// Walkers only treat slopes too steep to walk up as walls, and only find edges while grounded.
+/// Fliers treat anything ahead as a wall, and never find edges.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(EdgeAhead, WallAhead)]
+pub struct ForwardProbes {
+    /// How far ahead of the character to look for walls and drops.
+    pub reach: f32,
+    /// How far the ground can fall away ahead before it counts as an edge.
+    pub max_drop: f32,
+}
+
+impl Default for ForwardProbes {
+    fn default() -> Self {
+        Self {
+            reach: 0.25,
+            max_drop: 1.5,
+        }
+    }
+}
+
+/// Whether a grounded character is about to walk off a drop deeper than
+/// [`ForwardProbes::max_drop`].
+#[derive(Component, Reflect, Debug, Default, Deref, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct EdgeAhead(pub bool);
+
+/// Whether a character is about to walk into a wall.
+#[derive(Component, Reflect, Debug, Default, Deref, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct WallAhead(pub bool);
+
+pub fn update_forward_probes(
+    spatial_query: SpatialQuery,
+    mut probes: Query<(
+        Entity,
+        &ForwardProbes,
+        Option<&MovementController>,
+        &ShapeCaster,
+        &Position,
+        &MovementIntent,
+        Option<&GroundNormal>,
+        &mut EdgeAhead,
+        &mut WallAhead,
+    )>,
+    platforms: Query<(), With<OneWayPlatform>>,
+) {
+    for (entity, probe, controller, caster, position, intent, ground_norm, mut edge, mut wall) in
+        &mut probes
+    {
+        // Keep the last results while standing still, so AI can tell what it stopped for.
+        let Ok(direction) = Dir2::new(Vec2::X * intent.direction) else {
+            continue;
+        };
+        let origin = position.0 + caster.origin;
+        let filter = SpatialQueryFilter::from_mask(GamePhysicsLayers::LevelGeometry)
+            .with_excluded_entities([entity]);
+
+        // One-way platforms never act as walls.
+        let hit = spatial_query.cast_shape_predicate(
+            &caster.shape,
+            origin,
+            0.0,
+            direction,
+            &ShapeCastConfig::from_max_distance(probe.reach),
+            &filter,
+            &|entity| !platforms.contains(entity),
+        );
+        // Anything too steep to walk up is a wall.
+        let max_slope_angle = controller.map_or(0.0, |controller| controller.max_slope_angle);
+        wall.set_if_neq(WallAhead(hit.is_some_and(|hit| {
+            hit.normal1.angle_to(Vec2::Y).abs() >= max_slope_angle
+        })));
+
+        // Look down from just past the front of the character's feet.
+        let half_size = caster.shape.aabb(Vec2::ZERO, 0.0).size() / 2.0;
+        let front = origin + Vec2::new(direction.x * (half_size.x + probe.reach), -half_size.y);
+        let ground = spatial_query.cast_ray(front, Dir2::NEG_Y, probe.max_drop, true, &filter);
+        let grounded = ground_norm.is_some_and(GroundNormal::is_grounded);
+        edge.set_if_neq(EdgeAhead(grounded && ground.is_none()));
+    }
+}
@@ -0,0 +1,138 @@
+//! Enemy footsteps and vocalizations.
+//!
+//! Each enemy's manifest entry lists the clips for its [`EnemySound`]s. Footsteps, and anything
+//! else tied to the animation, are played by markers on the enemy's animations. Jumps play as the
+//! jump animation starts, and hurt and death sounds play on [`Hurt`] and [`Died`]. Every sound is
+//! played from the enemy's position, through spatial audio.
+
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+
+use crate::{
+    PausableSystems,
+    animation::{AnimationEvent, AnimationPlayer},
+    assets::{enemy::Enemy, serialize::enemy::EnemySound},
+    audio::{EmittedBy, spatial_sound_effect},
+    gameplay::{
+        combat::{Died, Hurt},
+        enemies::EnemyHandle,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(play_marker_sounds)
+        .add_observer(play_hurt_sounds)
+        .add_observer(play_death_sounds);
+
+    app.add_systems(
+        Update,
+        play_jump_sounds
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+fn volume(sound: EnemySound) -> f32 {
+    match sound {
+        EnemySound::Step => 0.3,
+        EnemySound::Jump => 0.5,
+        EnemySound::Hurt | EnemySound::Death => 0.8,
+    }
+}
+
+/// Plays a random clip for `sound` at `position`, if the enemy has any. The sound is pitch
+/// shifted by the motion of `emitter`.
+fn play_sound(
+    commands: &mut Commands,
+    enemy: &Enemy,
+    sound: EnemySound,
+    emitter: Entity,
+    position: Vec2,
+) {
+    if let Some(clip) = enemy.sounds.get(sound).choose(&mut rand::rng()) {
+        commands.spawn((
+            spatial_sound_effect(clip.clone(), volume(sound), position),
+            EmittedBy(emitter),
+        ));
+    }
+}
+
+/// Plays the sound for an animation marker, if the marker belongs to an enemy.
+fn play_enemy_sound(
+    sound: EnemySound,
+    entity: Entity,
+    enemies: &Query<(&EnemyHandle, &GlobalTransform)>,
+    assets: &Assets<Enemy>,
+    commands: &mut Commands,
+) {
+    if let Ok((handle, transform)) = enemies.get(entity)
+        && let Some(enemy) = assets.get(&handle.0)
+    {
+        play_sound(commands, enemy, sound, entity, transform.translation().xy());
+    }
+}
+
+fn play_marker_sounds(
+    ev: On<AnimationEvent>,
+    enemies: Query<(&EnemyHandle, &GlobalTransform)>,
+    assets: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    if let Some(sound) = EnemySound::from_marker(ev.marker) {
+        play_enemy_sound(sound, ev.entity, &enemies, &assets, &mut commands);
+    }
+}
+
+fn play_hurt_sounds(
+    hurt: On<Hurt>,
+    enemies: Query<(&EnemyHandle, &GlobalTransform)>,
+    assets: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    play_enemy_sound(
+        EnemySound::Hurt,
+        hurt.entity,
+        &enemies,
+        &assets,
+        &mut commands,
+    );
+}
+
+fn play_death_sounds(
+    died: On<Died>,
+    enemies: Query<(&EnemyHandle, &GlobalTransform)>,
+    assets: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    play_enemy_sound(
+        EnemySound::Death,
+        died.entity,
+        &enemies,
+        &assets,
+        &mut commands,
+    );
+}
+
+fn play_jump_sounds(
+    enemies: Query<(Entity, &EnemyHandle, Ref<AnimationPlayer>, &GlobalTransform)>,
+    assets: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    for (entity, handle, animation, transform) in &enemies {
+        if !animation.is_changed() || animation.is_added() {
+            continue;
+        }
+        if let Some(enemy) = assets.get(&handle.0)
+            && animation.animation.id() == enemy.jump_anim.id()
+        {
+            play_sound(
+                &mut commands,
+                enemy,
+                EnemySound::Jump,
+                entity,
+                transform.translation().xy(),
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! The heads-up display: the player's health, the score, a speedometer and a gamma gauge.
+//!
+//! The speedometer shows how fast the player is going as a fraction of the
+//! [`LocalSpeedOfLight`], along with their Lorentz factor, so players can tell how close to `c`
+//! they are and how strongly relativity is kicking in. The gamma gauge shows the same without
+//! numbers: its segments light up as the player's Lorentz factor approaches the clamp, and flash
+//! when it's nearly there.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    PausableSystems,
+    gameplay::{
+        combat::Health,
+        level::{
+            LevelGeometry,
+            hub::CurrentRun,
+            pickups::{LevelStats, Score},
+        },
+        player::Player,
+    },
+    physics::{LocalSpeedOfLight, LorentzFactor, beta, gamma, lightspeed_proximity},
+    screens::Screen,
+    theme::{
+        palette::{
+            GAMMA_GAUGE_CALM, GAMMA_GAUGE_OFF, GAMMA_GAUGE_WARNING, HEART_EMPTY, HEART_FULL,
+            SPEEDOMETER_BACKGROUND, SPEEDOMETER_FAST, SPEEDOMETER_SLOW,
+        },
+        prelude::*,
+    },
+};
+
+const HEART_SIZE: f32 = 20.0;
+const SPEEDOMETER_WIDTH: f32 = 240.0;
+const SPEEDOMETER_HEIGHT: f32 = 12.0;
+const GAUGE_SEGMENTS: usize = 12;
+const GAUGE_SEGMENT_WIDTH: f32 = 16.0;
+const GAUGE_SEGMENT_HEIGHT: f32 = 10.0;
+/// How close to the clamp the player's Lorentz factor is when the gauge starts flashing.
+const GAUGE_WARNING_PROXIMITY: f32 = 0.85;
+/// How many times a second the gauge flashes.
+const GAUGE_FLASH_HZ: f32 = 4.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
+
+    app.add_systems(
+        Update,
+        (
+            update_hearts,
+            update_score_label.run_if(
+                resource_changed::<Score>
+                    .or(resource_changed::<LevelStats>)
+                    .or(resource_exists_and_changed::<CurrentRun>),
+            ),
+            update_speedometer,
+            update_gamma_gauge,
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The row the player's hearts are drawn in, one per point of maximum health.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Hearts;
+
+/// The filled part of the heart for the given point of health, counting from zero.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HeartFill(usize);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ScoreLabel;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SpeedometerFill;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SpeedometerLabel;
+
+/// One segment of the gamma gauge, counting from zero at the left.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GaugeSegment(usize);
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            padding: UiRect::all(px(10)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Start,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (
+                Name::new("Status"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(6),
+                    ..default()
+                },
+                children![
+                    (
+                        Name::new("Hearts"),
+                        Hearts,
+                        Node {
+                            column_gap: px(4),
+                            ..default()
+                        },
+                    ),
+                    (widget::label(""), ScoreLabel),
+                ],
+            ),
+            (
+                Name::new("Relativity"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    row_gap: px(8),
+                    ..default()
+                },
+                children![speedometer(), gamma_gauge()],
+            ),
+        ],
+    ));
+}
+
+fn speedometer() -> impl Bundle {
+    (
+        Name::new("Speedometer"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: px(4),
+            ..default()
+        },
+        children![
+            (widget::label(""), SpeedometerLabel),
+            (
+                Name::new("Bar"),
+                Node {
+                    width: px(SPEEDOMETER_WIDTH),
+                    height: px(SPEEDOMETER_HEIGHT),
+                    ..default()
+                },
+                BackgroundColor(SPEEDOMETER_BACKGROUND),
+                children![(
+                    Name::new("Fill"),
+                    SpeedometerFill,
+                    Node {
+                        width: percent(0),
+                        height: percent(100),
+                        ..default()
+                    },
+                    BackgroundColor(SPEEDOMETER_SLOW),
+                )],
+            ),
+        ],
+    )
+}
+
+fn gamma_gauge() -> impl Bundle {
+    (
+        Name::new("Gamma Gauge"),
+        Node {
+            column_gap: px(3),
+            ..default()
+        },
+        Children::spawn(SpawnIter((0..GAUGE_SEGMENTS).map(|index| {
+            (
+                Name::new(format!("Segment {index}")),
+                GaugeSegment(index),
+                Node {
+                    width: px(GAUGE_SEGMENT_WIDTH),
+                    height: px(GAUGE_SEGMENT_HEIGHT),
+                    ..default()
+                },
+                BackgroundColor(GAMMA_GAUGE_OFF),
+            )
+        }))),
+    )
+}
+
+/// The heart for the given point of health, filled by `filled` from `0.0` to `1.0`.
+fn heart(index: usize, filled: f32) -> impl Bundle {
+    (
+        Name::new(format!("Heart {index}")),
+        Node {
+            width: px(HEART_SIZE),
+            height: px(HEART_SIZE),
+            ..default()
+        },
+        BackgroundColor(HEART_EMPTY),
+        children![(
+            Name::new("Fill"),
+            HeartFill(index),
+            Node {
+                width: percent(100.0 * filled),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(HEART_FULL),
+        )],
+    )
+}
+
+/// Respawns the hearts whenever the player's maximum health changes, and fills them from their
+/// current health. Partial points of health show as partly filled hearts.
+fn update_hearts(
+    player: Single<&Health, (With<Player>, Changed<Health>)>,
+    hearts: Single<(Entity, Option<&Children>), With<Hearts>>,
+    mut fills: Query<(&HeartFill, &mut Node)>,
+    mut commands: Commands,
+) {
+    let health = **player;
+    let (hearts, children) = hearts.into_inner();
+    let count = health.max.ceil().max(0.0) as usize;
+    let filled = move |index: usize| (health.current - index as f32).clamp(0.0, 1.0);
+
+    if children.map_or(0, |children| children.len()) != count {
+        commands
+            .entity(hearts)
+            .despawn_related::<Children>()
+            .insert(Children::spawn(SpawnIter(
+                (0..count).map(move |index| heart(index, filled(index))),
+            )));
+        return;
+    }
+
+    for (fill, mut node) in &mut fills {
+        node.width = percent(100.0 * filled(fill.0));
+    }
+}
+
+fn update_score_label(
+    score: Res<Score>,
+    stats: Res<LevelStats>,
+    run: Option<Res<CurrentRun>>,
+    mut label: Single<&mut Text, With<ScoreLabel>>,
+) {
+    label.0 = format!(
+        "Score: {}  Items: {}/{}",
+        score.0, stats.items_collected, stats.items_total
+    );
+    if let Some(run) = run.filter(|run| !run.carried.is_empty()) {
+        let carried: Vec<_> = run.carried.iter().map(|c| c.item.as_str()).collect();
+        label.0 += &format!("  Carrying: {}", carried.join(", "));
+    }
+}
+
+fn update_speedometer(
+    c: Res<LocalSpeedOfLight>,
+    player: Single<&LinearVelocity, With<Player>>,
+    mut label: Single<&mut Text, With<SpeedometerLabel>>,
+    mut fill: Single<(&mut Node, &mut BackgroundColor), With<SpeedometerFill>>,
+) {
+    let speed = player.length();
+    let b = beta(speed, c.0);
+    label.0 = format!("{b:.3}c  γ{:.2}", gamma(speed, c.0));
+
+    let (node, color) = &mut *fill;
+    node.width = percent(100.0 * b);
+    color.0 = SPEEDOMETER_SLOW.mix(&SPEEDOMETER_FAST, b);
+}
+
+fn update_gamma_gauge(
+    time: Res<Time>,
+    level: Single<&LorentzFactor, With<LevelGeometry>>,
+    mut segments: Query<(&GaugeSegment, &mut BackgroundColor)>,
+) {
+    let proximity = lightspeed_proximity(level.scalar());
+    let lit = (proximity * GAUGE_SEGMENTS as f32).ceil() as usize;
+    let flash_off = proximity >= GAUGE_WARNING_PROXIMITY
+        && (time.elapsed_secs() * GAUGE_FLASH_HZ).fract() < 0.5;
+
+    for (segment, mut color) in &mut segments {
+        let t = segment.0 as f32 / (GAUGE_SEGMENTS - 1) as f32;
+        let target = if segment.0 < lit && !flash_off {
+            GAMMA_GAUGE_CALM.mix(&GAMMA_GAUGE_WARNING, t)
+        } else {
+            GAMMA_GAUGE_OFF
+        };
+        color.set_if_neq(BackgroundColor(target));
+    }
+}
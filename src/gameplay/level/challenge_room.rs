@@ -0,0 +1,406 @@
+//! Optional challenge rooms placed in LDtk, which seal themselves when a player walks in and
+//! reward an item for beating them.
+//!
+//! A `Challenge_Room` is a [`TriggerZone`] with one of two kinds of challenge:
+//!
+//! - [`ChallengeKind::Waves`] seals both sides and spawns its `Enemies` once per wave, starting the
+//!   next wave when the last one is dead.
+//! - [`ChallengeKind::Gauntlet`] seals only the side the player came in by, and is beaten by
+//!   leaving through the far side.
+//!
+//! Either kind can have a `Time_Limit`, counted down in the player's proper time. Running out of
+//! time unseals the room and clears its enemies, so it can be tried again.
+
+use std::time::Duration;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::{
+        enemy::{Enemy, EnemyManifest},
+        level::{EnemySpawn, LdtkEntitySpawn, RegisterLdtkEntity},
+    },
+    gameplay::{
+        enemies::{death::Corpse, enemy},
+        level::{
+            LevelAssets,
+            pickups::collectible,
+            trigger_zone::{TriggerEntered, TriggerExited, TriggerZone},
+        },
+        player::Player,
+    },
+    physics::{GamePhysicsLayersExt, ProperTime},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+const SEAL_WIDTH: f32 = 0.5;
+const SEAL_COLOR: Color = Color::srgb(0.77, 0.24, 0.24);
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity("Challenge_Room", challenge_room)
+        .add_observer(start_challenge)
+        .add_observer(finish_gauntlet)
+        .add_systems(OnEnter(Screen::Gameplay), spawn_challenge_countdown);
+
+    app.add_systems(
+        Update,
+        (
+            tick_challenges.in_set(AppSystems::TickTimers),
+            (update_challenge_waves, update_challenge_countdown)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeKind {
+    Waves,
+    Gauntlet,
+}
+
+/// A room that seals itself and runs a challenge when a player enters it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ChallengeRoom {
+    pub kind: ChallengeKind,
+    /// The labels of the enemies spawned in each wave.
+    enemies: Vec<String>,
+    waves: u32,
+    time_limit_secs: Option<f32>,
+    /// The item spawned in the middle of the room once it is beaten.
+    reward: String,
+    /// The area covered by the room, in level coordinates.
+    rect: Rect,
+    pub state: ChallengeState,
+}
+
+#[derive(Reflect, Debug, Default)]
+pub enum ChallengeState {
+    #[default]
+    Ready,
+    Running {
+        /// The wave being fought, counting from 1.
+        wave: u32,
+        remaining_secs: Option<f32>,
+        /// The side the player came in by, `-1.0` for left or `1.0` for right.
+        entrance: f32,
+        seals: Vec<Entity>,
+    },
+    Beaten,
+}
+
+/// An enemy spawned by a [`ChallengeRoom`] wave.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ChallengeEnemy {
+    room: Entity,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ChallengeCountdownLabel;
+
+/// The fields of `Challenge_Room` LDtk entities.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChallengeRoomFields {
+    kind: ChallengeKind,
+    #[serde(default)]
+    enemies: Vec<String>,
+    #[serde(default = "ChallengeRoomFields::default_waves")]
+    waves: u32,
+    #[serde(rename = "Time_Limit", default)]
+    time_limit: Option<f32>,
+    #[serde(default = "ChallengeRoomFields::default_reward")]
+    reward: String,
+}
+
+impl ChallengeRoomFields {
+    fn default_waves() -> u32 {
+        1
+    }
+
+    fn default_reward() -> String {
+        "Gold_Coin".to_string()
+    }
+}
+
+fn challenge_room(fields: ChallengeRoomFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    let size = entity.rect.size();
+    (
+        Name::new(format!("Challenge Room: {:?}", fields.kind)),
+        ChallengeRoom {
+            kind: fields.kind,
+            enemies: fields.enemies.iter().map(|e| e.to_lowercase()).collect(),
+            waves: fields.waves.max(1),
+            time_limit_secs: fields.time_limit.filter(|secs| *secs > 0.0),
+            reward: fields.reward.to_lowercase(),
+            rect: entity.rect,
+            state: ChallengeState::Ready,
+        },
+        TriggerZone {
+            id: entity.iid.clone(),
+            fields: default(),
+        },
+        Collider::rectangle(size.x, size.y),
+        CollisionLayers::trigger(),
+        Transform::from_translation(entity.rect.center().extend(0.0)),
+    )
+}
+
+/// A wall closing off one side of a room, spawned next to the room in its parent's space.
+fn seal(rect: Rect, side: f32) -> impl Bundle {
+    let height = rect.height();
+    let x = rect.center().x + side * (rect.half_size().x + SEAL_WIDTH / 2.0);
+    (
+        Name::new("Challenge Seal"),
+        RigidBody::Static,
+        Collider::rectangle(SEAL_WIDTH, height),
+        CollisionLayers::level_geometry(),
+        Sprite::from_color(SEAL_COLOR, Vec2::new(SEAL_WIDTH, height)),
+        Transform::from_xyz(x, rect.center().y, -0.5),
+    )
+}
+
+fn start_challenge(
+    entered: On<TriggerEntered>,
+    mut rooms: Query<(&mut ChallengeRoom, &GlobalTransform, &ChildOf)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    level_assets: Res<LevelAssets>,
+    manifests: Res<Assets<EnemyManifest>>,
+    enemies: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    let Ok((mut room, transform, child_of)) = rooms.get_mut(entered.zone) else {
+        return;
+    };
+    if !matches!(room.state, ChallengeState::Ready) {
+        return;
+    }
+
+    let entrance = players.get(entered.player).map_or(-1.0, |player| {
+        (player.translation().x - transform.translation().x).signum()
+    });
+    let sides: &[f32] = match room.kind {
+        ChallengeKind::Waves => &[-1.0, 1.0],
+        ChallengeKind::Gauntlet => &[entrance],
+    };
+    let seals = sides
+        .iter()
+        .map(|&side| {
+            commands
+                .spawn((seal(room.rect, side), ChildOf(child_of.parent())))
+                .id()
+        })
+        .collect();
+
+    info!("Started {:?} challenge {:?}", room.kind, entered.id);
+    room.state = ChallengeState::Running {
+        wave: 1,
+        remaining_secs: room.time_limit_secs,
+        entrance,
+        seals,
+    };
+    if room.kind == ChallengeKind::Waves
+        && let Some(manifest) = manifests.get(&level_assets.enemies)
+    {
+        spawn_wave(
+            &mut commands,
+            entered.zone,
+            &room,
+            child_of.parent(),
+            manifest,
+            &enemies,
+        );
+    }
+}
+
+/// Spawns one of each of the room's enemies, spread out across it.
+fn spawn_wave(
+    commands: &mut Commands,
+    room_entity: Entity,
+    room: &ChallengeRoom,
+    parent: Entity,
+    manifest: &EnemyManifest,
+    enemies: &Assets<Enemy>,
+) {
+    let count = room.enemies.len();
+    for (i, label) in room.enemies.iter().enumerate() {
+        let t = (i + 1) as f32 / (count + 1) as f32;
+        let spawn = EnemySpawn {
+            // Waves aren't placed in LDtk, so they aren't recorded in the level's events.
+            iid: String::new(),
+            label: label.clone(),
+            position: Vec2::new(
+                room.rect.min.x + t * room.rect.width(),
+                room.rect.center().y,
+            ),
+            facing: if t < 0.5 { 1.0 } else { -1.0 },
+            patrol_radius: Some(room.rect.half_size().x),
+        };
+        if let Some(bundle) = enemy(manifest, enemies, &spawn) {
+            commands.spawn((
+                bundle,
+                ChallengeEnemy { room: room_entity },
+                ChildOf(parent),
+            ));
+        }
+    }
+}
+
+fn tick_challenges(
+    time: Res<Time>,
+    player: Option<Single<Option<&ProperTime>, With<Player>>>,
+    mut rooms: Query<(Entity, &mut ChallengeRoom)>,
+    challenge_enemies: Query<(Entity, &ChallengeEnemy)>,
+    mut commands: Commands,
+) {
+    // The player's clock is the reference, so this is normally just the frame time.
+    let delta = player
+        .and_then(|player| player.into_inner().map(ProperTime::delta))
+        .unwrap_or(time.delta());
+
+    for (entity, mut room) in &mut rooms {
+        let ChallengeState::Running {
+            remaining_secs: Some(remaining),
+            ..
+        } = &mut room.state
+        else {
+            continue;
+        };
+        *remaining -= delta.as_secs_f32();
+        if *remaining > 0.0 {
+            continue;
+        }
+
+        info!("Ran out of time in a {:?} challenge", room.kind);
+        for (enemy, challenge_enemy) in &challenge_enemies {
+            if challenge_enemy.room == entity {
+                commands.entity(enemy).despawn();
+            }
+        }
+        end_challenge(&mut commands, &mut room, ChallengeState::Ready);
+    }
+}
+
+fn update_challenge_waves(
+    mut rooms: Query<(Entity, &mut ChallengeRoom, &ChildOf)>,
+    // Corpses don't hold up the next wave.
+    challenge_enemies: Query<&ChallengeEnemy, Without<Corpse>>,
+    level_assets: Res<LevelAssets>,
+    manifests: Res<Assets<EnemyManifest>>,
+    enemies: Res<Assets<Enemy>>,
+    mut commands: Commands,
+) {
+    for (entity, mut room, child_of) in &mut rooms {
+        if room.kind != ChallengeKind::Waves
+            || challenge_enemies.iter().any(|enemy| enemy.room == entity)
+        {
+            continue;
+        }
+        let waves = room.waves;
+        let ChallengeState::Running { wave, .. } = &mut room.state else {
+            continue;
+        };
+
+        if *wave < waves {
+            *wave += 1;
+            if let Some(manifest) = manifests.get(&level_assets.enemies) {
+                spawn_wave(
+                    &mut commands,
+                    entity,
+                    &room,
+                    child_of.parent(),
+                    manifest,
+                    &enemies,
+                );
+            }
+        } else {
+            spawn_reward(&mut commands, &room, child_of.parent());
+            end_challenge(&mut commands, &mut room, ChallengeState::Beaten);
+        }
+    }
+}
+
+fn finish_gauntlet(
+    exited: On<TriggerExited>,
+    mut rooms: Query<(&mut ChallengeRoom, &GlobalTransform, &ChildOf)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok((mut room, transform, child_of)) = rooms.get_mut(exited.zone) else {
+        return;
+    };
+    let ChallengeState::Running { entrance, .. } = room.state else {
+        return;
+    };
+    let Ok(player) = players.get(exited.player) else {
+        return;
+    };
+
+    // Leaving by the far side beats the gauntlet
+    let side = (player.translation().x - transform.translation().x).signum();
+    if room.kind == ChallengeKind::Gauntlet && side != entrance {
+        spawn_reward(&mut commands, &room, child_of.parent());
+        end_challenge(&mut commands, &mut room, ChallengeState::Beaten);
+    }
+}
+
+fn spawn_reward(commands: &mut Commands, room: &ChallengeRoom, parent: Entity) {
+    info!("Beat a {:?} challenge", room.kind);
+    commands.spawn((
+        collectible(&room.reward, room.rect.center()),
+        ChildOf(parent),
+    ));
+}
+
+/// Unseals a running room and leaves it in `state`.
+fn end_challenge(commands: &mut Commands, room: &mut ChallengeRoom, state: ChallengeState) {
+    if let ChallengeState::Running { seals, .. } = std::mem::replace(&mut room.state, state) {
+        for seal in seals {
+            commands.entity(seal).despawn();
+        }
+    }
+}
+
+fn spawn_challenge_countdown(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Challenge Countdown"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(10),
+            width: percent(100),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(widget::label(""), ChallengeCountdownLabel)],
+    ));
+}
+
+fn update_challenge_countdown(
+    rooms: Query<&ChallengeRoom>,
+    mut label: Single<&mut Text, With<ChallengeCountdownLabel>>,
+) {
+    let remaining = rooms
+        .iter()
+        .filter_map(|room| match room.state {
+            ChallengeState::Running { remaining_secs, .. } => remaining_secs,
+            _ => None,
+        })
+        .min_by(f32::total_cmp);
+
+    let text = remaining.map_or_else(String::new, |secs| {
+        let secs = Duration::from_secs_f32(secs.max(0.0));
+        format!("τ {}.{}s", secs.as_secs(), secs.subsec_millis() / 100)
+    });
+    label.set_if_neq(Text(text));
+}
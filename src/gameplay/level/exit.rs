@@ -0,0 +1,208 @@
+//! Level exits, and progression through the levels of a run.
+//!
+//! Exits are [`TriggerZone`]s placed with `Level_Exit` LDtk entities. Touching one swaps the
+//! current level for the next in place, or triggers [`RunFinished`] if there is no next level.
+//! Every level left this way triggers [`LevelFinished`] first. With the skip level assist on,
+//! [`SkipLevel`] does the same as the current level's first exit. Defeating a boss in a level
+//! without exits finishes the run, so boss arenas don't need one.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    assets::level::Level,
+    gameplay::{
+        enemies::boss::BossDefeated,
+        level::{
+            CurrentLevel,
+            hub::CurrentRun,
+            spawn_level,
+            trigger_zone::{TriggerEntered, TriggerZone},
+        },
+    },
+    physics::GamePhysicsLayersExt,
+};
+
+const EXIT_COLOR: Color = Color::srgba(0.37, 0.83, 0.37, 0.5);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(use_level_exit)
+        .add_observer(skip_level)
+        .add_observer(leave_after_boss);
+}
+
+/// The levels of a run entered from the hub.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct LevelProgression {
+    /// The level being played.
+    pub current: Handle<Level>,
+    /// Levels already finished this run, in the order they were played.
+    pub completed: Vec<Handle<Level>>,
+}
+
+impl LevelProgression {
+    pub fn new(first: Handle<Level>) -> Self {
+        Self {
+            current: first,
+            completed: Vec::new(),
+        }
+    }
+}
+
+/// Leads to the [`next`] level when a player enters it.
+///
+/// [`next`]: Self::next
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct LevelExit {
+    /// The level to continue to, or `None` to finish the run.
+    pub next: Option<Handle<Level>>,
+}
+
+/// Triggered when the player leaves a level of a run through an exit, before the next level
+/// spawns.
+#[derive(Event, Debug, Clone)]
+pub struct LevelFinished {
+    pub level: Handle<Level>,
+}
+
+/// Triggered when the player leaves the last level of a run. The run ends once they're back in
+/// the hub.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RunFinished;
+
+/// Leaves the current level as though through its first exit.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SkipLevel;
+
+pub fn exits_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .exits
+        .iter()
+        .map(|exit| {
+            let size = exit.rect.size();
+            (
+                Name::new("Level Exit"),
+                LevelExit {
+                    next: exit.next.clone(),
+                },
+                TriggerZone {
+                    id: exit.id.clone(),
+                    fields: default(),
+                },
+                Collider::rectangle(size.x, size.y),
+                CollisionLayers::trigger(),
+                Sprite::from_color(EXIT_COLOR, size),
+                Transform::from_translation(exit.rect.center().extend(-1.0)),
+            )
+        })
+        .collect()
+}
+
+fn use_level_exit(
+    entered: On<TriggerEntered>,
+    exits: Query<&LevelExit>,
+    current_level: Single<Entity, With<CurrentLevel>>,
+    progression: Option<ResMut<LevelProgression>>,
+    asset_server: Res<AssetServer>,
+    commands: Commands,
+) {
+    // Exits only lead anywhere during a run.
+    let (Ok(exit), Some(progression)) = (exits.get(entered.zone), progression) else {
+        return;
+    };
+    leave_level(
+        exit.next.as_ref(),
+        *current_level,
+        progression,
+        &asset_server,
+        commands,
+    );
+}
+
+fn skip_level(
+    _: On<SkipLevel>,
+    levels: Res<Assets<Level>>,
+    current_level: Single<Entity, With<CurrentLevel>>,
+    progression: Option<ResMut<LevelProgression>>,
+    asset_server: Res<AssetServer>,
+    mut run: Option<ResMut<CurrentRun>>,
+    commands: Commands,
+) {
+    let Some(progression) = progression else {
+        return;
+    };
+    let Some(exit) = levels
+        .get(&progression.current)
+        .and_then(|level| level.exits.first())
+    else {
+        warn!("The current level has no exit to skip to");
+        return;
+    };
+    let next = exit.next.clone();
+
+    if let Some(run) = run.as_mut() {
+        run.assisted = true;
+    }
+    leave_level(
+        next.as_ref(),
+        *current_level,
+        progression,
+        &asset_server,
+        commands,
+    );
+}
+
+fn leave_after_boss(
+    _: On<BossDefeated>,
+    levels: Res<Assets<Level>>,
+    current_level: Single<Entity, With<CurrentLevel>>,
+    progression: Option<ResMut<LevelProgression>>,
+    asset_server: Res<AssetServer>,
+    commands: Commands,
+) {
+    let Some(progression) = progression else {
+        return;
+    };
+    if levels
+        .get(&progression.current)
+        .is_none_or(|level| !level.exits.is_empty())
+    {
+        return;
+    }
+    leave_level(None, *current_level, progression, &asset_server, commands);
+}
+
+/// Swaps the current level for the `next` one, or finishes the run if there isn't one.
+fn leave_level(
+    next: Option<&Handle<Level>>,
+    current_level: Entity,
+    mut progression: ResMut<LevelProgression>,
+    asset_server: &AssetServer,
+    mut commands: Commands,
+) {
+    let Some(next) = next else {
+        info!(
+            "Run complete after {} levels",
+            progression.completed.len() + 1
+        );
+        commands.trigger(LevelFinished {
+            level: progression.current.clone(),
+        });
+        commands.trigger(RunFinished);
+        return;
+    };
+    if !asset_server.is_loaded_with_dependencies(next) {
+        warn!("The level behind the exit isn't loaded yet");
+        return;
+    }
+
+    commands.trigger(LevelFinished {
+        level: progression.current.clone(),
+    });
+    let finished = std::mem::replace(&mut progression.current, next.clone());
+    progression.completed.push(finished);
+    commands.entity(current_level).despawn();
+    commands.run_system_cached_with(spawn_level, next.clone());
+}
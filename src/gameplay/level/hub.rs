@@ -0,0 +1,220 @@
+//! The hub level, from which other levels are entered and returned to.
+//!
+//! The hub's NPC dialogue progress and level events, like unlocked doors, are kept in
+//! [`SaveData`], while everything about a single trip through the levels behind a door lives in
+//! the [`CurrentRun`] and [`LevelProgression`], and is discarded when the player returns.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::level::{DoorSpawn, Level},
+    gameplay::{
+        level::{
+            exit::LevelProgression,
+            state::{LevelEvent, LevelEventLog},
+        },
+        npc::{DialogueProgress, INTERACT_RANGE, Npc},
+        player::Player,
+    },
+    input::Action,
+    save::SaveData,
+    screens::Area,
+};
+
+const DOOR_SIZE: Vec2 = Vec2::new(1.0, 2.0);
+const UNLOCKED_COLOR: Color = Color::srgb(0.66, 0.79, 0.35);
+const LOCKED_COLOR: Color = Color::srgb(0.4, 0.4, 0.45);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(restore_hub_dialogue)
+        .add_systems(OnExit(Area::Level), end_run);
+
+    app.add_systems(
+        Update,
+        (
+            enter_doors
+                .in_set(AppSystems::RecordInput)
+                .run_if(input_just_pressed(Action::Interact)),
+            (
+                save_hub_dialogue,
+                update_door_sprites.run_if(resource_changed::<SaveData>),
+            )
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Area::Hub))
+            .in_set(PausableSystems),
+    );
+}
+
+/// A door to another level.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Door {
+    pub id: String,
+    pub level: Handle<Level>,
+    /// The door whose level must be visited before this one unlocks, if any.
+    pub unlocked_by: Option<String>,
+}
+
+impl Door {
+    pub fn is_unlocked(&self, save: &SaveData) -> bool {
+        self.unlocked_by.is_none()
+            || save
+                .hub
+                .events
+                .contains(&LevelEvent::DoorUnlocked(self.id.clone()))
+    }
+}
+
+/// Per-run state for a level entered from the hub. Removed when the player returns.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct CurrentRun {
+    /// The door the level was entered through.
+    pub door: String,
+    /// Doors in the hub that will unlock once the run is over.
+    pub unlocks: Vec<String>,
+    /// What has happened in the level during the run.
+    pub events: LevelEventLog,
+    /// Persistent items collected so far.
+    pub carried: Vec<CarriedItem>,
+    /// Whether any assist options were used during the run.
+    pub assisted: bool,
+}
+
+/// A persistent item, like a key, carried between the levels of a run.
+#[derive(Reflect, Debug, Clone)]
+pub struct CarriedItem {
+    /// The LDtk instance id of the item.
+    pub iid: String,
+    pub item: String,
+}
+
+pub fn doors_vec(level: &Level, save: &SaveData) -> Vec<impl Bundle> {
+    level.doors.iter().map(|spawn| door(spawn, save)).collect()
+}
+
+fn door(spawn: &DoorSpawn, save: &SaveData) -> impl Bundle {
+    let door = Door {
+        id: spawn.id.clone(),
+        level: spawn.level.clone(),
+        unlocked_by: spawn.unlocked_by.clone(),
+    };
+    let color = if door.is_unlocked(save) {
+        UNLOCKED_COLOR
+    } else {
+        LOCKED_COLOR
+    };
+
+    (
+        Name::new(format!("Door: {}", spawn.id)),
+        Sprite::from_color(color, DOOR_SIZE),
+        // Doors are placed by their top cell.
+        Transform::from_translation((spawn.position - 0.5 * Vec2::Y).extend(-1.0)),
+        door,
+    )
+}
+
+fn enter_doors(
+    player: Single<&GlobalTransform, With<Player>>,
+    doors: Query<(&Door, &GlobalTransform)>,
+    save: Res<SaveData>,
+    asset_server: Res<AssetServer>,
+    mut next_area: ResMut<NextState<Area>>,
+    mut commands: Commands,
+) {
+    let player_pos = player.translation().xy();
+    let Some((door, _)) = doors
+        .iter()
+        .map(|(door, transform)| (door, transform.translation().xy().distance(player_pos)))
+        .filter(|&(_, distance)| distance < INTERACT_RANGE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+    else {
+        return;
+    };
+
+    if !door.is_unlocked(&save) {
+        info!("The door to {:?} is locked", door.id);
+        return;
+    }
+    if !asset_server.is_loaded_with_dependencies(&door.level) {
+        warn!("The level behind door {:?} isn't loaded yet", door.id);
+        return;
+    }
+
+    let unlocks = doors
+        .iter()
+        .map(|(other, _)| other)
+        .filter(|other| other.unlocked_by.as_ref() == Some(&door.id))
+        .map(|other| other.id.clone())
+        .collect();
+
+    commands.insert_resource(CurrentRun {
+        door: door.id.clone(),
+        unlocks,
+        events: default(),
+        carried: Vec::new(),
+        assisted: false,
+    });
+    commands.insert_resource(LevelProgression::new(door.level.clone()));
+    next_area.set(Area::Level);
+}
+
+fn end_run(run: Option<Res<CurrentRun>>, mut save: ResMut<SaveData>, mut commands: Commands) {
+    let Some(run) = run else {
+        return;
+    };
+
+    if run.assisted {
+        info!("Ended an assisted run");
+    }
+    for door in &run.unlocks {
+        let event = LevelEvent::DoorUnlocked(door.clone());
+        if !save.hub.events.contains(&event) {
+            save.hub.events.record(event);
+        }
+    }
+    commands.remove_resource::<CurrentRun>();
+    commands.remove_resource::<LevelProgression>();
+}
+
+fn update_door_sprites(save: Res<SaveData>, mut doors: Query<(&Door, &mut Sprite)>) {
+    for (door, mut sprite) in &mut doors {
+        sprite.color = if door.is_unlocked(&save) {
+            UNLOCKED_COLOR
+        } else {
+            LOCKED_COLOR
+        };
+    }
+}
+
+/// Picks up hub NPC conversations where they were left off.
+fn restore_hub_dialogue(
+    add: On<Add, DialogueProgress>,
+    area: Option<Res<State<Area>>>,
+    save: Res<SaveData>,
+    mut npcs: Query<(&Npc, &mut DialogueProgress)>,
+) {
+    if area.is_none_or(|area| *area.get() != Area::Hub) {
+        return;
+    }
+
+    if let Ok((npc, mut progress)) = npcs.get_mut(add.entity)
+        && let Some(&saved) = save.hub.npc_dialogue.get(&npc.name)
+    {
+        progress.0 = saved;
+    }
+}
+
+fn save_hub_dialogue(
+    npcs: Query<(&Npc, &DialogueProgress), Changed<DialogueProgress>>,
+    mut save: ResMut<SaveData>,
+) {
+    for (npc, progress) in &npcs {
+        // Avoid rewriting the save when nothing actually changed.
+        if save.hub.npc_dialogue.get(&npc.name) != Some(&progress.0) {
+            save.hub.npc_dialogue.insert(npc.name.clone(), progress.0);
+        }
+    }
+}
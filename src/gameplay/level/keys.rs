@@ -0,0 +1,267 @@
+//! Colored keys, and the locked doors they open.
+//!
+//! Both are placed in LDtk, as `Key` and `Locked_Door` entities with a `Color` field. Collected
+//! keys go on the [`Keyring`], and opening a door uses up one key of its color.
+//!
+//! Picking up a key and opening a door are recorded in the level's [`LevelEventLog`], so they stay
+//! gone when the level is spawned again, and the keyring is counted from that same log. Keys found
+//! in the hub are therefore kept with the save, while keys found during a run are carried through
+//! its levels and dropped when it ends.
+
+use avian2d::prelude::*;
+use bevy::{
+    input::common_conditions::input_just_pressed, platform::collections::HashMap, prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::level::{LdtkEntitySpawn, RegisterLdtkEntity},
+    gameplay::{
+        level::{
+            hub::CurrentRun,
+            state::{LevelEvent, LevelEventLog, LevelIid, RecordLevelEvent},
+        },
+        npc::INTERACT_RANGE,
+        player::Player,
+    },
+    input::Action,
+    physics::GamePhysicsLayersExt,
+    save::SaveData,
+    screens::{Area, Screen},
+};
+
+const KEY_PICKUP_RANGE: f32 = 0.75;
+const KEY_SIZE: Vec2 = Vec2::new(0.3, 0.5);
+const KEY_ICON_SIZE: f32 = 16.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Keyring>()
+        .register_ldtk_entity("Key", key)
+        .register_ldtk_entity("Locked_Door", locked_door)
+        .add_systems(OnEnter(Screen::Gameplay), spawn_key_icons);
+
+    app.add_systems(
+        Update,
+        (
+            open_locked_doors
+                .in_set(AppSystems::RecordInput)
+                .run_if(input_just_pressed(Action::Interact)),
+            (
+                collect_keys,
+                count_keys.run_if(
+                    resource_changed::<SaveData>
+                        .or(resource_exists_and_changed::<CurrentRun>)
+                        .or(state_changed::<Area>),
+                ),
+                update_key_icons.run_if(resource_changed::<Keyring>),
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyColor {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl KeyColor {
+    pub fn color(self) -> Color {
+        match self {
+            KeyColor::Red => Color::srgb(0.84, 0.23, 0.23),
+            KeyColor::Green => Color::srgb(0.35, 0.75, 0.24),
+            KeyColor::Blue => Color::srgb(0.27, 0.5, 0.84),
+            KeyColor::Yellow => Color::srgb(0.91, 0.77, 0.28),
+        }
+    }
+}
+
+/// The keys the players are holding, counted from the log of the level being played.
+#[derive(Resource, Reflect, Default, Debug, PartialEq)]
+#[reflect(Resource)]
+pub struct Keyring {
+    pub keys: HashMap<KeyColor, u32>,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct KeyPickup {
+    pub color: KeyColor,
+}
+
+/// Blocks the way until a player opens it with a key of the same color.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct LockedDoor {
+    pub color: KeyColor,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct KeyIcons;
+
+/// The fields of `Key` and `Locked_Door` LDtk entities.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KeyFields {
+    color: KeyColor,
+}
+
+fn key(fields: KeyFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    (
+        Name::new(format!("Key: {:?}", fields.color)),
+        KeyPickup {
+            color: fields.color,
+        },
+        LevelIid(entity.iid.clone()),
+        Sprite::from_color(fields.color.color(), KEY_SIZE),
+        Transform::from_translation(entity.position.extend(0.5)),
+    )
+}
+
+fn locked_door(fields: KeyFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    let size = entity.rect.size();
+    (
+        Name::new(format!("Locked Door: {:?}", fields.color)),
+        LockedDoor {
+            color: fields.color,
+        },
+        LevelIid(entity.iid.clone()),
+        RigidBody::Static,
+        Collider::rectangle(size.x, size.y),
+        CollisionLayers::level_geometry(),
+        Sprite::from_color(fields.color.color().darker(0.2), size),
+        Transform::from_translation(entity.rect.center().extend(-0.5)),
+    )
+}
+
+/// Counts the keys picked up and not yet used in the log of the level being played.
+fn count_keys(
+    area: Option<Res<State<Area>>>,
+    save: Res<SaveData>,
+    run: Option<Res<CurrentRun>>,
+    mut keyring: ResMut<Keyring>,
+) {
+    let mut keys = HashMap::<KeyColor, u32>::default();
+    for event in LevelEventLog::current(area.as_deref(), &save, run.as_deref())
+        .into_iter()
+        .flat_map(LevelEventLog::iter)
+    {
+        match event {
+            LevelEvent::KeyCollected { color, .. } => *keys.entry(*color).or_default() += 1,
+            LevelEvent::DoorOpened { color, .. } => {
+                let count = keys.entry(*color).or_default();
+                *count = count.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    keyring.set_if_neq(Keyring { keys });
+}
+
+fn collect_keys(
+    players: Query<&GlobalTransform, With<Player>>,
+    keys: Query<(Entity, &KeyPickup, &LevelIid, &GlobalTransform)>,
+    mut commands: Commands,
+) {
+    for (entity, key, iid, transform) in &keys {
+        let position = transform.translation().xy();
+        if !players
+            .iter()
+            .any(|player| player.translation().xy().distance(position) < KEY_PICKUP_RANGE)
+        {
+            continue;
+        }
+
+        commands.trigger(RecordLevelEvent(LevelEvent::KeyCollected {
+            iid: iid.0.clone(),
+            color: key.color,
+        }));
+        commands.entity(entity).despawn();
+    }
+}
+
+fn open_locked_doors(
+    players: Query<&GlobalTransform, With<Player>>,
+    doors: Query<(Entity, &LockedDoor, &LevelIid, &GlobalTransform)>,
+    keyring: Res<Keyring>,
+    mut commands: Commands,
+) {
+    // Doors opened this frame aren't in the keyring yet, so count the keys they use up here.
+    let mut keys = keyring.keys.clone();
+    for (entity, door, iid, transform) in &doors {
+        let position = transform.translation().xy();
+        if !players
+            .iter()
+            .any(|player| player.translation().xy().distance(position) < INTERACT_RANGE)
+        {
+            continue;
+        }
+
+        let Some(count) = keys.get_mut(&door.color).filter(|count| **count > 0) else {
+            info!("The door needs a {:?} key", door.color);
+            continue;
+        };
+        *count -= 1;
+        commands.trigger(RecordLevelEvent(LevelEvent::DoorOpened {
+            iid: iid.0.clone(),
+            color: door.color,
+        }));
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_key_icons(mut keyring: ResMut<Keyring>, mut commands: Commands) {
+    // Fill in the new icons even if the keyring is the same as last time.
+    keyring.set_changed();
+    commands.spawn((
+        Name::new("Key Icons"),
+        KeyIcons,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(40),
+            left: px(10),
+            column_gap: px(6),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_key_icons(
+    keyring: Res<Keyring>,
+    icons: Single<Entity, With<KeyIcons>>,
+    mut commands: Commands,
+) {
+    let mut keys: Vec<_> = keyring
+        .keys
+        .iter()
+        .flat_map(|(&color, &count)| std::iter::repeat_n(color, count as usize))
+        .collect();
+    keys.sort_by_key(|&color| color as u8);
+
+    commands
+        .entity(*icons)
+        .despawn_children()
+        .with_children(|icons| {
+            for color in keys {
+                icons.spawn((
+                    Name::new(format!("Key Icon: {color:?}")),
+                    Node {
+                        width: px(KEY_ICON_SIZE * 0.6),
+                        height: px(KEY_ICON_SIZE),
+                        ..default()
+                    },
+                    BackgroundColor(color.color()),
+                ));
+            }
+        });
+}
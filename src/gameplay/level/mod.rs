@@ -0,0 +1,647 @@
+//! Spawn the hub and the levels entered from it.
+//!
+//! Besides level geometry, the submodules handle the terrain tilemap and what is placed in levels:
+//! exits, doors, keys, pickups, props, water, trigger zones and challenge rooms, along with the
+//! [`state`] log of what has happened to them, and collect the stats of each run. Enemies are
+//! spawned through the [`enemies`](crate::gameplay::enemies) module.
+
+pub mod challenge_room;
+pub mod exit;
+pub mod hub;
+pub mod keys;
+pub mod pickups;
+pub mod props;
+pub mod state;
+pub mod stats;
+pub mod streaming;
+pub mod tilemap;
+pub mod trigger_zone;
+pub mod water;
+
+use avian2d::prelude::{Collider, CollisionLayers, Friction, Restitution, RigidBody, Sensor};
+use bevy::{ecs::bundle::NoBundleEffect, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    asset_tracking::LoadResource,
+    assets::{
+        enemy::{Enemy, EnemyManifest},
+        level::{
+            HazardKind, LdtkEntityRegistry, LdtkEntitySpawn, Level, RegisterLdtkEntity, SurfaceKind,
+        },
+        world::LdtkWorld,
+    },
+    audio::PlayMusic,
+    config::{GameConfig, TerrainColliderMode},
+    controller::SurfaceMaterial,
+    gameplay::{
+        combat::Hazard,
+        enemies::enemies_vec,
+        level::{
+            exit::{LevelProgression, exits_vec},
+            hub::doors_vec,
+            pickups::items_vec,
+            props::props_vec,
+            tilemap::tilemap,
+            trigger_zone::trigger_zones_vec,
+        },
+        npc::npcs_vec,
+        player::{PlayerAssets, player},
+    },
+    physics::{
+        GamePhysicsLayersExt, GravityWell, LevelPhysics, LorentzFactor, MovingPlatform,
+        OneWayPlatform, SpeedOfLightZone, WaterVolume, ZeroGravityZone,
+    },
+    save::SaveData,
+    screens::Area,
+    units::{TILE_SIZE, WorldMeters},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        challenge_room::plugin,
+        exit::plugin,
+        hub::plugin,
+        keys::plugin,
+        pickups::plugin,
+        props::plugin,
+        state::plugin,
+        stats::plugin,
+        streaming::plugin,
+        tilemap::plugin,
+        trigger_zone::plugin,
+        water::plugin,
+    ));
+
+    app.load_resource::<LevelAssets>()
+        .add_systems(OnEnter(Area::Hub), spawn_area_level)
+        .add_systems(OnEnter(Area::Level), spawn_area_level)
+        .add_systems(OnExit(Area::Hub), restore_level_physics)
+        .add_systems(OnExit(Area::Level), restore_level_physics)
+        .register_ldtk_entity("Gravity_Well", gravity_well);
+
+    #[cfg(feature = "dev_native")]
+    {
+        app.add_plugins(hot_reload::plugin);
+    }
+}
+
+const PLATFORM_COLOR: Color = Color::srgb(0.55, 0.43, 0.3);
+const WATER_COLOR: Color = Color::srgba(0.25, 0.5, 0.75, 0.4);
+const SPIKES_COLOR: Color = Color::srgb(0.62, 0.64, 0.68);
+const LAVA_COLOR: Color = Color::srgb(0.88, 0.34, 0.11);
+const ICE_COLOR: Color = Color::srgb(0.66, 0.85, 0.92);
+const MUD_COLOR: Color = Color::srgb(0.42, 0.31, 0.16);
+const BOUNCY_COLOR: Color = Color::srgb(0.84, 0.36, 0.69);
+const ZERO_GRAVITY_COLOR: Color = Color::srgba(0.3, 0.2, 0.5, 0.25);
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LevelAssets {
+    #[dependency]
+    music: Handle<AudioSource>,
+    #[dependency]
+    pub world: Handle<LdtkWorld>,
+    #[dependency]
+    hub: Handle<Level>,
+    #[dependency]
+    pub enemies: Handle<EnemyManifest>,
+}
+
+impl FromWorld for LevelAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            music: assets.load("audio/music/Silent Wood.ogg"),
+            world: assets.load("test.ldtk"),
+            hub: assets.load("test.ldtk#Level_1"),
+            enemies: assets.load("enemies.json"),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Deref)]
+pub struct CurrentLevel(Handle<Level>);
+
+#[derive(Component, Reflect)]
+pub struct LevelGeometry;
+
+/// A system that spawns the level for the current [`Area`]: either the hub, or the current level
+/// of the [`LevelProgression`].
+fn spawn_area_level(
+    area: Res<State<Area>>,
+    progression: Option<Res<LevelProgression>>,
+    level_assets: Res<LevelAssets>,
+    mut commands: Commands,
+) {
+    let level_handle = match (area.get(), progression) {
+        (Area::Level, Some(progression)) => progression.current.clone(),
+        _ => level_assets.hub.clone(),
+    };
+    commands.run_system_cached_with(spawn_level, level_handle);
+}
+
+/// A system that spawns the given level, to be despawned when the current [`Area`] is left.
+pub fn spawn_level(
+    In(level_handle): In<Handle<Level>>,
+    mut commands: Commands,
+    area: Res<State<Area>>,
+    save: Res<SaveData>,
+    level_assets: Res<LevelAssets>,
+    registry: Res<LdtkEntityRegistry>,
+    config: Res<GameConfig>,
+    player_assets: Res<PlayerAssets>,
+    levels: Res<Assets<Level>>,
+    enemy_manifest: Res<Assets<EnemyManifest>>,
+    enemies: Res<Assets<Enemy>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let level = levels.get(&level_handle).unwrap();
+    let enemy_manifest = enemy_manifest.get(&level_assets.enemies).unwrap();
+    commands.insert_resource(level.physics);
+    // Keeps playing if it already was, like when moving between the hub and a level.
+    commands.trigger(PlayMusic {
+        track: level_assets.music.clone(),
+        volume: 0.7,
+        layers: Vec::new(),
+    });
+    commands
+        .spawn((
+            Name::new("Level"),
+            CurrentLevel(level_handle.clone()),
+            Transform::default(),
+            Visibility::default(),
+            DespawnOnExit(*area.get()),
+            children![
+                player(
+                    level.player_spawn,
+                    &config,
+                    &player_assets,
+                    &mut texture_atlas_layouts
+                ),
+                (
+                    Name::new("Enemies"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(
+                        enemies_vec(enemy_manifest, &enemies, level).into_iter()
+                    ))
+                ),
+                (
+                    Name::new("NPCs"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(
+                        npcs_vec(enemy_manifest, &enemies, level).into_iter()
+                    ))
+                ),
+                (
+                    Name::new("Speed of Light Zones"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(light_zones_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Zero Gravity Zones"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(zero_gravity_zones_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Moving Platforms"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(moving_platforms_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Props"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(props_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Items"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(items_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Water"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(water_volumes_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Trigger Zones"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(trigger_zones_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Exits"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(exits_vec(level).into_iter()))
+                ),
+                (
+                    Name::new("Doors"),
+                    Transform::default(),
+                    Visibility::default(),
+                    Children::spawn(SpawnIter(doors_vec(level, &save).into_iter()))
+                )
+            ],
+        ))
+        .with_children(|children| {
+            spawn_level_geometry(
+                children,
+                level,
+                (LevelGeometry, LorentzFactor::default()),
+                config.terrain_colliders,
+            );
+            spawn_registered_entities(children, level, &registry);
+        });
+}
+
+/// Spawns the level's entities that have a spawner in the [`LdtkEntityRegistry`].
+fn spawn_registered_entities(
+    children: &mut ChildSpawnerCommands,
+    level: &Level,
+    registry: &LdtkEntityRegistry,
+) {
+    let group = children
+        .spawn((
+            Name::new("Registered Entities"),
+            Transform::default(),
+            Visibility::default(),
+        ))
+        .id();
+
+    for entity in &level.entities {
+        let mut commands = children.commands();
+        let mut entity_commands = commands.spawn(ChildOf(group));
+        match registry.spawn(entity, &mut entity_commands) {
+            Some(Ok(())) => {}
+            Some(Err(error)) => {
+                warn!("Failed to spawn {:?}: {error}", entity.identifier);
+                entity_commands.despawn();
+            }
+            None => entity_commands.despawn(),
+        }
+    }
+}
+
+/// Spawns a level's tilemap and colliders under a single static body.
+pub fn spawn_level_geometry(
+    children: &mut ChildSpawnerCommands,
+    level: &Level,
+    geometry: impl Bundle,
+    mode: TerrainColliderMode,
+) {
+    let geometry_id = children
+        .spawn((
+            Name::new("Level Geometry"),
+            geometry,
+            Visibility::default(),
+            RigidBody::Static,
+            children![tilemap(level)],
+        ))
+        .id();
+
+    children
+        .commands()
+        .spawn_batch(colliders_batch(level, geometry_id, mode));
+    children
+        .commands()
+        .spawn_batch(platforms_batch(level, geometry_id));
+    children
+        .commands()
+        .spawn_batch(hazards_batch(level, geometry_id));
+    children
+        .commands()
+        .spawn_batch(surfaces_batch(level, geometry_id));
+}
+
+fn restore_level_physics(mut physics: ResMut<LevelPhysics>) {
+    *physics = LevelPhysics::default();
+}
+
+fn colliders_batch(
+    level: &Level,
+    level_geometry: Entity,
+    mode: TerrainColliderMode,
+) -> Vec<impl Bundle<Effect: NoBundleEffect>> {
+    let colliders: Vec<_> = match mode {
+        TerrainColliderMode::Rectangles => level
+            .terrain_colliders
+            .iter()
+            .map(|tc| tc.into_collider_and_transform(TILE_SIZE))
+            .collect(),
+        TerrainColliderMode::Outlines => level
+            .terrain_outlines
+            .iter()
+            .map(|outline| (outline.to_collider(TILE_SIZE), Transform::default()))
+            .collect(),
+    };
+
+    colliders
+        .into_iter()
+        .map(|(collider, transform)| {
+            (
+                Name::new("Terrain Collider"),
+                ChildOf(level_geometry),
+                RigidBody::Static,
+                CollisionLayers::level_geometry(),
+                collider,
+                transform,
+            )
+        })
+        .collect()
+}
+
+fn platforms_batch(
+    level: &Level,
+    level_geometry: Entity,
+) -> Vec<impl Bundle<Effect: NoBundleEffect>> {
+    level
+        .platform_colliders
+        .iter()
+        .map(|pc| {
+            let (collider, transform) = pc.into_collider_and_transform(TILE_SIZE);
+            (
+                Name::new("One-Way Platform"),
+                ChildOf(level_geometry),
+                OneWayPlatform,
+                RigidBody::Static,
+                CollisionLayers::level_geometry(),
+                // The terrain tileset has no platform tiles yet.
+                Sprite::from_color(PLATFORM_COLOR, pc.as_rect().size() * TILE_SIZE.0),
+                collider,
+                transform,
+            )
+        })
+        .collect()
+}
+
+fn hazards_batch(
+    level: &Level,
+    level_geometry: Entity,
+) -> Vec<impl Bundle<Effect: NoBundleEffect>> {
+    level
+        .hazard_colliders
+        .iter()
+        .map(|hc| {
+            let (collider, transform) = hc.collider.into_collider_and_transform(TILE_SIZE);
+            let (name, hazard, color) = match hc.kind {
+                HazardKind::Spikes => (
+                    "Spikes",
+                    Hazard {
+                        damage: 1.0,
+                        knockback: 8.0,
+                    },
+                    SPIKES_COLOR,
+                ),
+                HazardKind::Lava => (
+                    "Lava",
+                    Hazard {
+                        damage: 2.0,
+                        knockback: 12.0,
+                    },
+                    LAVA_COLOR,
+                ),
+            };
+            (
+                Name::new(name),
+                ChildOf(level_geometry),
+                hazard,
+                Sensor,
+                CollisionLayers::hazard(),
+                // The terrain tileset has no hazard tiles yet.
+                Sprite::from_color(color, hc.collider.as_rect().size() * TILE_SIZE.0),
+                collider,
+                transform,
+            )
+        })
+        .collect()
+}
+
+fn surfaces_batch(
+    level: &Level,
+    level_geometry: Entity,
+) -> Vec<impl Bundle<Effect: NoBundleEffect>> {
+    level
+        .surface_colliders
+        .iter()
+        .map(|sc| {
+            let (collider, transform) = sc.collider.into_collider_and_transform(TILE_SIZE);
+            let (name, material, color) = match sc.kind {
+                SurfaceKind::Ice => (
+                    "Ice",
+                    SurfaceMaterial {
+                        friction: 0.1,
+                        bounciness: 0.0,
+                        footstep_speed: 1.4,
+                        footstep_volume: 0.8,
+                        hardness: 0.5,
+                    },
+                    ICE_COLOR,
+                ),
+                SurfaceKind::Mud => (
+                    "Mud",
+                    SurfaceMaterial {
+                        friction: 2.5,
+                        bounciness: 0.0,
+                        footstep_speed: 0.7,
+                        footstep_volume: 0.6,
+                        hardness: 0.25,
+                    },
+                    MUD_COLOR,
+                ),
+                SurfaceKind::Bouncy => (
+                    "Bouncy Surface",
+                    SurfaceMaterial {
+                        friction: 1.0,
+                        bounciness: 0.7,
+                        footstep_speed: 1.2,
+                        footstep_volume: 1.0,
+                        hardness: 1.0,
+                    },
+                    BOUNCY_COLOR,
+                ),
+            };
+            (
+                Name::new(name),
+                ChildOf(level_geometry),
+                RigidBody::Static,
+                CollisionLayers::level_geometry(),
+                // Loose bodies slide and bounce on it too
+                Friction::new(0.5 * material.friction),
+                Restitution::new(material.bounciness),
+                material,
+                // The terrain tileset has no surface tiles yet.
+                Sprite::from_color(color, sc.collider.as_rect().size() * TILE_SIZE.0),
+                collider,
+                transform,
+            )
+        })
+        .collect()
+}
+
+fn moving_platforms_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .moving_platforms
+        .iter()
+        .map(|platform| {
+            (
+                Name::new("Moving Platform"),
+                MovingPlatform::new(platform.waypoints.clone(), platform.speed, platform.easing),
+                CollisionLayers::level_geometry(),
+                Collider::rectangle(platform.size.x, platform.size.y),
+                Sprite::from_color(PLATFORM_COLOR, platform.size),
+                Transform::from_translation(platform.waypoints[0].extend(0.0)),
+            )
+        })
+        .collect()
+}
+
+fn water_volumes_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .water_volumes
+        .iter()
+        .map(|water| {
+            (
+                Name::new("Water Volume"),
+                WaterVolume {
+                    size: water.rect.size(),
+                    buoyancy: water.buoyancy,
+                },
+                Sprite::from_color(WATER_COLOR, water.rect.size()),
+                // In front of characters, so they look submerged.
+                Transform::from_translation(water.rect.center().extend(1.0)),
+            )
+        })
+        .collect()
+}
+
+/// The fields of a `Gravity_Well` LDtk entity.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GravityWellFields {
+    #[serde(default = "GravityWellFields::default_strength")]
+    strength: f32,
+    /// In tiles.
+    #[serde(default = "GravityWellFields::default_radius")]
+    radius: f32,
+}
+
+impl GravityWellFields {
+    fn default_strength() -> f32 {
+        50.0
+    }
+
+    fn default_radius() -> f32 {
+        8.0
+    }
+}
+
+fn gravity_well(fields: GravityWellFields, entity: &LdtkEntitySpawn) -> impl Bundle + use<> {
+    (
+        Name::new("Gravity Well"),
+        GravityWell {
+            strength: fields.strength,
+            radius: WorldMeters::from_tiles(fields.radius).0,
+        },
+        Transform::from_translation(entity.position.extend(0.0)),
+    )
+}
+
+fn light_zones_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .light_zones
+        .iter()
+        .map(|zone| {
+            (
+                Name::new("Speed of Light Zone"),
+                SpeedOfLightZone {
+                    speed_of_light: zone.speed_of_light,
+                    size: zone.rect.size(),
+                },
+                Transform::from_translation(zone.rect.center().extend(0.0)),
+            )
+        })
+        .collect()
+}
+
+fn zero_gravity_zones_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .zero_gravity_zones
+        .iter()
+        .map(|zone| {
+            (
+                Name::new("Zero Gravity Zone"),
+                ZeroGravityZone {
+                    size: zone.rect.size(),
+                },
+                Sprite::from_color(ZERO_GRAVITY_COLOR, zone.rect.size()),
+                // Behind characters, as a backdrop.
+                Transform::from_translation(zone.rect.center().extend(-1.0)),
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "dev_native")]
+pub(super) mod hot_reload {
+    use bevy::asset::AssetEventSystems;
+
+    use super::*;
+
+    pub fn plugin(app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            reload_level
+                .run_if(on_message::<AssetEvent<Level>>)
+                .after(AssetEventSystems),
+        );
+    }
+
+    fn reload_level(
+        mut asset_events: MessageReader<AssetEvent<Level>>,
+        levels: Res<Assets<Level>>,
+        level_handle: Single<&CurrentLevel>,
+        level_geometry: Single<(Entity, &Children), With<LevelGeometry>>,
+        config: Res<GameConfig>,
+        mut commands: Commands,
+    ) {
+        for ev in asset_events.read() {
+            match ev {
+                &AssetEvent::Modified { id } if id == level_handle.id() => {
+                    let level = levels.get(id).unwrap();
+                    info!("Reloading level {:?}", level.name);
+
+                    // Despawn existing tilemap and colliders
+                    let despawn_batch: Vec<_> = level_geometry.1.iter().collect();
+
+                    commands.queue(move |world: &mut World| {
+                        despawn_batch.into_iter().for_each(|entity| {
+                            world.despawn(entity);
+                        })
+                    });
+
+                    // Spawn tilemap
+                    commands.spawn((tilemap(level), ChildOf(level_geometry.0)));
+
+                    // Spawn new terrain colliders
+                    commands.spawn_batch(colliders_batch(
+                        level,
+                        level_geometry.0,
+                        config.terrain_colliders,
+                    ));
+                    commands.spawn_batch(platforms_batch(level, level_geometry.0));
+                    commands.spawn_batch(hazards_batch(level, level_geometry.0));
+                    commands.spawn_batch(surfaces_batch(level, level_geometry.0));
+                }
+                _ => {}
+            }
+        }
+    }
+}
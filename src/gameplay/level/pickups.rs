@@ -0,0 +1,267 @@
+//! Collectibles, the score, and per-level completion stats.
+//!
+//! Collectibles are placed with `Item` LDtk entities or dropped as loot. Touching one adds its
+//! value to the [`Score`], which lasts for the whole gameplay session, and to the current
+//! [`LevelStats`], which start over with each level. There is no pickup sound yet, but one can be
+//! played from [`collect_pickups`].
+//!
+//! Items marked `Persistent` in LDtk, like keys, are also added to the [`CurrentRun`] when
+//! collected during a run, and are carried from then on. Like any collected item, the level's
+//! event log keeps them from respawning when their level is spawned again.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::level::Level,
+    gameplay::{
+        level::{
+            CurrentLevel,
+            hub::{CarriedItem, CurrentRun},
+            state::{LevelEvent, LevelEventLog, LevelIid, RecordLevelEvent},
+        },
+        player::Player,
+    },
+    physics::GamePhysicsLayersExt,
+    save::SaveData,
+    screens::{Area, Screen},
+};
+
+const PICKUP_RANGE: f32 = 0.75;
+const ITEM_SIZE: f32 = 0.4;
+const ITEM_COLOR: Color = Color::srgb(1.0, 0.84, 0.0);
+const SPARKLE_COUNT: usize = 6;
+const SPARKLE_SIZE: f32 = 0.12;
+const SPARKLE_LIFETIME_SECS: f32 = 0.4;
+const LOOT_RADIUS: f32 = 0.2;
+const LOOT_POP_SPEED: f32 = 6.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Score>()
+        .init_resource::<LevelStats>()
+        .add_observer(reset_level_stats)
+        .add_systems(OnEnter(Screen::Gameplay), reset_score);
+
+    app.add_systems(
+        Update,
+        (
+            tick_level_stats.in_set(AppSystems::TickTimers),
+            (collect_pickups, update_sparkles).in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Points collected over the whole gameplay session.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct Score(pub u32);
+
+/// Completion stats for the level the player is in. These are reset when a level spawns, so they
+/// can still be read when leaving it.
+#[derive(Resource, Reflect, Default, Debug, Clone)]
+#[reflect(Resource)]
+pub struct LevelStats {
+    /// Points collected in this level.
+    pub score: u32,
+    /// How many of the level's placed items have been collected. Loot doesn't count.
+    pub items_collected: usize,
+    pub items_total: usize,
+    pub elapsed_secs: f32,
+}
+
+impl LevelStats {
+    pub fn all_items_collected(&self) -> bool {
+        self.items_collected >= self.items_total
+    }
+}
+
+/// Something the player can pick up for points.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Collectible {
+    pub item: String,
+    pub value: u32,
+}
+
+impl Collectible {
+    pub fn new(item: impl Into<String>) -> Self {
+        let item = item.into();
+        let value = match item.as_str() {
+            "gold_coin" => 10,
+            _ => 1,
+        };
+        Self { item, value }
+    }
+}
+
+/// Marks a [`Collectible`] that was placed in the level, rather than dropped.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+struct LevelItem {
+    persistent: bool,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Sparkle {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+/// A collectible `item` at `position`, without any physics.
+pub fn collectible(item: &str, position: Vec2) -> impl Bundle + use<> {
+    (
+        Name::new(format!("Item: {item}")),
+        Collectible::new(item),
+        Sprite::from_color(ITEM_COLOR, Vec2::splat(ITEM_SIZE)),
+        Transform::from_translation(position.extend(0.5)),
+    )
+}
+
+/// A collectible `item` dropped as loot at `position`, popping up in a random direction and
+/// falling to the ground.
+pub fn loot_drop<R: Rng>(item: &str, position: Vec2, rng: &mut R) -> impl Bundle + use<R> {
+    let velocity =
+        LOOT_POP_SPEED * Vec2::new(rng.random_range(-0.5..0.5), rng.random_range(0.5..1.0));
+    (
+        collectible(item, position),
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        Collider::circle(LOOT_RADIUS),
+        CollisionLayers::item(),
+        LinearVelocity(velocity),
+    )
+}
+
+pub fn items_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .items
+        .iter()
+        .map(|spawn| {
+            (
+                collectible(&spawn.item, spawn.position),
+                LevelItem {
+                    persistent: spawn.persistent,
+                },
+                LevelIid(spawn.iid.clone()),
+            )
+        })
+        .collect()
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    score.0 = 0;
+}
+
+fn reset_level_stats(
+    add: On<Add, CurrentLevel>,
+    current_levels: Query<&CurrentLevel>,
+    levels: Res<Assets<Level>>,
+    area: Option<Res<State<Area>>>,
+    save: Res<SaveData>,
+    run: Option<Res<CurrentRun>>,
+    mut stats: ResMut<LevelStats>,
+) {
+    // Items collected on an earlier visit are replayed away, so they don't count.
+    let log = LevelEventLog::current(area.as_deref(), &save, run.as_deref());
+    let items_total = current_levels
+        .get(add.entity)
+        .ok()
+        .and_then(|current| levels.get(current.id()))
+        .map_or(0, |level| {
+            level
+                .items
+                .iter()
+                .filter(|spawn| !log.is_some_and(|log| log.removes(&spawn.iid)))
+                .count()
+        });
+    *stats = LevelStats {
+        items_total,
+        ..default()
+    };
+}
+
+fn tick_level_stats(time: Res<Time>, mut stats: ResMut<LevelStats>) {
+    stats.elapsed_secs += time.delta_secs();
+}
+
+fn collect_pickups(
+    players: Query<&GlobalTransform, With<Player>>,
+    items: Query<(
+        Entity,
+        &Collectible,
+        &GlobalTransform,
+        Option<&LevelItem>,
+        Option<&LevelIid>,
+    )>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<LevelStats>,
+    mut run: Option<ResMut<CurrentRun>>,
+    mut commands: Commands,
+) {
+    let rng = &mut rand::rng();
+    for (entity, collectible, transform, level_item, iid) in &items {
+        let position = transform.translation().xy();
+        if !players
+            .iter()
+            .any(|player| player.translation().xy().distance(position) < PICKUP_RANGE)
+        {
+            continue;
+        }
+
+        score.0 += collectible.value;
+        stats.score += collectible.value;
+        if let Some(level_item) = level_item {
+            stats.items_collected += 1;
+            if level_item.persistent
+                && let Some(run) = run.as_mut()
+                && let Some(iid) = iid
+            {
+                run.carried.push(CarriedItem {
+                    iid: iid.0.clone(),
+                    item: collectible.item.clone(),
+                });
+            }
+        }
+        if let Some(iid) = iid {
+            commands.trigger(RecordLevelEvent(LevelEvent::ItemCollected(iid.0.clone())));
+        }
+        commands.entity(entity).despawn();
+
+        for _ in 0..SPARKLE_COUNT {
+            let velocity = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU))
+                * rng.random_range(1.0..3.0);
+            commands.spawn((
+                Name::new("Sparkle"),
+                Sparkle {
+                    velocity,
+                    lifetime: Timer::from_seconds(SPARKLE_LIFETIME_SECS, TimerMode::Once),
+                },
+                Sprite::from_color(ITEM_COLOR, Vec2::splat(SPARKLE_SIZE)),
+                Transform::from_translation(position.extend(2.0)),
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+fn update_sparkles(
+    time: Res<Time>,
+    mut sparkles: Query<(Entity, &mut Sparkle, &mut Transform, &mut Sprite)>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut sparkle, mut transform, mut sprite) in &mut sparkles {
+        if sparkle.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += (sparkle.velocity * dt).extend(0.0);
+        sprite.color.set_alpha(1.0 - sparkle.lifetime.fraction());
+    }
+}
@@ -0,0 +1,187 @@
+//! Breakable props, like crates and pots, that drop loot when destroyed.
+//!
+//! Props are placed with `Prop` LDtk entities. They get knocked around like any other dynamic
+//! body, and when their [`Health`] runs out they shatter into debris and roll their loot table.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    assets::{level::Level, loot::LootTables},
+    gameplay::{
+        combat::{DespawnOnDeath, Died, Health},
+        level::{pickups::loot_drop, state::LevelIid},
+    },
+    physics::GamePhysicsLayersExt,
+    screens::Screen,
+};
+
+const DEBRIS_COUNT: usize = 8;
+const DEBRIS_LIFETIME_SECS: f32 = 0.8;
+const DEBRIS_GRAVITY: f32 = 20.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<PropAssets>();
+    app.add_observer(break_prop);
+    app.add_systems(
+        Update,
+        update_debris
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PropAssets {
+    #[dependency]
+    loot_tables: Handle<LootTables>,
+}
+
+impl FromWorld for PropAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            loot_tables: assets.load("props.loot.json"),
+        }
+    }
+}
+
+/// A breakable object that rolls [`loot_table`] when it dies.
+///
+/// [`loot_table`]: Self::loot_table
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Prop {
+    pub loot_table: String,
+}
+
+/// A piece of a broken [`Prop`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Debris {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+/// What each kind of prop looks like and how tough it is.
+struct PropKind {
+    size: Vec2,
+    health: f32,
+    color: Color,
+}
+
+impl PropKind {
+    fn get(kind: &str) -> Option<Self> {
+        match kind {
+            "crate" => Some(Self {
+                size: Vec2::splat(0.9),
+                health: 2.0,
+                color: Color::srgb(0.6, 0.42, 0.22),
+            }),
+            "pot" => Some(Self {
+                size: Vec2::new(0.6, 0.7),
+                health: 1.0,
+                color: Color::srgb(0.72, 0.45, 0.35),
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub fn props_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .props
+        .iter()
+        .filter_map(|spawn| {
+            let Some(kind) = PropKind::get(&spawn.kind) else {
+                warn!("Unknown prop kind: {:?}", spawn.kind);
+                return None;
+            };
+
+            Some((
+                Name::new(format!("Prop: {}", spawn.kind)),
+                Prop {
+                    loot_table: spawn.loot_table.clone(),
+                },
+                LevelIid(spawn.iid.clone()),
+                Health::new(kind.health),
+                DespawnOnDeath,
+                RigidBody::Dynamic,
+                LockedAxes::ROTATION_LOCKED,
+                Collider::rectangle(kind.size.x, kind.size.y),
+                CollisionLayers::level_geometry(),
+                Sprite::from_color(kind.color, kind.size),
+                Transform::from_translation(spawn.position.extend(0.5)),
+            ))
+        })
+        .collect()
+}
+
+fn break_prop(
+    died: On<Died>,
+    props: Query<(&Prop, &Sprite, &Transform, &ChildOf)>,
+    prop_assets: Res<PropAssets>,
+    loot_tables: Res<Assets<LootTables>>,
+    mut commands: Commands,
+) {
+    let Ok((prop, sprite, transform, child_of)) = props.get(died.entity) else {
+        return;
+    };
+    let position = transform.translation.xy();
+    let rng = &mut rand::rng();
+
+    for _ in 0..DEBRIS_COUNT {
+        let velocity = Vec2::new(rng.random_range(-4.0..4.0), rng.random_range(2.0..8.0));
+        let size = Vec2::splat(rng.random_range(0.1..0.3));
+        commands.spawn((
+            Name::new("Debris"),
+            Debris {
+                velocity,
+                lifetime: Timer::from_seconds(DEBRIS_LIFETIME_SECS, TimerMode::Once),
+            },
+            Sprite::from_color(sprite.color, size),
+            Transform::from_translation(position.extend(2.0)),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+
+    let Some(table) = loot_tables
+        .get(&prop_assets.loot_tables)
+        .and_then(|tables| tables.get(&prop.loot_table))
+    else {
+        warn!("Unknown loot table: {:?}", prop.loot_table);
+        return;
+    };
+
+    for (item, count) in table.roll(rng) {
+        for _ in 0..count {
+            commands.spawn((
+                loot_drop(item, position, rng),
+                // Drops belong to the level, like the prop they came from.
+                ChildOf(child_of.parent()),
+            ));
+        }
+    }
+}
+
+fn update_debris(
+    time: Res<Time>,
+    mut debris: Query<(Entity, &mut Debris, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut debris, mut transform) in &mut debris {
+        if debris.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        debris.velocity.y -= DEBRIS_GRAVITY * dt;
+        transform.translation += (debris.velocity * dt).extend(0.0);
+    }
+}
@@ -0,0 +1,154 @@
+//! Level state that changes during play, kept as a list of events per level.
+//!
+//! Entities placed in a level carry their LDtk instance id as a [`LevelIid`]. When one of them is
+//! gone for good, like a defeated enemy, a broken prop, a collected item or an opened door, a
+//! [`LevelEvent`] is appended to the level's [`LevelEventLog`] by triggering [`RecordLevelEvent`].
+//! Whenever the level is spawned, its log is replayed onto the fresh entities, so it comes back the
+//! way it was left. The hub's log is kept in the [`SaveData`], while a level entered from it logs
+//! into the [`CurrentRun`] and starts fresh with every run.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        combat::Died,
+        enemies::EnemyHandle,
+        level::{hub::CurrentRun, keys::KeyColor, props::Prop},
+    },
+    save::SaveData,
+    screens::Area,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(record_level_event)
+        .add_observer(record_deaths)
+        .add_observer(replay_level_events);
+}
+
+/// Something that happened in a level and should still be true when it is spawned again.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, PartialEq, Eq)]
+pub enum LevelEvent {
+    /// The enemy with this LDtk instance id was defeated.
+    EnemyDefeated(String),
+    /// The prop with this LDtk instance id was broken.
+    PropBroken(String),
+    /// The item with this LDtk instance id was picked up.
+    ItemCollected(String),
+    /// A key of this color, with this LDtk instance id, was picked up.
+    KeyCollected { iid: String, color: KeyColor },
+    /// The locked door of this color, with this LDtk instance id, was opened with a key.
+    DoorOpened { iid: String, color: KeyColor },
+    /// The hub door with this id was unlocked.
+    DoorUnlocked(String),
+}
+
+impl LevelEvent {
+    /// Whether replaying the event despawns the entity with the LDtk instance id `iid`.
+    pub fn removes(&self, iid: &str) -> bool {
+        match self {
+            LevelEvent::EnemyDefeated(id)
+            | LevelEvent::PropBroken(id)
+            | LevelEvent::ItemCollected(id)
+            | LevelEvent::KeyCollected { iid: id, .. }
+            | LevelEvent::DoorOpened { iid: id, .. } => id == iid,
+            LevelEvent::DoorUnlocked(_) => false,
+        }
+    }
+}
+
+/// The events of a single level, in the order they happened.
+#[derive(Serialize, Deserialize, Reflect, Default, Debug, Clone)]
+#[serde(transparent)]
+pub struct LevelEventLog(Vec<LevelEvent>);
+
+impl LevelEventLog {
+    pub fn record(&mut self, event: LevelEvent) {
+        self.0.push(event);
+    }
+
+    pub fn contains(&self, event: &LevelEvent) -> bool {
+        self.0.contains(event)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LevelEvent> {
+        self.0.iter()
+    }
+
+    /// Whether replaying the log despawns the entity with the LDtk instance id `iid`.
+    pub fn removes(&self, iid: &str) -> bool {
+        self.iter().any(|event| event.removes(iid))
+    }
+
+    /// The log of the level being played in `area`: the hub's from the `save`, or the `run`'s.
+    pub fn current<'a>(
+        area: Option<&State<Area>>,
+        save: &'a SaveData,
+        run: Option<&'a CurrentRun>,
+    ) -> Option<&'a Self> {
+        match (area.map(State::get), run) {
+            (Some(Area::Hub), _) => Some(&save.hub.events),
+            (Some(Area::Level), Some(run)) => Some(&run.events),
+            _ => None,
+        }
+    }
+}
+
+/// The LDtk instance id of an entity placed in a level, which its [`LevelEvent`]s refer to.
+#[derive(Component, Reflect, Debug, Clone, Deref)]
+#[reflect(Component)]
+pub struct LevelIid(pub String);
+
+/// Appends an event to the log of the level being played.
+#[derive(Event, Debug, Clone)]
+pub struct RecordLevelEvent(pub LevelEvent);
+
+fn record_level_event(
+    record: On<RecordLevelEvent>,
+    area: Option<Res<State<Area>>>,
+    mut save: ResMut<SaveData>,
+    run: Option<ResMut<CurrentRun>>,
+) {
+    match (area.map(|area| *area.get()), run) {
+        (Some(Area::Hub), _) => save.hub.events.record(record.0.clone()),
+        (Some(Area::Level), Some(mut run)) => run.events.record(record.0.clone()),
+        _ => {}
+    }
+}
+
+/// Records the deaths of enemies and props placed in the level.
+fn record_deaths(
+    died: On<Died>,
+    placed: Query<(&LevelIid, Has<EnemyHandle>, Has<Prop>)>,
+    mut commands: Commands,
+) {
+    let Ok((iid, is_enemy, is_prop)) = placed.get(died.entity) else {
+        return;
+    };
+
+    let event = if is_enemy {
+        LevelEvent::EnemyDefeated(iid.0.clone())
+    } else if is_prop {
+        LevelEvent::PropBroken(iid.0.clone())
+    } else {
+        return;
+    };
+    commands.trigger(RecordLevelEvent(event));
+}
+
+/// Despawns placed entities that the log of the level being played says are gone.
+fn replay_level_events(
+    add: On<Add, LevelIid>,
+    placed: Query<&LevelIid>,
+    area: Option<Res<State<Area>>>,
+    save: Res<SaveData>,
+    run: Option<Res<CurrentRun>>,
+    mut commands: Commands,
+) {
+    if let Some(log) = LevelEventLog::current(area.as_deref(), &save, run.as_deref())
+        && let Ok(iid) = placed.get(add.entity)
+        && log.removes(iid)
+    {
+        commands.entity(add.entity).despawn();
+    }
+}
@@ -0,0 +1,118 @@
+//! Statistics for a whole run, shown on the results screen when it's finished.
+//!
+//! [`GameStats`] start over whenever a run does. The [`LevelStats`] of each level are copied into
+//! it as the level is finished, along with how many times the player died there. The top speed
+//! reached is tracked over the whole run.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    PausableSystems,
+    assets::level::Level,
+    gameplay::{
+        level::{exit::LevelFinished, pickups::LevelStats},
+        player::{Player, spectator::PlayerDied},
+    },
+    physics::{LocalSpeedOfLight, beta},
+    screens::Area,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameStats>()
+        .add_observer(record_finished_level)
+        .add_observer(count_deaths)
+        .add_systems(OnEnter(Area::Level), reset_game_stats);
+
+    app.add_systems(
+        Update,
+        track_max_beta
+            .run_if(in_state(Area::Level))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Stats for the current or most recent run.
+#[derive(Resource, Reflect, Default, Debug, Clone)]
+#[reflect(Resource)]
+pub struct GameStats {
+    /// Every level finished so far, in the order they were played.
+    pub levels: Vec<LevelRecord>,
+    /// The player's top speed, as a fraction of the local speed of light.
+    pub max_beta: f32,
+    /// Deaths in the level being played, until it's finished.
+    deaths_in_level: u32,
+}
+
+impl GameStats {
+    pub fn total_secs(&self) -> f32 {
+        self.levels.iter().map(|level| level.elapsed_secs).sum()
+    }
+
+    pub fn total_deaths(&self) -> u32 {
+        self.levels.iter().map(|level| level.deaths).sum()
+    }
+
+    pub fn total_score(&self) -> u32 {
+        self.levels.iter().map(|level| level.score).sum()
+    }
+
+    /// How many of the placed items were collected, and how many there were, over every level.
+    pub fn total_items(&self) -> (usize, usize) {
+        self.levels
+            .iter()
+            .fold((0, 0), |(collected, total), level| {
+                (collected + level.items_collected, total + level.items_total)
+            })
+    }
+}
+
+/// The stats of one finished level.
+#[derive(Reflect, Debug, Clone)]
+pub struct LevelRecord {
+    pub name: String,
+    pub elapsed_secs: f32,
+    pub score: u32,
+    pub items_collected: usize,
+    pub items_total: usize,
+    pub deaths: u32,
+}
+
+fn reset_game_stats(mut stats: ResMut<GameStats>) {
+    *stats = default();
+}
+
+fn record_finished_level(
+    finished: On<LevelFinished>,
+    levels: Res<Assets<Level>>,
+    level_stats: Res<LevelStats>,
+    mut stats: ResMut<GameStats>,
+) {
+    let name = levels
+        .get(&finished.level)
+        .map_or_else(|| "Unknown".to_string(), |level| level.name.clone());
+    let deaths = std::mem::take(&mut stats.deaths_in_level);
+    stats.levels.push(LevelRecord {
+        name,
+        elapsed_secs: level_stats.elapsed_secs,
+        score: level_stats.score,
+        items_collected: level_stats.items_collected,
+        items_total: level_stats.items_total,
+        deaths,
+    });
+}
+
+fn count_deaths(_: On<PlayerDied>, mut stats: ResMut<GameStats>) {
+    stats.deaths_in_level += 1;
+}
+
+fn track_max_beta(
+    c: Res<LocalSpeedOfLight>,
+    player: Single<&LinearVelocity, With<Player>>,
+    mut stats: ResMut<GameStats>,
+) {
+    let b = beta(player.length(), c.0);
+    if b > stats.max_beta {
+        stats.max_beta = b;
+    }
+}
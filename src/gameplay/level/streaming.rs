@@ -0,0 +1,127 @@
+//! Streaming in the levels next to the current one, using the neighbour links of the LDtk world.
+//!
+//! Neighbours are spawned at their offset from the current level in the world once a player gets
+//! close to them, and despawned again once every player is far enough away. Only their geometry
+//! is streamed; their entities are spawned when the level itself is played.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::{level::Level, world::LdtkWorld},
+    config::GameConfig,
+    gameplay::{
+        level::{CurrentLevel, LevelAssets, spawn_level_geometry},
+        player::Player,
+    },
+    screens::{Area, Screen},
+    units::TILE_SIZE,
+};
+
+/// Neighbours closer than this to a player are spawned, in tiles.
+const STREAM_IN_DISTANCE: f32 = 12.0;
+/// Neighbours farther than this from every player are despawned, in tiles. This is larger than
+/// [`STREAM_IN_DISTANCE`] so levels don't flicker in and out at the edge.
+const STREAM_OUT_DISTANCE: f32 = 20.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        stream_neighbour_levels
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The geometry of a level next to the [`CurrentLevel`].
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct StreamedLevel {
+    pub level: Handle<Level>,
+    /// The current level this one was placed relative to.
+    pub anchor: AssetId<Level>,
+}
+
+fn stream_neighbour_levels(
+    current_level: Single<(&CurrentLevel, &GlobalTransform)>,
+    streamed_levels: Query<(Entity, &StreamedLevel)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    area: Res<State<Area>>,
+    level_assets: Res<LevelAssets>,
+    config: Res<GameConfig>,
+    worlds: Res<Assets<LdtkWorld>>,
+    levels: Res<Assets<Level>>,
+    mut commands: Commands,
+) {
+    let (current_handle, current_transform) = *current_level;
+    let Some(current) = levels.get(&**current_handle) else {
+        return;
+    };
+    let Some(world) = worlds.get(&level_assets.world) else {
+        return;
+    };
+
+    // Where a level sits relative to the current one, and how far the nearest player is from it
+    let origin = current_transform.translation().xy();
+    let level_offset =
+        |level: &Level| (level.grid_offset - current.grid_offset).as_vec2() * TILE_SIZE.0;
+    let player_distance = |level: &Level| {
+        let min = origin + level_offset(level);
+        let rect = Rect::from_corners(min, min + level.grid_size.as_vec2() * TILE_SIZE.0);
+        players
+            .iter()
+            .map(|player| {
+                let position = player.translation().xy();
+                position.distance(rect.min.max(position.min(rect.max)))
+            })
+            .reduce(f32::min)
+            .map_or(f32::INFINITY, |distance| distance / TILE_SIZE.0)
+    };
+
+    let neighbours: Vec<_> = world.neighbours(current_handle.id()).collect();
+
+    for (entity, streamed) in &streamed_levels {
+        let keep = streamed.anchor == current_handle.id()
+            && neighbours
+                .iter()
+                .any(|neighbour| neighbour.level.id() == streamed.level.id())
+            && levels
+                .get(&streamed.level)
+                .is_some_and(|level| player_distance(level) <= STREAM_OUT_DISTANCE);
+        if !keep {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for neighbour in neighbours {
+        if streamed_levels
+            .iter()
+            .any(|(_, streamed)| streamed.level.id() == neighbour.level.id())
+        {
+            continue;
+        }
+        let Some(level) = levels.get(&neighbour.level) else {
+            continue;
+        };
+        if player_distance(level) > STREAM_IN_DISTANCE {
+            continue;
+        }
+
+        debug!("Streaming in level {:?}", level.name);
+        commands
+            .spawn((
+                Name::new(format!("Streamed Level: {}", level.name)),
+                StreamedLevel {
+                    level: neighbour.level.clone(),
+                    anchor: current_handle.id(),
+                },
+                Transform::from_translation((origin + level_offset(level)).extend(0.0)),
+                Visibility::default(),
+                DespawnOnExit(*area.get()),
+            ))
+            .with_children(|children| {
+                spawn_level_geometry(children, level, (), config.terrain_colliders)
+            });
+    }
+}
@@ -0,0 +1,192 @@
+//! The terrain tilemap, split into chunks so that editing tiles only re-uploads the chunks
+//! touched.
+//!
+//! Each [`TilemapChunk`] uploads all of its tile data whenever any of it changes, which is
+//! wasteful for a whole level drawn as one chunk. Levels are instead drawn as a grid of chunks of
+//! at most [`CHUNK_SIZE`] tiles, and tiles are edited through the level-wide [`TerrainTiles`].
+//! Edits mark the chunks they fall in as dirty, and only dirty chunks have their tile data
+//! rewritten, once per frame in [`sync_terrain_chunks`].
+//!
+//! Tile animations are played this way too, so only chunks with a changing frame are uploaded.
+
+use bevy::{
+    ecs::spawn::SpawnIter,
+    prelude::*,
+    sprite_render::{AlphaMode2d, TileData, TilemapChunk, TilemapChunkTileData},
+};
+
+use crate::{
+    PausableSystems,
+    assets::level::{Level, TileAnimation},
+    screens::Screen,
+    units::TILE_SIZE,
+};
+
+/// The largest chunk the terrain is split into, in tiles.
+pub const CHUNK_SIZE: UVec2 = UVec2::splat(32);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        animate_tiles
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(PostUpdate, sync_terrain_chunks);
+}
+
+/// Every terrain tile in a level, indexed from its bottom-left corner.
+///
+/// Tiles should be edited through this rather than the tile data of the chunks, which is
+/// overwritten whenever the chunk is next synced.
+#[derive(Component, Debug)]
+pub struct TerrainTiles {
+    size: UVec2,
+    tiles: Vec<Option<TileData>>,
+    /// Whether each chunk has changed since it was last synced, row by row from the bottom-left.
+    dirty: Vec<bool>,
+}
+
+impl TerrainTiles {
+    fn new(size: UVec2, tiles: Vec<Option<TileData>>) -> Self {
+        Self {
+            size,
+            tiles,
+            dirty: vec![false; chunk_grid_size(size).element_product() as usize],
+        }
+    }
+
+    /// The tile at `position`, or `None` if it's empty or out of bounds.
+    pub fn get(&self, position: UVec2) -> Option<TileData> {
+        self.index(position).and_then(|i| self.tiles[i])
+    }
+
+    /// Replaces the tile at `position`, and marks its chunk as dirty. Positions out of bounds are
+    /// ignored.
+    pub fn set(&mut self, position: UVec2, tile: Option<TileData>) {
+        let Some(i) = self.index(position) else {
+            return;
+        };
+        self.tiles[i] = tile;
+        let chunk = position / CHUNK_SIZE;
+        self.dirty[(chunk.y * chunk_grid_size(self.size).x + chunk.x) as usize] = true;
+    }
+
+    fn index(&self, position: UVec2) -> Option<usize> {
+        (position.cmplt(self.size).all()).then(|| (position.y * self.size.x + position.x) as usize)
+    }
+
+    /// Copies the tiles of the chunk at `chunk` into `tile_data`.
+    fn copy_chunk(&self, chunk: UVec2, tile_data: &mut [Option<TileData>]) {
+        let min = chunk * CHUNK_SIZE;
+        let width = chunk_size(self.size, chunk).x as usize;
+        for (row, data) in tile_data.chunks_exact_mut(width).enumerate() {
+            let start = ((min.y as usize + row) * self.size.x as usize) + min.x as usize;
+            data.copy_from_slice(&self.tiles[start..start + width]);
+        }
+    }
+}
+
+/// One chunk of a level's [`TerrainTiles`], at the given position in the grid of chunks.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct TerrainChunk(pub UVec2);
+
+/// The animated cells of a tilemap.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct AnimatedTiles(pub Vec<TileAnimation>);
+
+/// How many chunks a tilemap of `size` tiles is split into, along each axis.
+fn chunk_grid_size(size: UVec2) -> UVec2 {
+    (size + CHUNK_SIZE - 1) / CHUNK_SIZE
+}
+
+/// The size of the chunk at `chunk` in a tilemap of `size` tiles. Chunks along the top and right
+/// edges may be smaller than [`CHUNK_SIZE`].
+fn chunk_size(size: UVec2, chunk: UVec2) -> UVec2 {
+    (size - chunk * CHUNK_SIZE).min(CHUNK_SIZE)
+}
+
+/// The level's terrain, drawn as a grid of chunks.
+pub fn tilemap(level: &Level) -> impl Bundle {
+    let size = level.grid_size;
+    let terrain = TerrainTiles::new(size, level.terrain_tiledata.0.clone());
+    let grid = chunk_grid_size(size);
+
+    let chunks = (0..grid.y)
+        .flat_map(|y| (0..grid.x).map(move |x| UVec2::new(x, y)))
+        .map(|chunk| {
+            let chunk_size = chunk_size(size, chunk);
+            let mut tile_data = vec![None; chunk_size.element_product() as usize];
+            terrain.copy_chunk(chunk, &mut tile_data);
+
+            // Relative to the center of the level, like the tilemap itself
+            let center = (chunk * CHUNK_SIZE).as_vec2() + chunk_size.as_vec2() / 2.0;
+            (
+                Name::new(format!("Terrain Chunk {chunk}")),
+                TerrainChunk(chunk),
+                Transform::from_translation((center - size.as_vec2() / 2.0).extend(0.0)),
+                TilemapChunk {
+                    tile_display_size: UVec2::ONE,
+                    chunk_size,
+                    tileset: level.terrain_tileset.clone(),
+                    alpha_mode: AlphaMode2d::Blend,
+                },
+                TilemapChunkTileData(tile_data),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    (
+        Name::new("Terrain Tilemap"),
+        Transform::from_translation((level.center_offset() * TILE_SIZE.0).extend(0.0))
+            .with_scale(Vec2::splat(TILE_SIZE.0).extend(1.0)),
+        Visibility::default(),
+        terrain,
+        AnimatedTiles(level.terrain_animations.clone()),
+        Children::spawn(SpawnIter(chunks.into_iter())),
+    )
+}
+
+fn animate_tiles(time: Res<Time>, mut tilemaps: Query<(&AnimatedTiles, &mut TerrainTiles)>) {
+    let elapsed = time.elapsed_secs();
+    for (animated, mut terrain) in &mut tilemaps {
+        let width = terrain.size.x as usize;
+        for animation in &animated.0 {
+            let step = (elapsed / animation.frame_secs) as usize;
+            for &(cell, start) in &animation.cells {
+                let index = animation.frames[(start + step) % animation.frames.len()];
+                let position = UVec2::new((cell % width) as u32, (cell / width) as u32);
+                // Only touch tiles whose frame changes, so their chunk isn't re-uploaded
+                if terrain
+                    .get(position)
+                    .is_some_and(|tile| tile.tileset_index != index)
+                {
+                    terrain.set(position, Some(TileData::from_tileset_index(index)));
+                }
+            }
+        }
+    }
+}
+
+fn sync_terrain_chunks(
+    mut tilemaps: Query<(&mut TerrainTiles, &Children), Changed<TerrainTiles>>,
+    mut chunks: Query<(&TerrainChunk, &mut TilemapChunkTileData)>,
+) {
+    for (mut terrain, children) in &mut tilemaps {
+        let terrain = terrain.bypass_change_detection();
+        if !terrain.dirty.contains(&true) {
+            continue;
+        }
+
+        let grid_width = chunk_grid_size(terrain.size).x;
+        let mut iter = chunks.iter_many_mut(children);
+        while let Some((chunk, mut tile_data)) = iter.fetch_next() {
+            let dirty = &mut terrain.dirty[(chunk.0.y * grid_width + chunk.0.x) as usize];
+            if std::mem::take(dirty) {
+                terrain.copy_chunk(chunk.0, &mut tile_data.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Trigger zones placed in LDtk, which report players entering and leaving them.
+//!
+//! Each zone triggers [`TriggerEntered`] and [`TriggerExited`] on itself, carrying its id and
+//! custom fields, so dialogue, cameras, and the like can react to a zone with an observer instead
+//! of a system of their own.
+
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{assets::level::Level, gameplay::player::Player, physics::GamePhysicsLayersExt};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(enter_trigger_zone)
+        .add_observer(exit_trigger_zone)
+        .add_observer(log_trigger_entered)
+        .add_observer(log_trigger_exited);
+}
+
+/// A sensor region that reports players entering and leaving it.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct TriggerZone {
+    /// The zone's `Id` field in LDtk, or its iid if that isn't set.
+    pub id: String,
+    /// The zone's other LDtk fields.
+    pub fields: HashMap<String, String>,
+}
+
+/// Triggered on a [`TriggerZone`] when a player enters it.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct TriggerEntered {
+    #[event_target]
+    pub zone: Entity,
+    pub player: Entity,
+    pub id: String,
+    pub fields: HashMap<String, String>,
+}
+
+/// Triggered on a [`TriggerZone`] when a player leaves it.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct TriggerExited {
+    #[event_target]
+    pub zone: Entity,
+    pub player: Entity,
+    pub id: String,
+    pub fields: HashMap<String, String>,
+}
+
+pub fn trigger_zones_vec(level: &Level) -> Vec<impl Bundle> {
+    level
+        .trigger_zones
+        .iter()
+        .map(|zone| {
+            let size = zone.rect.size();
+            (
+                Name::new(format!("Trigger Zone: {}", zone.id)),
+                TriggerZone {
+                    id: zone.id.clone(),
+                    fields: zone.fields.clone(),
+                },
+                Collider::rectangle(size.x, size.y),
+                CollisionLayers::trigger(),
+                Transform::from_translation(zone.rect.center().extend(0.0)),
+            )
+        })
+        .collect()
+}
+
+fn enter_trigger_zone(
+    collision: On<CollisionStart>,
+    zones: Query<&TriggerZone>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok(zone) = zones.get(collision.collider1) else {
+        return;
+    };
+    let player = collision.body2.unwrap_or(collision.collider2);
+    if players.contains(player) {
+        commands.trigger(TriggerEntered {
+            zone: collision.collider1,
+            player,
+            id: zone.id.clone(),
+            fields: zone.fields.clone(),
+        });
+    }
+}
+
+fn exit_trigger_zone(
+    collision: On<CollisionEnd>,
+    zones: Query<&TriggerZone>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok(zone) = zones.get(collision.collider1) else {
+        return;
+    };
+    let player = collision.body2.unwrap_or(collision.collider2);
+    if players.contains(player) {
+        commands.trigger(TriggerExited {
+            zone: collision.collider1,
+            player,
+            id: zone.id.clone(),
+            fields: zone.fields.clone(),
+        });
+    }
+}
+
+fn log_trigger_entered(entered: On<TriggerEntered>) {
+    debug!(
+        "Player {} entered trigger zone {:?} {:?}",
+        entered.player, entered.id, entered.fields
+    );
+}
+
+fn log_trigger_exited(exited: On<TriggerExited>) {
+    debug!(
+        "Player {} left trigger zone {:?} {:?}",
+        exited.player, exited.id, exited.fields
+    );
+}
@@ -0,0 +1,92 @@
+//! Splash effects for characters entering and leaving water.
+//!
+//! The physics of swimming live in the character controller; this only reacts to [`Splash`]
+//! events. There is no splash sound yet, but one can be played from the same observer.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    physics::{Splash, WaterVolume},
+    screens::Screen,
+};
+
+const DROPLET_COLOR: Color = Color::srgba(0.7, 0.85, 1.0, 0.8);
+const DROPLET_SIZE: f32 = 0.15;
+const DROPLET_LIFETIME_SECS: f32 = 0.6;
+const DROPLET_GRAVITY: f32 = 15.0;
+/// Splashes slower than this don't spawn any droplets.
+const MIN_SPLASH_SPEED: f32 = 2.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(spawn_splash_droplets);
+    app.add_systems(
+        Update,
+        update_droplets
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// A short-lived particle thrown up by a [`Splash`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Droplet {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+fn spawn_splash_droplets(
+    splash: On<Splash>,
+    volumes: Query<&Sprite, With<WaterVolume>>,
+    mut commands: Commands,
+) {
+    // Climbing out makes a smaller splash than diving in.
+    let speed = splash.velocity.y.abs() * if splash.entered { 1.0 } else { 0.5 };
+    if speed < MIN_SPLASH_SPEED {
+        return;
+    }
+
+    // Tint droplets like the water they came from.
+    let color = volumes.get(splash.volume).map_or(DROPLET_COLOR, |sprite| {
+        sprite.color.mix(&DROPLET_COLOR, 0.5)
+    });
+
+    let rng = &mut rand::rng();
+    let count = (speed as usize).min(12);
+    for _ in 0..count {
+        let velocity = Vec2::new(
+            rng.random_range(-0.5..0.5) * speed,
+            rng.random_range(0.3..0.7) * speed,
+        );
+        commands.spawn((
+            Name::new("Droplet"),
+            Droplet {
+                velocity,
+                lifetime: Timer::from_seconds(DROPLET_LIFETIME_SECS, TimerMode::Once),
+            },
+            Sprite::from_color(color, Vec2::splat(DROPLET_SIZE)),
+            Transform::from_translation(splash.position.extend(2.0)),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
+
+fn update_droplets(
+    time: Res<Time>,
+    mut droplets: Query<(Entity, &mut Droplet, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut droplet, mut transform) in &mut droplets {
+        if droplet.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        droplet.velocity.y -= DROPLET_GRAVITY * dt;
+        transform.translation += (droplet.velocity * dt).extend(0.0);
+    }
+}
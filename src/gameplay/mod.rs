@@ -0,0 +1,39 @@
+//! Gameplay, split into a plugin per feature.
+//!
+//! - [`level`] spawns the hub and levels, and everything placed in them.
+//! - [`player`] handles the player character, from input to death and respawning.
+//! - [`enemies`] spawns enemies and decides what they do.
+//! - [`camera`] follows the player and adds effects like screen shake.
+//! - [`combat`] deals damage, through contact, projectiles and explosions.
+//! - [`hud`] shows the player's health, the score and their speed.
+//!
+//! Characters of all kinds share [`movement`], and [`npc`]s reuse enemy definitions without
+//! fighting. Features talk to each other through the public items of these modules and through
+//! events such as [`DamageEvent`] and [`FireWeapon`], rather than through each other's internals.
+//!
+//! [`DamageEvent`]: combat::DamageEvent
+//! [`FireWeapon`]: combat::projectile::FireWeapon
+
+use bevy::prelude::*;
+
+pub mod camera;
+pub mod combat;
+pub mod enemies;
+pub mod hud;
+pub mod level;
+pub mod movement;
+pub mod npc;
+pub mod player;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        camera::plugin,
+        combat::plugin,
+        enemies::plugin,
+        hud::plugin,
+        level::plugin,
+        movement::plugin,
+        npc::plugin,
+        player::plugin,
+    ));
+}
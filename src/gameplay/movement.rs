@@ -0,0 +1,318 @@
+//! Handle player input and translate it into movement through a character
+//! controller. A character controller is the collection of systems that govern
+//! the movement of characters.
+//!
+//! In our case, the character controller has the following logic:
+//! - Set [`MovementController`] intent based on directional keyboard input.
+//!   This is done in the `player` module, as it is specific to the player
+//!   character.
+//! - Apply movement based on [`MovementController`] intent and maximum speed.
+//! - Wrap the character within the window.
+//!
+//! Note that the implementation used here is limited for demonstration
+//! purposes. If you want to move the player in a smoother way,
+//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/main/examples/movement/physics_in_fixed_timestep.rs).
+
+use std::f32::consts::TAU;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    PausableSystems,
+    controller::{Knockback, Stunned},
+    physics::{GamePhysicsLayers, LevelPhysics},
+};
+
+/// How strongly a hovering character is pulled back to its hover height.
+const HOVER_CORRECTION: f32 = 2.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(insert_archetype_controller)
+        .add_systems(FixedPreUpdate, update_grounded_caster_scales)
+        .add_systems(
+            FixedUpdate,
+            (
+                (apply_movement_damping, update_grounded, apply_movement).chain(),
+                apply_flight,
+            )
+                .in_set(PausableSystems),
+        );
+}
+
+/// A character that moves as its `archetype` says.
+///
+/// A bundle can't change shape depending on the archetype, so the branching happens when the
+/// [`MovementArchetype`] is inserted: walkers get a [`MovementController`], and fliers get a
+/// [`FlyingController`] and no gravity. Inserting a different archetype later swaps them over.
+pub fn movement_controller(
+    archetype: MovementArchetype,
+    collider: Collider,
+    offset: Vec2,
+    layers: CollisionLayers,
+) -> impl Bundle {
+    (
+        archetype,
+        Mass(1.5),
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        // Fliers never check for ground, but forward probes use the caster's shape.
+        ground_caster(collider.clone(), offset),
+        children![(
+            layers,
+            collider,
+            Transform::from_translation(offset.extend(0.0))
+        )],
+    )
+}
+
+/// Looks for ground just below a character's `collider`.
+pub fn ground_caster(collider: Collider, offset: Vec2) -> ShapeCaster {
+    ShapeCaster::new(collider, offset, 0.0, Dir2::NEG_Y).with_query_filter(
+        SpatialQueryFilter::from_mask(GamePhysicsLayers::LevelGeometry),
+    )
+}
+
+/// How a character gets around, along with its tunables.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub enum MovementArchetype {
+    /// Runs and jumps along the ground, under gravity.
+    Walking(MovementController),
+    /// Flies freely, ignoring gravity and the ground.
+    Flying(FlyingController),
+}
+
+impl Default for MovementArchetype {
+    fn default() -> Self {
+        Self::Walking(default())
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(MovementIntent, GroundNormal)]
+pub struct MovementController {
+    pub max_speed: f32,
+    pub accel_ground: f32,
+    pub accel_air: f32,
+    pub jump_strength: f32,
+    pub damping_factor_air: f32,
+    pub damping_factor_ground: f32,
+    pub max_slope_angle: f32,
+}
+
+impl Default for MovementController {
+    fn default() -> Self {
+        Self {
+            max_speed: 10.0,
+            accel_ground: 1.0,
+            accel_air: 0.1,
+            jump_strength: 20.,
+            damping_factor_air: 0.1,
+            damping_factor_ground: 0.9,
+            max_slope_angle: f32::to_radians(45.0),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(MovementIntent, FlightIntent)]
+pub struct FlyingController {
+    /// The top speed while hovering and pursuing.
+    pub max_speed: f32,
+    /// How quickly the character changes velocity, in units per second squared.
+    pub accel: f32,
+    /// How far above and below its hover height the character bobs.
+    pub hover_amplitude: f32,
+    /// How long one bob takes, in seconds.
+    pub hover_period_secs: f32,
+    /// The top speed while diving.
+    pub dive_speed: f32,
+}
+
+impl Default for FlyingController {
+    fn default() -> Self {
+        Self {
+            max_speed: 4.0,
+            accel: 20.0,
+            hover_amplitude: 0.5,
+            hover_period_secs: 2.0,
+            dive_speed: 12.0,
+        }
+    }
+}
+
+/// What a character with a [`FlyingController`] is doing, on top of its [`MovementIntent`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub enum FlightIntent {
+    /// Bobbing up and down around a height, while drifting sideways in the [`MovementIntent`]
+    /// direction. Without a height, the character bobs around wherever it is.
+    Hover(Option<f32>),
+    /// Flying straight at a point.
+    Pursue(Vec2),
+    /// Swooping at a point, faster than it can otherwise fly.
+    Dive(Vec2),
+}
+
+impl Default for FlightIntent {
+    fn default() -> Self {
+        Self::Hover(None)
+    }
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct MovementIntent {
+    pub direction: f32,
+    pub jump: bool,
+}
+
+#[derive(Component, Reflect, Default, Deref, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct GroundNormal(Option<Vec2>);
+
+impl GroundNormal {
+    pub fn is_grounded(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+fn insert_archetype_controller(
+    insert: On<Insert, MovementArchetype>,
+    archetypes: Query<&MovementArchetype>,
+    mut commands: Commands,
+) {
+    let Ok(archetype) = archetypes.get(insert.entity) else {
+        return;
+    };
+    let mut entity = commands.entity(insert.entity);
+    match archetype.clone() {
+        MovementArchetype::Walking(controller) => {
+            entity
+                .remove::<(FlyingController, FlightIntent)>()
+                .insert((controller, GravityScale(1.0)));
+        }
+        MovementArchetype::Flying(controller) => {
+            entity
+                .remove::<(MovementController, GroundNormal)>()
+                .insert((controller, GravityScale(0.0)));
+        }
+    }
+}
+
+fn update_grounded_caster_scales(
+    mut query: Query<(&GlobalTransform, &mut ShapeCaster), With<MovementArchetype>>,
+) {
+    for (transform, mut caster) in &mut query {
+        caster.shape.set_scale(0.9 * transform.scale().xy(), 10);
+        caster.max_distance = 0.1 * transform.scale().y;
+    }
+}
+
+fn update_grounded(mut controllers: Query<(&MovementController, &ShapeHits, &mut GroundNormal)>) {
+    for (controller, hits, mut ground_norm) in &mut controllers {
+        ground_norm.0 = hits
+            .iter()
+            .find(|hit| hit.normal1.angle_to(Vec2::Y).abs() < controller.max_slope_angle)
+            .map(|hit| hit.normal1);
+    }
+}
+
+fn apply_movement(
+    mut movement_query: Query<
+        (&MovementIntent, &MovementController, &GroundNormal, Forces),
+        (Without<Knockback>, Without<Stunned>),
+    >,
+) {
+    for (intent, controller, ground_norm, mut forces) in &mut movement_query {
+        // TODO: Clean this up
+
+        // Only allow acceleration if we're not at the max speed
+        let cur_speed = forces.linear_velocity().x * intent.direction;
+        if cur_speed < controller.max_speed {
+            let accel = if ground_norm.is_grounded() {
+                controller.accel_ground
+            } else {
+                controller.accel_air
+            };
+            forces.apply_linear_impulse(accel * intent.direction * Vec2::X);
+
+            // Don't accelerate past max speed
+            let new_speed = forces.linear_velocity().x * intent.direction;
+            if new_speed > controller.max_speed {
+                forces.linear_velocity_mut().x = controller.max_speed * intent.direction;
+            }
+        }
+
+        if let Some(normal) = ground_norm.0
+            && intent.jump
+        {
+            forces.apply_linear_impulse(controller.jump_strength * normal);
+        }
+    }
+}
+
+fn apply_movement_damping(
+    time: Res<Time>,
+    physics: Res<LevelPhysics>,
+    mut query: Query<(&MovementController, &GroundNormal, &mut LinearVelocity)>,
+) {
+    let dt = time.delta_secs() * physics.damping_scale;
+    for (controller, ground_norm, mut velocity) in &mut query {
+        let damping = if ground_norm.is_grounded() {
+            controller.damping_factor_ground
+        } else {
+            controller.damping_factor_air
+        };
+
+        velocity.x *= 1.0 / (1.0 + damping * dt);
+    }
+}
+
+fn apply_flight(
+    time: Res<Time>,
+    mut fliers: Query<
+        (
+            Entity,
+            &FlyingController,
+            &FlightIntent,
+            &MovementIntent,
+            &Position,
+            &mut LinearVelocity,
+        ),
+        (Without<Knockback>, Without<Stunned>),
+    >,
+) {
+    for (entity, controller, flight, intent, position, mut velocity) in &mut fliers {
+        let (target, accel) = match *flight {
+            FlightIntent::Hover(height) => {
+                // Offset each flier's bobbing, so groups don't move in lockstep.
+                let omega = TAU / controller.hover_period_secs.max(f32::EPSILON);
+                let phase = omega * time.elapsed_secs() + entity.index_u32() as f32;
+                let bob = controller.hover_amplitude * omega * phase.cos();
+                let correction = height.map_or(0.0, |height| {
+                    HOVER_CORRECTION
+                        * (height + controller.hover_amplitude * phase.sin() - position.y)
+                });
+                let target = Vec2::new(intent.direction * controller.max_speed, bob + correction);
+                (
+                    target.clamp_length_max(controller.max_speed),
+                    controller.accel,
+                )
+            }
+            FlightIntent::Pursue(point) => (
+                (point - position.0).normalize_or_zero() * controller.max_speed,
+                controller.accel,
+            ),
+            FlightIntent::Dive(point) => (
+                (point - position.0).normalize_or_zero() * controller.dive_speed,
+                // Dives commit hard to their heading.
+                2.0 * controller.accel,
+            ),
+        };
+        velocity.0 = velocity.0.move_towards(target, accel * time.delta_secs());
+    }
+}
@@ -0,0 +1,261 @@
+//! Non-hostile characters that walk between waypoints and can be talked to.
+//!
+//! NPCs reuse actor definitions from the enemy manifest for their visuals and movement, but never
+//! collide with or fight the player.
+
+use std::time::Duration;
+
+use avian2d::prelude::{CollisionLayers, LinearVelocity, Position};
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    animation::AnimationPlayer,
+    assets::{
+        enemy::{Enemy, EnemyManifest},
+        level::{Level, NpcSpawn},
+    },
+    gameplay::{
+        movement::{GroundNormal, MovementIntent, movement_controller},
+        player::Player,
+    },
+    input::Action,
+    physics::GamePhysicsLayersExt,
+    screens::Screen,
+};
+
+pub(super) const INTERACT_RANGE: f32 = 2.0;
+const ARRIVE_DISTANCE: f32 = 0.25;
+/// How many times per second an idle NPC might turn around to look about.
+const LOOK_AROUND_RATE: f64 = 0.2;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            interact_with_npcs
+                .in_set(AppSystems::RecordInput)
+                .run_if(input_just_pressed(Action::Interact)),
+            (update_npc_schedules, update_npc_animations)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+
+    app.add_observer(log_npc_dialogue);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[require(DialogueProgress)]
+pub struct Npc {
+    pub name: String,
+    /// Lines spoken on successive interactions. The last line is repeated once reached.
+    pub dialogue: Vec<String>,
+}
+
+/// The index of the next line of dialogue an [`Npc`] will speak.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct DialogueProgress(pub usize);
+
+/// The actor definition an [`Npc`] was spawned from.
+#[derive(Component, Reflect, Deref)]
+#[reflect(Component)]
+pub struct NpcActor(Handle<Enemy>);
+
+/// A looping route of waypoints, with a pause at each one.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NpcSchedule {
+    pub waypoints: Vec<Vec2>,
+    next: usize,
+    idle: Timer,
+}
+
+impl NpcSchedule {
+    pub fn new(waypoints: Vec<Vec2>, wait: Duration) -> Self {
+        Self {
+            waypoints,
+            next: 0,
+            idle: Timer::new(wait, TimerMode::Once),
+        }
+    }
+}
+
+/// Triggered on an [`Npc`] when the player interacts with it.
+///
+/// Dialogue and quest systems can observe this to react to specific NPCs.
+#[derive(EntityEvent, Reflect)]
+pub struct NpcInteraction {
+    #[event_target]
+    pub npc: Entity,
+    pub player: Entity,
+}
+
+pub fn npcs_vec(
+    enemy_manifest: &EnemyManifest,
+    enemies: &Assets<Enemy>,
+    level: &Level,
+) -> Vec<impl Bundle> {
+    level
+        .npc_spawns
+        .iter()
+        .filter_map(|spawn| {
+            let Some(handle) = enemy_manifest.enemies.get(&spawn.label) else {
+                warn!("Unknown NPC actor label: {:?}", spawn.label);
+                return None;
+            };
+
+            let actor = enemies.get(handle)?;
+            Some(npc(spawn, handle.clone(), actor))
+        })
+        .collect()
+}
+
+fn npc(spawn: &NpcSpawn, handle: Handle<Enemy>, actor: &Enemy) -> impl Bundle {
+    (
+        Name::new(format!("NPC: {}", spawn.name)),
+        Npc {
+            name: spawn.name.clone(),
+            dialogue: spawn
+                .dialogue
+                .iter()
+                .flat_map(|dialogue| dialogue.lines())
+                .map(str::to_string)
+                .collect(),
+        },
+        NpcActor(handle),
+        NpcSchedule::new(
+            spawn.waypoints.clone(),
+            Duration::from_secs_f32(spawn.wait_secs),
+        ),
+        Sprite {
+            image: actor.atlas.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: actor.atlas_layout.clone(),
+                index: 0,
+            }),
+            custom_size: Some(actor.size),
+            ..default()
+        },
+        AnimationPlayer::from(actor.idle_anim.clone()),
+        Transform::from_translation((spawn.position - actor.collider_offset).extend(0.0)),
+        movement_controller(
+            actor.movement.clone(),
+            actor.collider.clone(),
+            actor.collider_offset,
+            CollisionLayers::npc(),
+        ),
+    )
+}
+
+fn update_npc_schedules(
+    time: Res<Time>,
+    mut npcs: Query<(&Position, &mut NpcSchedule, &mut MovementIntent)>,
+) {
+    for (position, mut schedule, mut intent) in &mut npcs {
+        intent.jump = false;
+
+        // Wait at the current waypoint.
+        if !schedule.idle.is_finished() {
+            schedule.idle.tick(time.delta());
+            intent.direction = 0.0;
+            continue;
+        }
+
+        let Some(&target) = schedule.waypoints.get(schedule.next) else {
+            intent.direction = 0.0;
+            continue;
+        };
+
+        let dx = target.x - position.x;
+        if dx.abs() < ARRIVE_DISTANCE {
+            schedule.next = (schedule.next + 1) % schedule.waypoints.len();
+            schedule.idle.reset();
+            intent.direction = 0.0;
+        } else {
+            intent.direction = dx.signum();
+        }
+    }
+}
+
+fn update_npc_animations(
+    time: Res<Time>,
+    assets: Res<Assets<Enemy>>,
+    mut npcs: Query<(
+        &NpcActor,
+        &MovementIntent,
+        &GroundNormal,
+        &LinearVelocity,
+        &mut Sprite,
+        &mut AnimationPlayer,
+    )>,
+) {
+    for (actor, intent, ground_norm, velocity, mut sprite, mut animation) in &mut npcs {
+        let Some(actor) = assets.get(&actor.0) else {
+            continue;
+        };
+
+        let next_anim = if !ground_norm.is_grounded() {
+            &actor.fall_anim
+        } else if intent.direction != 0.0 || velocity.x.abs() > 0.1 {
+            &actor.walk_anim
+        } else {
+            // Ambient idle behavior: occasionally look the other way.
+            if rand::rng().random_bool((LOOK_AROUND_RATE * time.delta_secs_f64()).min(1.0)) {
+                sprite.flip_x = !sprite.flip_x;
+            }
+            &actor.idle_anim
+        };
+
+        if intent.direction != 0.0 {
+            sprite.flip_x = intent.direction < 0.0;
+        }
+
+        if next_anim.id() != animation.animation.id() {
+            animation.animation = next_anim.clone();
+        }
+    }
+}
+
+fn interact_with_npcs(
+    player: Single<(Entity, &GlobalTransform), With<Player>>,
+    npcs: Query<(Entity, &GlobalTransform), With<Npc>>,
+    mut commands: Commands,
+) {
+    let (player, player_transform) = player.into_inner();
+    let player_pos = player_transform.translation().xy();
+
+    let nearest = npcs
+        .iter()
+        .map(|(npc, transform)| (npc, transform.translation().xy().distance(player_pos)))
+        .filter(|&(_, distance)| distance < INTERACT_RANGE)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((npc, _)) = nearest {
+        commands.trigger(NpcInteraction { npc, player });
+    }
+}
+
+/// Placeholder dialogue presentation until there is a proper dialogue UI.
+fn log_npc_dialogue(
+    interaction: On<NpcInteraction>,
+    mut npcs: Query<(&Npc, &mut DialogueProgress)>,
+) {
+    let Ok((npc, mut progress)) = npcs.get_mut(interaction.npc) else {
+        return;
+    };
+
+    let Some(line) = npc.dialogue.get(progress.0).or(npc.dialogue.last()) else {
+        return;
+    };
+    info!("{}: {line}", npc.name);
+
+    if progress.0 + 1 < npc.dialogue.len() {
+        progress.0 += 1;
+    }
+}
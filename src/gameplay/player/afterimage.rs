@@ -0,0 +1,160 @@
+//! Fading copies of the player left behind when moving close to the speed of light.
+//!
+//! A fixed pool of sprites is spawned with the gameplay screen and reused oldest-first, so a long
+//! sprint never spawns or despawns anything.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::player::Player,
+    physics::{LocalSpeedOfLight, beta},
+    screens::Screen,
+    theme::palette::{AFTERIMAGE_FAST, AFTERIMAGE_SLOW},
+};
+
+/// How many afterimages can be visible at once.
+const AFTERIMAGE_POOL_SIZE: usize = 8;
+/// The speed, as a fraction of the speed of light, above which afterimages are left behind.
+const AFTERIMAGE_MIN_BETA: f32 = 0.5;
+/// The speed at which afterimages are tinted entirely with [`AFTERIMAGE_FAST`].
+const AFTERIMAGE_MAX_BETA: f32 = 0.9;
+const AFTERIMAGE_INTERVAL_SECS: f32 = 0.05;
+const AFTERIMAGE_LIFETIME_SECS: f32 = 0.3;
+const AFTERIMAGE_START_ALPHA: f32 = 0.6;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_afterimage_pool);
+
+    app.add_systems(
+        Update,
+        (emit_afterimages, fade_afterimages)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// A pooled copy of the player's sprite.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Afterimage {
+    /// How long the copy has been fading, or `None` while it is unused.
+    age: Option<f32>,
+    tint: Color,
+}
+
+/// The pooled afterimages, in the order they are reused.
+#[derive(Resource, Debug)]
+struct AfterimagePool {
+    afterimages: Vec<Entity>,
+    next: usize,
+    timer: Timer,
+}
+
+fn spawn_afterimage_pool(mut commands: Commands) {
+    let afterimages = (0..AFTERIMAGE_POOL_SIZE)
+        .map(|_| {
+            commands
+                .spawn((
+                    Name::new("Afterimage"),
+                    Afterimage {
+                        age: None,
+                        tint: AFTERIMAGE_SLOW,
+                    },
+                    Sprite::default(),
+                    Transform::default(),
+                    Visibility::Hidden,
+                    DespawnOnExit(Screen::Gameplay),
+                ))
+                .id()
+        })
+        .collect();
+
+    commands.insert_resource(AfterimagePool {
+        afterimages,
+        next: 0,
+        timer: Timer::from_seconds(AFTERIMAGE_INTERVAL_SECS, TimerMode::Repeating),
+    });
+}
+
+fn emit_afterimages(
+    time: Res<Time>,
+    c: Res<LocalSpeedOfLight>,
+    pool: Option<ResMut<AfterimagePool>>,
+    players: Query<(&LinearVelocity, &Children), With<Player>>,
+    player_sprites: Query<(&Sprite, &GlobalTransform), Without<Afterimage>>,
+    mut afterimages: Query<
+        (
+            &mut Afterimage,
+            &mut Sprite,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<Player>,
+    >,
+) {
+    let Some(mut pool) = pool else {
+        return;
+    };
+    if !pool.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (velocity, children) in &players {
+        let b = beta(velocity.length(), c.0);
+        if b < AFTERIMAGE_MIN_BETA {
+            continue;
+        }
+        let Some((sprite, transform)) = children.iter().find_map(|e| player_sprites.get(e).ok())
+        else {
+            continue;
+        };
+
+        let entity = pool.afterimages[pool.next];
+        pool.next = (pool.next + 1) % pool.afterimages.len();
+        let Ok((mut afterimage, mut copy, mut copy_transform, mut visibility)) =
+            afterimages.get_mut(entity)
+        else {
+            continue;
+        };
+
+        let t = ((b - AFTERIMAGE_MIN_BETA) / (AFTERIMAGE_MAX_BETA - AFTERIMAGE_MIN_BETA))
+            .clamp(0.0, 1.0);
+        afterimage.age = Some(0.0);
+        afterimage.tint = AFTERIMAGE_SLOW.mix(&AFTERIMAGE_FAST, t);
+
+        *copy = Sprite {
+            color: afterimage.tint.with_alpha(AFTERIMAGE_START_ALPHA),
+            ..sprite.clone()
+        };
+        // Just behind the player, so the live sprite is always drawn on top
+        *copy_transform = transform.compute_transform();
+        copy_transform.translation.z -= 0.01;
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn fade_afterimages(
+    time: Res<Time>,
+    mut afterimages: Query<(&mut Afterimage, &mut Sprite, &mut Visibility)>,
+) {
+    for (mut afterimage, mut sprite, mut visibility) in &mut afterimages {
+        let Some(age) = afterimage.age.as_mut() else {
+            continue;
+        };
+        *age += time.delta_secs();
+
+        let remaining = 1.0 - *age / AFTERIMAGE_LIFETIME_SECS;
+        if remaining <= 0.0 {
+            afterimage.age = None;
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        sprite.color = afterimage
+            .tint
+            .with_alpha(AFTERIMAGE_START_ALPHA * remaining);
+    }
+}
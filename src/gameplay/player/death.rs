@@ -0,0 +1,235 @@
+//! The player's death sequence.
+//!
+//! Killing the player with [`KillPlayer`] locks their input, plays the death animation in slow
+//! motion, and fades the screen out before triggering [`PlayerDied`]. The screen then fades back
+//! in for the spectator camera, and again when the player respawns with brief invulnerability.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    animation::AnimationPlayer,
+    assist::AssistSettings,
+    controller::{CharacterIntent, DashState},
+    gameplay::{
+        combat::{Died, Invulnerability},
+        player::{
+            Player, PlayerAssets,
+            spectator::{PlayerDied, PlayerRespawned},
+        },
+    },
+    screens::{Area, Screen},
+};
+
+/// How long the death animation plays before the fade out, in real time.
+const DYING_SECS: f32 = 0.8;
+const FADE_SECS: f32 = 0.5;
+const SLOW_MO_SPEED: f32 = 0.3;
+const RESPAWN_INVULNERABILITY_SECS: f32 = 2.0;
+const INVULNERABLE_BLINK_HZ: f32 = 8.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(start_death_sequence)
+        .add_observer(kill_player_on_death)
+        .add_observer(fade_in_after_death)
+        .add_observer(protect_respawned_player)
+        .add_systems(OnEnter(Screen::Gameplay), spawn_screen_fade)
+        .add_systems(OnExit(Area::Hub), reset_time_scale)
+        .add_systems(OnExit(Area::Level), reset_time_scale);
+
+    app.add_systems(
+        Update,
+        (
+            tick_death_sequences.in_set(AppSystems::TickTimers),
+            (
+                advance_death_sequences,
+                blink_invulnerable,
+                update_screen_fade,
+            )
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Starts the death sequence for a player.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct KillPlayer {
+    #[event_target]
+    pub player: Entity,
+}
+
+/// A player that is partway through dying. Their input is ignored until the sequence ends.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct DeathSequence {
+    pub phase: DeathPhase,
+    timer: Timer,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathPhase {
+    /// The death animation is playing in slow motion.
+    Dying,
+    /// The screen is fading to black.
+    FadingOut,
+}
+
+/// A full-screen overlay that fades towards its target opacity.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ScreenFade {
+    pub target: f32,
+}
+
+fn start_death_sequence(
+    kill: On<KillPlayer>,
+    assets: Res<PlayerAssets>,
+    mut players: Query<
+        (&mut CharacterIntent, &Children, Option<&DashState>),
+        (
+            With<Player>,
+            Without<DeathSequence>,
+            Without<Invulnerability>,
+        ),
+    >,
+    mut animations: Query<&mut AnimationPlayer>,
+    assist: Res<AssistSettings>,
+    mut time: ResMut<Time<Virtual>>,
+    mut commands: Commands,
+) {
+    let Ok((mut intent, children, dash)) = players.get_mut(kill.player) else {
+        return;
+    };
+    // Dashing grants i-frames.
+    if dash.is_some_and(DashState::is_dashing) {
+        return;
+    }
+
+    *intent = CharacterIntent::default();
+    let mut iter = animations.iter_many_mut(children);
+    while let Some(mut animation) = iter.fetch_next() {
+        animation.animation = assets.death_anim.clone();
+    }
+
+    time.set_relative_speed(SLOW_MO_SPEED * assist.time_scale);
+    commands.entity(kill.player).insert(DeathSequence {
+        phase: DeathPhase::Dying,
+        timer: Timer::from_seconds(DYING_SECS, TimerMode::Once),
+    });
+}
+
+fn kill_player_on_death(died: On<Died>, players: Query<(), With<Player>>, mut commands: Commands) {
+    if players.contains(died.entity) {
+        commands.trigger(KillPlayer {
+            player: died.entity,
+        });
+    }
+}
+
+fn tick_death_sequences(time: Res<Time<Real>>, mut sequences: Query<&mut DeathSequence>) {
+    // Use real time so the slow motion doesn't also stretch the sequence.
+    for mut sequence in &mut sequences {
+        sequence.timer.tick(time.delta());
+    }
+}
+
+fn advance_death_sequences(
+    mut sequences: Query<(Entity, &mut DeathSequence, &mut LinearVelocity)>,
+    mut fade: Single<&mut ScreenFade>,
+    assist: Res<AssistSettings>,
+    mut time: ResMut<Time<Virtual>>,
+    mut commands: Commands,
+) {
+    for (player, mut sequence, mut velocity) in &mut sequences {
+        // Let the body drift to a stop rather than sliding on.
+        velocity.x *= 0.9;
+
+        if !sequence.timer.is_finished() {
+            continue;
+        }
+
+        match sequence.phase {
+            DeathPhase::Dying => {
+                time.set_relative_speed(assist.time_scale);
+                fade.target = 1.0;
+                sequence.phase = DeathPhase::FadingOut;
+                sequence.timer = Timer::from_seconds(FADE_SECS, TimerMode::Once);
+            }
+            DeathPhase::FadingOut => {
+                commands.trigger(PlayerDied { player });
+            }
+        }
+    }
+}
+
+fn fade_in_after_death(_: On<PlayerDied>, mut fade: Single<&mut ScreenFade>) {
+    fade.target = 0.0;
+}
+
+fn protect_respawned_player(
+    respawn: On<PlayerRespawned>,
+    fade: Single<(&mut ScreenFade, &mut BackgroundColor)>,
+    mut commands: Commands,
+) {
+    commands
+        .entity(respawn.player)
+        .insert(Invulnerability::from_seconds(RESPAWN_INVULNERABILITY_SECS));
+
+    // Cut to black and fade in at the checkpoint.
+    let (mut fade, mut background) = fade.into_inner();
+    background.0.set_alpha(1.0);
+    fade.target = 0.0;
+}
+
+fn blink_invulnerable(
+    players: Query<(&Children, Option<&Invulnerability>), With<Player>>,
+    mut sprites: Query<&mut Visibility, With<Sprite>>,
+) {
+    for (children, invulnerable) in &players {
+        let visible = invulnerable.is_none_or(|invulnerable| {
+            invulnerable.0.is_finished()
+                || (invulnerable.0.elapsed_secs() * INVULNERABLE_BLINK_HZ).fract() < 0.5
+        });
+        let mut iter = sprites.iter_many_mut(children);
+        while let Some(mut visibility) = iter.fetch_next() {
+            visibility.set_if_neq(if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+        }
+    }
+}
+
+fn spawn_screen_fade(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Screen Fade"),
+        ScreenFade { target: 0.0 },
+        Node {
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        Pickable::IGNORE,
+        GlobalZIndex(0),
+        BackgroundColor(Color::BLACK.with_alpha(0.0)),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_screen_fade(time: Res<Time<Real>>, fade: Single<(&ScreenFade, &mut BackgroundColor)>) {
+    let (fade, mut background) = fade.into_inner();
+    let alpha = background.0.alpha();
+    let step = time.delta_secs() / FADE_SECS;
+    let next = alpha + (fade.target - alpha).clamp(-step, step);
+    if next != alpha {
+        background.0.set_alpha(next);
+    }
+}
+
+fn reset_time_scale(assist: Res<AssistSettings>, mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(assist.time_scale);
+}
@@ -0,0 +1,356 @@
+//! Player-specific behavior.
+
+pub mod afterimage;
+pub mod death;
+pub mod spectator;
+pub mod touch;
+
+use avian2d::prelude::{Collider, CollisionEventsEnabled, CollisionLayers, LinearVelocity};
+use bevy::{prelude::*, ui_widgets::observe};
+use rand::seq::IndexedRandom;
+
+use crate::{
+    AppSystems, PausableSystems,
+    animation::{Animation, AnimationEvent, AnimationPlayer},
+    asset_tracking::LoadResource,
+    audio::sound_effect,
+    config::GameConfig,
+    controller::{CharacterIntent, GroundMaterial, GroundNormal, WallState, character_controller},
+    gameplay::{
+        camera::cursor::CursorWorldPosition,
+        combat::{
+            Health,
+            projectile::{FireWeapon, Weapon},
+            stomp::Stomper,
+        },
+        player::death::DeathSequence,
+    },
+    input::{Action, AnalogMovement, FixedInput, InputOptions},
+    physics::{GamePhysicsLayersExt, SpeedOfLightLimit},
+    screens::Screen,
+    units::TilesPerSecond,
+};
+
+const PLAYER_MAX_HEALTH: f32 = 3.0;
+const HIT_INVULNERABILITY_SECS: f32 = 1.0;
+const PROJECTILE_SPEED: f32 = 20.0;
+const PROJECTILE_DAMAGE: f32 = 1.0;
+const FIRE_COOLDOWN_SECS: f32 = 0.25;
+const PROJECTILE_BOUNCES: u32 = 1;
+/// Enough to shoot through mud and ice, but not regular ground.
+const PROJECTILE_PENETRATION: f32 = 0.6;
+const STOMP_DAMAGE: f32 = 1.0;
+const STOMP_BOUNCE_SPEED: TilesPerSecond = TilesPerSecond(14.0);
+/// The fraction of full speed the player moves at while walking.
+const WALK_SPEED: f32 = 0.25;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        afterimage::plugin,
+        death::plugin,
+        spectator::plugin,
+        touch::plugin,
+    ));
+
+    app.load_resource::<PlayerAssets>();
+
+    // Record directional input as movement controls, once per tick of the controller.
+    app.add_systems(
+        FixedPreUpdate,
+        record_player_directional_input
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            record_player_fire_input.in_set(AppSystems::RecordInput),
+            update_animation_movement,
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The player character.
+pub fn player(
+    position: Vec2,
+    config: &GameConfig,
+    player_assets: &PlayerAssets,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) -> impl Bundle {
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 1, 23, Some(UVec2::ONE), None);
+    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+
+    (
+        Name::new("Player"),
+        Player,
+        SpeedOfLightLimit,
+        Health::new(PLAYER_MAX_HEALTH).with_hit_invulnerability(HIT_INVULNERABILITY_SECS),
+        // Needed for contact damage from enemies, and stomping on them.
+        CollisionEventsEnabled,
+        Stomper {
+            damage: STOMP_DAMAGE,
+            bounce_speed: STOMP_BOUNCE_SPEED.to_world(),
+        },
+        Weapon::new(
+            PROJECTILE_SPEED,
+            PROJECTILE_DAMAGE,
+            FIRE_COOLDOWN_SECS,
+            CollisionLayers::player_projectile(),
+        )
+        .with_bounces(PROJECTILE_BOUNCES)
+        .with_penetration(PROJECTILE_PENETRATION),
+        Transform::from_translation(position.extend(0.0)),
+        Visibility::default(),
+        character_controller(
+            config.player_controller.to_world(),
+            Collider::capsule(0.2, 0.5),
+            CollisionLayers::player(),
+        ),
+        children![(
+            Sprite {
+                image: player_assets.ducky.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: texture_atlas_layout,
+                    index: 0,
+                }),
+                custom_size: Some(Vec2::splat(2.)),
+                ..default()
+            },
+            Transform::from_translation((0.508 * Vec2::Y).extend(0.0)),
+            AnimationPlayer::from(player_assets.idle_anim.clone()),
+            observe(trigger_step_sound_effect),
+        )],
+    )
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Player;
+
+fn record_player_directional_input(
+    input: Res<FixedInput<Action>>,
+    analog: Res<AnalogMovement>,
+    options: Res<InputOptions>,
+    mut walk_toggled: Local<bool>,
+    mut intent: Single<&mut CharacterIntent, (With<Player>, Without<DeathSequence>)>,
+) {
+    // Collect directional input. Keys win over analog input, so a resting stick never holds back
+    // a pressed key.
+    let lt = input.pressed(Action::MoveLeft);
+    let rt = input.pressed(Action::MoveRight);
+    let movement = match rt as i8 - lt as i8 {
+        0 => analog.0,
+        direction => f32::from(direction),
+    };
+
+    let walk = if options.toggle_walk {
+        if input.just_pressed(Action::Walk) {
+            *walk_toggled = !*walk_toggled;
+        }
+        *walk_toggled
+    } else {
+        input.pressed(Action::Walk)
+    };
+
+    intent.movement = movement * if walk { WALK_SPEED } else { 1.0 };
+    intent.jump = input.pressed(Action::Jump);
+    intent.jump_released = input.just_released(Action::Jump);
+    intent.drop = input.pressed(Action::MoveDown);
+    intent.dash = input.pressed(Action::Dash);
+}
+
+/// Fires towards the cursor.
+fn record_player_fire_input(
+    input: Res<ButtonInput<Action>>,
+    cursor: Res<CursorWorldPosition>,
+    player: Single<(Entity, &GlobalTransform), (With<Player>, Without<DeathSequence>)>,
+    mut commands: Commands,
+) {
+    if !input.pressed(Action::Fire) {
+        return;
+    }
+    let Some(target) = cursor.0 else {
+        return;
+    };
+
+    let (entity, transform) = player.into_inner();
+    if let Ok(direction) = Dir2::new(target - transform.translation().xy()) {
+        commands.trigger(FireWeapon { entity, direction });
+    }
+}
+
+fn update_animation_movement(
+    assets: Res<PlayerAssets>,
+    player: Single<
+        (
+            &CharacterIntent,
+            Option<&GroundNormal>,
+            Option<&WallState>,
+            Option<&LinearVelocity>,
+            &Children,
+        ),
+        (With<Player>, Without<DeathSequence>),
+    >,
+    mut sprites: Query<(&mut Sprite, &mut AnimationPlayer)>,
+) {
+    let (intent, ground_norm, wall_state, velocity, children) = player.into_inner();
+    let Ok((mut sprite, mut animation)) = sprites.get_mut(children[0]) else {
+        return;
+    };
+
+    if let Some(wall_normal) = wall_state.and_then(WallState::normal) {
+        // Face the wall while clinging to it.
+        sprite.flip_x = wall_normal.x > 0.0;
+    } else if intent.movement != 0.0 {
+        sprite.flip_x = intent.movement < 0.0;
+    }
+
+    let next_anim = if ground_norm.is_none_or(GroundNormal::is_grounded) {
+        let vx = velocity.map_or(0.0, |v| v.x.abs());
+        if intent.movement == 0.0 && vx < 0.1 {
+            &assets.idle_anim
+        } else if vx < 10.0 {
+            &assets.walk_anim
+        } else {
+            &assets.run_anim
+        }
+    } else {
+        let vy = velocity.map_or(-1.0, |v| v.y);
+        if vy.abs() < 0.5 {
+            &assets.peak_anim
+        } else if vy > 0.0 {
+            &assets.jump_anim
+        } else {
+            &assets.fall_anim
+        }
+    };
+
+    if next_anim.id() != animation.animation.id() {
+        animation.animation = next_anim.clone();
+    }
+}
+
+/// Plays a footstep, changed to suit the ground the player is standing on.
+fn trigger_step_sound_effect(
+    ev: On<AnimationEvent>,
+    player_assets: If<Res<PlayerAssets>>,
+    parents: Query<&ChildOf>,
+    ground_materials: Query<&GroundMaterial>,
+    mut commands: Commands,
+) {
+    // Other animated entities have their own markers
+    if ev.marker == PlayerAssets::STEP_MARKER {
+        let rng = &mut rand::rng();
+        let random_step = player_assets.steps.choose(rng).unwrap().clone();
+        let material = parents
+            .get(ev.entity)
+            .and_then(|parent| ground_materials.get(parent.parent()))
+            .map_or_else(|_| default(), |ground| **ground);
+        commands
+            .spawn(sound_effect(random_step, 0.3 * material.footstep_volume))
+            .entry::<PlaybackSettings>()
+            .and_modify(move |mut settings| settings.speed = material.footstep_speed);
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PlayerAssets {
+    #[dependency]
+    ducky: Handle<Image>,
+    #[dependency]
+    pub steps: Vec<Handle<AudioSource>>,
+    #[dependency]
+    pub idle_anim: Handle<Animation>,
+    #[dependency]
+    pub walk_anim: Handle<Animation>,
+    #[dependency]
+    pub run_anim: Handle<Animation>,
+    #[dependency]
+    pub jump_anim: Handle<Animation>,
+    #[dependency]
+    pub peak_anim: Handle<Animation>,
+    #[dependency]
+    pub fall_anim: Handle<Animation>,
+    #[dependency]
+    pub death_anim: Handle<Animation>,
+}
+
+impl PlayerAssets {
+    /// The marker on the footstep frames of the walk and run animation files.
+    pub const STEP_MARKER: usize = 0;
+}
+
+impl FromWorld for PlayerAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            ducky: assets.load("images/player.png"),
+            steps: vec![
+                assets.load("audio/sound_effects/steps/grass1.ogg"),
+                assets.load("audio/sound_effects/steps/grass2.ogg"),
+                assets.load("audio/sound_effects/steps/grass3.ogg"),
+                assets.load("audio/sound_effects/steps/grass4.ogg"),
+            ],
+            idle_anim: assets.load("animations/player/idle.anim.ron"),
+            walk_anim: assets.load("animations/player/walk.anim.ron"),
+            run_anim: assets.load("animations/player/run.anim.ron"),
+            jump_anim: assets.load("animations/player/jump.anim.ron"),
+            peak_anim: assets.load("animations/player/peak.anim.ron"),
+            fall_anim: assets.load("animations/player/fall.anim.ron"),
+            death_anim: assets.load("animations/player/death.anim.ron"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SoundEffect;
+
+    #[test]
+    fn step_marker_on_sprite_plays_footstep() {
+        let mut app = App::new();
+        app.insert_resource(PlayerAssets {
+            ducky: default(),
+            steps: vec![default()],
+            idle_anim: default(),
+            walk_anim: default(),
+            run_anim: default(),
+            jump_anim: default(),
+            peak_anim: default(),
+            fall_anim: default(),
+            death_anim: default(),
+        });
+
+        // The animation events target the player's sprite, not the player itself.
+        let player = app
+            .world_mut()
+            .spawn((
+                Player,
+                children![(
+                    Name::new("Player Sprite"),
+                    observe(trigger_step_sound_effect)
+                )],
+            ))
+            .id();
+        let sprite = app.world().entity(player).get::<Children>().unwrap()[0];
+
+        app.world_mut().trigger(AnimationEvent {
+            entity: sprite,
+            marker: PlayerAssets::STEP_MARKER,
+        });
+        app.world_mut().flush();
+
+        let sounds = app
+            .world_mut()
+            .query_filtered::<(), With<SoundEffect>>()
+            .iter(app.world())
+            .count();
+        assert_eq!(sounds, 1);
+    }
+}
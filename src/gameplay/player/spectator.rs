@@ -0,0 +1,221 @@
+//! Spectator camera used while a dead player waits to respawn.
+//!
+//! When a [`PlayerDied`] event is triggered the player is despawned and the [`PlayerCamera`]
+//! starts spectating. It follows any surviving players (cycled with [`Action::SwitchTarget`]), or
+//! flies freely with the movement actions when nobody is left, until the respawn timer runs out
+//! and the player is spawned back at the level's checkpoint.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    assets::level::Level,
+    config::GameConfig,
+    gameplay::{
+        camera::{PlayerCamera, update_player_camera_position},
+        level::CurrentLevel,
+        player::{Player, PlayerAssets, player},
+    },
+    input::Action,
+    screens::Screen,
+    theme::prelude::*,
+};
+
+const RESPAWN_DELAY_SECS: f32 = 3.0;
+const FREE_CAM_SPEED: f32 = 20.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(start_spectating)
+        .add_systems(OnExit(Screen::Gameplay), stop_spectating);
+
+    app.add_systems(
+        Update,
+        (
+            tick_respawn_timer.in_set(AppSystems::TickTimers),
+            (cycle_spectator_target, move_free_cam).in_set(AppSystems::RecordInput),
+            (update_respawn_label, respawn_players).in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+
+    app.add_systems(
+        PostUpdate,
+        follow_spectator_target
+            .after(update_player_camera_position)
+            .before(TransformSystems::Propagate),
+    );
+}
+
+/// Triggered when a player dies. The player will be despawned and respawned after a delay.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerDied {
+    pub player: Entity,
+}
+
+/// Triggered when a dead player is spawned back into the level.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerRespawned {
+    pub player: Entity,
+}
+
+/// Added to the [`PlayerCamera`] while its player is dead.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Spectating {
+    /// The player being followed, or `None` for a free-flying camera.
+    pub target: Option<Entity>,
+    /// Time remaining until the dead player respawns.
+    pub respawn_timer: Timer,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RespawnTimerUi;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RespawnTimerLabel;
+
+fn start_spectating(
+    ev: On<PlayerDied>,
+    players: Query<Entity, With<Player>>,
+    camera: Single<Entity, With<PlayerCamera>>,
+    mut commands: Commands,
+) {
+    commands.entity(ev.player).try_despawn();
+
+    let target = players.iter().find(|&entity| entity != ev.player);
+    commands.entity(*camera).insert(Spectating {
+        target,
+        respawn_timer: Timer::from_seconds(RESPAWN_DELAY_SECS, TimerMode::Once),
+    });
+
+    commands.spawn((
+        widget::ui_root("Respawn Timer"),
+        RespawnTimerUi,
+        DespawnOnExit(Screen::Gameplay),
+        children![(widget::header(""), RespawnTimerLabel)],
+    ));
+}
+
+fn stop_spectating(camera: Single<Entity, With<PlayerCamera>>, mut commands: Commands) {
+    commands.entity(*camera).remove::<Spectating>();
+}
+
+fn tick_respawn_timer(time: Res<Time>, mut spectators: Query<&mut Spectating>) {
+    for mut spectating in &mut spectators {
+        spectating.respawn_timer.tick(time.delta());
+    }
+}
+
+fn cycle_spectator_target(
+    input: Res<ButtonInput<Action>>,
+    players: Query<Entity, With<Player>>,
+    mut spectating: Single<&mut Spectating>,
+) {
+    let alive = players.iter().collect::<Vec<_>>();
+
+    // Fall back to the free camera when the followed player is gone.
+    if spectating
+        .target
+        .is_some_and(|target| !alive.contains(&target))
+    {
+        spectating.target = None;
+    }
+
+    if !input.just_pressed(Action::SwitchTarget) {
+        return;
+    }
+
+    // Cycle through surviving players, with the free camera as the last entry.
+    let next = match spectating.target {
+        None => alive.first().copied(),
+        Some(target) => alive
+            .iter()
+            .position(|&entity| entity == target)
+            .and_then(|i| alive.get(i + 1).copied()),
+    };
+    spectating.target = next;
+}
+
+fn move_free_cam(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    camera: Single<(&Spectating, &mut Transform)>,
+) {
+    let (spectating, mut transform) = camera.into_inner();
+    if spectating.target.is_some() {
+        return;
+    }
+
+    let lt = input.pressed(Action::MoveLeft);
+    let rt = input.pressed(Action::MoveRight);
+    let dn = input.pressed(Action::MoveDown);
+    let up = input.pressed(Action::MoveUp);
+    let direction = Vec2::new(
+        f32::from(rt as i8 - lt as i8),
+        f32::from(up as i8 - dn as i8),
+    );
+
+    transform.translation += (FREE_CAM_SPEED * time.delta_secs() * direction).extend(0.0);
+}
+
+pub fn follow_spectator_target(
+    camera: Single<(&Spectating, &mut Transform), Without<Player>>,
+    players: Query<&GlobalTransform, With<Player>>,
+) {
+    let (spectating, mut transform) = camera.into_inner();
+    if let Some(target) = spectating.target
+        && let Ok(player) = players.get(target)
+    {
+        transform.translation = player.translation();
+    }
+}
+
+fn update_respawn_label(
+    spectating: Single<&Spectating>,
+    mut label: Single<&mut Text, With<RespawnTimerLabel>>,
+) {
+    let remaining = spectating.respawn_timer.remaining_secs();
+    label.0 = format!("Respawning in {remaining:.1}s");
+}
+
+fn respawn_players(
+    spectator: Single<(Entity, &Spectating)>,
+    timer_ui: Query<Entity, With<RespawnTimerUi>>,
+    level: Single<(Entity, &CurrentLevel)>,
+    levels: Res<Assets<Level>>,
+    config: Res<GameConfig>,
+    player_assets: Res<PlayerAssets>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut commands: Commands,
+) {
+    let (camera, spectating) = spectator.into_inner();
+    if !spectating.respawn_timer.is_finished() {
+        return;
+    }
+
+    let (level_entity, level_handle) = level.into_inner();
+    let Some(level) = levels.get(level_handle.id()) else {
+        return;
+    };
+
+    commands.entity(camera).remove::<Spectating>();
+    for entity in &timer_ui {
+        commands.entity(entity).despawn();
+    }
+
+    let player = commands
+        .spawn((
+            player(
+                level.player_spawn,
+                &config,
+                &player_assets,
+                &mut texture_atlas_layouts,
+            ),
+            ChildOf(level_entity),
+        ))
+        .id();
+    commands.trigger(PlayerRespawned { player });
+}
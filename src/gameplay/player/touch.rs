@@ -0,0 +1,204 @@
+//! On-screen touch controls, so the game is playable on phones.
+//!
+//! A virtual joystick moves the player, at a speed depending on how far it's pushed, and drops
+//! through platforms when pushed down, while a button jumps. Both go through [`VirtualActions`],
+//! so the player reads them the same way as keys and gamepads. The controls stay hidden until the
+//! first touch, or are always shown with the `touch` feature.
+
+use bevy::{input::touch::Touches, prelude::*, ui_widgets::observe};
+
+use crate::{
+    PausableSystems,
+    input::{Action, VirtualActions},
+    math::apply_deadzone,
+    screens::Screen,
+    theme::{
+        palette::{TOUCH_CONTROL_BACKGROUND, TOUCH_CONTROL_KNOB},
+        prelude::*,
+    },
+};
+
+const STICK_RADIUS: f32 = 80.0;
+const KNOB_RADIUS: f32 = 32.0;
+const JUMP_BUTTON_SIZE: f32 = 120.0;
+/// How far the stick has to be pushed, as a fraction of its radius, to move the player.
+const STICK_DEADZONE: f32 = 0.3;
+/// How far down the stick has to be pushed to drop through platforms.
+const STICK_DROP_THRESHOLD: f32 = 0.6;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(TouchControlsEnabled(cfg!(feature = "touch")));
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_touch_controls)
+        .add_systems(OnExit(Screen::Gameplay), release_touch_actions);
+
+    app.add_systems(
+        Update,
+        (
+            detect_touch_device.run_if(not(resource_equals(TouchControlsEnabled(true)))),
+            show_touch_controls.run_if(
+                resource_changed::<TouchControlsEnabled>
+                    .or(any_match_filter::<Added<TouchControls>>),
+            ),
+            (apply_touch_stick, update_touch_stick_knob)
+                .run_if(any_match_filter::<Changed<TouchStick>>),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Whether the on-screen touch controls are shown.
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub struct TouchControlsEnabled(pub bool);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct TouchControls;
+
+/// How far the joystick is pushed, from `-1.0` to `1.0` on each axis with `+Y` up.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct TouchStick(Vec2);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct TouchStickKnob;
+
+fn spawn_touch_controls(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Touch Controls"),
+        TouchControls,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            padding: UiRect::all(px(40)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::End,
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![touch_stick(), jump_button()],
+    ));
+}
+
+fn touch_stick() -> impl Bundle {
+    (
+        Name::new("Joystick"),
+        TouchStick::default(),
+        Node {
+            width: px(2.0 * STICK_RADIUS),
+            height: px(2.0 * STICK_RADIUS),
+            border_radius: BorderRadius::MAX,
+            ..default()
+        },
+        BackgroundColor(TOUCH_CONTROL_BACKGROUND),
+        children![(
+            Name::new("Knob"),
+            TouchStickKnob,
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(STICK_RADIUS - KNOB_RADIUS),
+                top: px(STICK_RADIUS - KNOB_RADIUS),
+                width: px(2.0 * KNOB_RADIUS),
+                height: px(2.0 * KNOB_RADIUS),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+            BackgroundColor(TOUCH_CONTROL_KNOB),
+            Pickable::IGNORE,
+        )],
+        observe(drag_touch_stick),
+        observe(reset_touch_stick::<Pointer<DragEnd>>),
+        observe(reset_touch_stick::<Pointer<Release>>),
+        observe(reset_touch_stick::<Pointer<Cancel>>),
+    )
+}
+
+fn jump_button() -> impl Bundle {
+    (
+        Name::new("Jump Button"),
+        Node {
+            width: px(JUMP_BUTTON_SIZE),
+            height: px(JUMP_BUTTON_SIZE),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border_radius: BorderRadius::MAX,
+            ..default()
+        },
+        BackgroundColor(TOUCH_CONTROL_BACKGROUND),
+        children![(widget::label("Jump"), Pickable::IGNORE)],
+        observe(
+            |_: On<Pointer<Press>>, mut actions: ResMut<VirtualActions>| {
+                actions.press(Action::Jump);
+            },
+        ),
+        observe(release_jump::<Pointer<Release>>),
+        observe(release_jump::<Pointer<DragEnd>>),
+        observe(release_jump::<Pointer<Cancel>>),
+    )
+}
+
+fn drag_touch_stick(drag: On<Pointer<Drag>>, mut sticks: Query<&mut TouchStick>) {
+    let Ok(mut stick) = sticks.get_mut(drag.entity) else {
+        return;
+    };
+    // UI coordinates have `+Y` down.
+    let offset = Vec2::new(drag.distance.x, -drag.distance.y) / STICK_RADIUS;
+    stick.0 = offset.clamp_length_max(1.0);
+}
+
+fn reset_touch_stick<E: EntityEvent>(event: On<E>, mut sticks: Query<&mut TouchStick>) {
+    if let Ok(mut stick) = sticks.get_mut(event.event_target()) {
+        stick.0 = Vec2::ZERO;
+    }
+}
+
+fn release_jump<E: EntityEvent>(_: On<E>, mut actions: ResMut<VirtualActions>) {
+    actions.release(Action::Jump);
+}
+
+fn detect_touch_device(touches: Res<Touches>, mut enabled: ResMut<TouchControlsEnabled>) {
+    if touches.any_just_pressed() {
+        enabled.0 = true;
+    }
+}
+
+fn show_touch_controls(
+    enabled: Res<TouchControlsEnabled>,
+    mut controls: Query<&mut Visibility, With<TouchControls>>,
+) {
+    for mut visibility in &mut controls {
+        *visibility = if enabled.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn apply_touch_stick(stick: Single<&TouchStick>, mut actions: ResMut<VirtualActions>) {
+    let stick = stick.0;
+    actions.set_movement(apply_deadzone(stick.x, STICK_DEADZONE));
+    actions.set(Action::MoveDown, stick.y < -STICK_DROP_THRESHOLD);
+}
+
+fn update_touch_stick_knob(
+    stick: Single<&TouchStick>,
+    mut knob: Single<&mut Node, With<TouchStickKnob>>,
+) {
+    let offset = STICK_RADIUS * Vec2::new(stick.0.x, -stick.0.y);
+    knob.left = px(STICK_RADIUS - KNOB_RADIUS + offset.x);
+    knob.top = px(STICK_RADIUS - KNOB_RADIUS + offset.y);
+}
+
+fn release_touch_actions(mut actions: ResMut<VirtualActions>) {
+    actions.set_movement(0.0);
+    actions.release(Action::MoveDown);
+    actions.release(Action::Jump);
+}
@@ -0,0 +1,392 @@
+//! Logical input actions, and button input for systems on the fixed timestep.
+//!
+//! Gameplay and menus read [`Action`]s rather than raw keys and mouse buttons, so players can
+//! rebind them. Each frame the [`InputBindings`] are checked against the keyboard and mouse to
+//! update a [`ButtonInput<Action>`], which works with Bevy's input run conditions like
+//! [`input_just_pressed`](bevy::input::common_conditions::input_just_pressed). The bindings are
+//! serializable, and the player's are kept in their [`Settings`](crate::settings::Settings).
+//!
+//! [`ButtonInput`] describes a single frame, but `FixedUpdate` can run zero or several times per
+//! frame. Fixed systems reading it directly can miss a quick tap, or see the same press on several
+//! ticks. [`FixedInput`] keeps presses and releases latched until the next fixed tick has seen
+//! them.
+//!
+//! Gamepad sticks and the touch joystick are read into [`AnalogMovement`] instead, so they can move
+//! the player at any fraction of full speed.
+
+use std::{collections::BTreeMap, hash::Hash};
+
+use bevy::{input::InputSystems, platform::collections::HashSet, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::math::apply_deadzone;
+
+/// How far a gamepad stick has to be pushed before it moves the player.
+const GAMEPAD_STICK_DEADZONE: f32 = 0.15;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputBindings>()
+        .init_resource::<InputOptions>()
+        .init_resource::<VirtualActions>()
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<AnalogMovement>()
+        .add_systems(
+            PreUpdate,
+            (
+                update_actions.before(latch_input::<Action>),
+                update_analog_movement,
+            )
+                .after(InputSystems),
+        );
+
+    app.add_plugins(fixed_input_plugin::<Action>);
+}
+
+/// Something the player can do, bound to any number of keys and mouse buttons.
+#[derive(
+    Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    /// Only used by the free spectator camera.
+    MoveUp,
+    /// Held to drop through one-way platforms.
+    MoveDown,
+    Jump,
+    Dash,
+    /// Held to walk rather than run.
+    Walk,
+    Fire,
+    Interact,
+    /// Switches which player the spectator camera follows.
+    SwitchTarget,
+    Pause,
+    /// Goes back to the previous menu.
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Self; 12] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::Jump,
+        Self::Dash,
+        Self::Walk,
+        Self::Fire,
+        Self::Interact,
+        Self::SwitchTarget,
+        Self::Pause,
+        Self::Back,
+    ];
+
+    /// The action's name as shown to players.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::Jump => "Jump",
+            Self::Dash => "Dash",
+            Self::Walk => "Walk",
+            Self::Fire => "Fire",
+            Self::Interact => "Interact",
+            Self::SwitchTarget => "Switch Target",
+            Self::Pause => "Pause",
+            Self::Back => "Back",
+        }
+    }
+
+    /// The bindings the action has until the player rebinds it.
+    pub fn default_bindings(self) -> &'static [Binding] {
+        use Binding::{Key, Mouse};
+        match self {
+            Self::MoveLeft => &[Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft)],
+            Self::MoveRight => &[Key(KeyCode::KeyD), Key(KeyCode::ArrowRight)],
+            Self::MoveUp => &[Key(KeyCode::KeyW), Key(KeyCode::ArrowUp)],
+            Self::MoveDown => &[Key(KeyCode::KeyS), Key(KeyCode::ArrowDown)],
+            Self::Jump => &[Key(KeyCode::Space)],
+            Self::Dash => &[Key(KeyCode::KeyQ), Key(KeyCode::ControlLeft)],
+            Self::Walk => &[Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)],
+            Self::Fire => &[Mouse(MouseButton::Left)],
+            Self::Interact => &[Key(KeyCode::KeyE)],
+            Self::SwitchTarget => &[Key(KeyCode::Tab)],
+            Self::Pause => &[Key(KeyCode::KeyP)],
+            Self::Back => &[Key(KeyCode::Escape)],
+        }
+    }
+}
+
+/// A key or mouse button an [`Action`] can be bound to.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// The first key or mouse button pressed this frame, if any. Used to pick a new binding.
+    pub fn first_just_pressed(
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+    ) -> Option<Self> {
+        keys.get_just_pressed()
+            .next()
+            .map(|&key| Self::Key(key))
+            .or_else(|| {
+                mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|&button| Self::Mouse(button))
+            })
+    }
+
+    fn pressed(self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Self::Key(key) => keys.pressed(key),
+            Self::Mouse(button) => mouse.pressed(button),
+        }
+    }
+
+    fn just_pressed(self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Self::Key(key) => keys.just_pressed(key),
+            Self::Mouse(button) => mouse.just_pressed(button),
+        }
+    }
+
+    /// The binding's name as players know it, like "A" rather than "KeyA".
+    pub fn name(self) -> String {
+        let name = match self {
+            Self::Key(key) => format!("{key:?}"),
+            Self::Mouse(MouseButton::Other(button)) => return format!("Mouse {button}"),
+            Self::Mouse(button) => return format!("{button:?} Click"),
+        };
+        if let Some(rest) = name.strip_prefix("Key").or(name.strip_prefix("Digit")) {
+            return rest.to_string();
+        }
+        if let Some(direction) = name.strip_prefix("Arrow") {
+            return format!("{direction} Arrow");
+        }
+        for side in ["Left", "Right"] {
+            if let Some(rest) = name.strip_suffix(side) {
+                return format!("{side} {rest}");
+            }
+        }
+        name
+    }
+}
+
+/// The keys and mouse buttons bound to each [`Action`].
+///
+/// Only the actions the player has rebound are stored, so the rest keep their
+/// [default bindings](Action::default_bindings) and new actions get theirs without touching saved
+/// settings.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[reflect(Resource)]
+#[serde(transparent)]
+pub struct InputBindings(BTreeMap<Action, Vec<Binding>>);
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0
+            .get(&action)
+            .map_or(action.default_bindings(), Vec::as_slice)
+    }
+
+    /// Binds `binding` to `action` in place of all of its current bindings. The binding is taken
+    /// from any other action that had it, so one key never does two things at once.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        for other in Action::ALL {
+            if other != action && self.get(other).contains(&binding) {
+                let remaining = self
+                    .get(other)
+                    .iter()
+                    .copied()
+                    .filter(|&other_binding| other_binding != binding)
+                    .collect();
+                self.0.insert(other, remaining);
+            }
+        }
+        self.0.insert(action, vec![binding]);
+    }
+
+    /// Puts `action` back to its default bindings.
+    pub fn reset(&mut self, action: Action) {
+        self.0.remove(&action);
+    }
+
+    /// Puts every action back to its default bindings.
+    pub fn reset_all(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// How the player's input is interpreted, beyond which keys are bound to what.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct InputOptions {
+    /// Whether [`Action::Walk`] switches between walking and running on each press, rather than
+    /// walking while held.
+    pub toggle_walk: bool,
+}
+
+/// [`Action`]s held by something other than a key or mouse button, like the on-screen touch
+/// controls.
+#[derive(Resource, Debug, Default)]
+pub struct VirtualActions {
+    held: HashSet<Action>,
+    /// Presses since the last frame, which may have been released already.
+    tapped: HashSet<Action>,
+    movement: f32,
+}
+
+impl VirtualActions {
+    pub fn press(&mut self, action: Action) {
+        if self.held.insert(action) {
+            self.tapped.insert(action);
+        }
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.held.remove(&action);
+    }
+
+    /// Presses or releases `action`, for sources that are polled rather than event-driven.
+    pub fn set(&mut self, action: Action, held: bool) {
+        if held {
+            self.press(action);
+        } else {
+            self.release(action);
+        }
+    }
+
+    /// Sets the horizontal movement from an analog source, from `-1.0` to `1.0`. See
+    /// [`AnalogMovement`].
+    pub fn set_movement(&mut self, movement: f32) {
+        self.movement = movement.clamp(-1.0, 1.0);
+    }
+}
+
+/// Horizontal movement from gamepad sticks and the touch joystick, from `-1.0` to `1.0`, with its
+/// magnitude the fraction of full speed. The strongest source wins, and it's `0.0` when none are
+/// pushed.
+#[derive(Resource, Debug, Default)]
+pub struct AnalogMovement(pub f32);
+
+/// Presses and releases each [`Action`] from the keys and mouse buttons bound to it, and from any
+/// [`VirtualActions`].
+fn update_actions(
+    bindings: Res<InputBindings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut virtual_actions: ResMut<VirtualActions>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    actions.clear();
+    for action in Action::ALL {
+        let bound = bindings.get(action);
+        let pressed = bound.iter().any(|binding| binding.pressed(&keys, &mouse))
+            || virtual_actions.held.contains(&action);
+        // A tap that started and ended within the frame is still a press.
+        let just_pressed = bound
+            .iter()
+            .any(|binding| binding.just_pressed(&keys, &mouse))
+            || virtual_actions.tapped.contains(&action);
+
+        if (pressed || just_pressed) && !actions.pressed(action) {
+            actions.press(action);
+        }
+        if !pressed && actions.pressed(action) {
+            actions.release(action);
+        }
+    }
+    virtual_actions.tapped.clear();
+}
+
+fn update_analog_movement(
+    gamepads: Query<&Gamepad>,
+    virtual_actions: Res<VirtualActions>,
+    mut movement: ResMut<AnalogMovement>,
+) {
+    movement.0 = gamepads
+        .iter()
+        .map(|gamepad| apply_deadzone(gamepad.left_stick().x, GAMEPAD_STICK_DEADZONE))
+        .chain([virtual_actions.movement])
+        .fold(0.0, |strongest, axis| {
+            if axis.abs() > strongest.abs() {
+                axis
+            } else {
+                strongest
+            }
+        });
+}
+
+fn fixed_input_plugin<T: Copy + Eq + Hash + Send + Sync + 'static>(app: &mut App) {
+    app.init_resource::<FixedInput<T>>()
+        .add_systems(PreUpdate, latch_input::<T>.after(InputSystems))
+        .add_systems(FixedPostUpdate, clear_input_edges::<T>);
+}
+
+/// The state of buttons of type `T` as of the current fixed tick.
+///
+/// Unlike [`ButtonInput`], a button that was pressed and released again since the last tick still
+/// counts as pressed, so the tick can act on it.
+#[derive(Resource, Debug)]
+pub struct FixedInput<T> {
+    pressed: HashSet<T>,
+    /// Presses since the last tick, which may have been released already.
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T> Default for FixedInput<T> {
+    fn default() -> Self {
+        Self {
+            pressed: default(),
+            just_pressed: default(),
+            just_released: default(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> FixedInput<T> {
+    /// Whether `input` is held, or has been pressed at all since the last tick.
+    pub fn pressed(&self, input: T) -> bool {
+        self.pressed.contains(&input) || self.just_pressed.contains(&input)
+    }
+
+    /// Whether `input` has been pressed since the last tick. Each press is only seen by one tick.
+    pub fn just_pressed(&self, input: T) -> bool {
+        self.just_pressed.contains(&input)
+    }
+
+    /// Whether `input` has been released since the last tick. Each release is only seen by one
+    /// tick.
+    pub fn just_released(&self, input: T) -> bool {
+        self.just_released.contains(&input)
+    }
+}
+
+fn latch_input<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    input: Res<ButtonInput<T>>,
+    mut fixed: ResMut<FixedInput<T>>,
+) {
+    fixed.pressed.clear();
+    fixed.pressed.extend(input.get_pressed().copied());
+    fixed.just_pressed.extend(input.get_just_pressed().copied());
+    fixed
+        .just_released
+        .extend(input.get_just_released().copied());
+}
+
+fn clear_input_edges<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    mut fixed: ResMut<FixedInput<T>>,
+) {
+    fixed.just_pressed.clear();
+    fixed.just_released.clear();
+}
@@ -0,0 +1,205 @@
+//! The game as a library, so that other binaries can run it.
+//!
+//! The game binary adds [`AppPlugin`]. Tools that don't want a window, like a headless test
+//! runner, can instead add [`GamePluginGroup`] on top of whichever Bevy plugins they need. The
+//! `render` and `audio` features control whether the group includes the plugins that need a
+//! renderer or audio output.
+
+// Support configuring Bevy lints within code.
+#![cfg_attr(bevy_lint, feature(register_tool), register_tool(bevy))]
+
+mod animation;
+mod asset_tracking;
+mod assets;
+mod assist;
+// Without audio output, only the bundles for spawning sounds are used.
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+mod audio;
+#[cfg(feature = "render")]
+mod background;
+mod config;
+mod controller;
+#[cfg(feature = "dev")]
+mod dev_tools;
+mod gameplay;
+mod input;
+mod math;
+mod menus;
+mod physics;
+mod save;
+mod screens;
+mod settings;
+mod theme;
+mod units;
+pub mod validation;
+
+/// Internals used by the benchmarks in `benches/`.
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::assets::level::tileset_image::TilesetImageBuilder;
+}
+
+pub mod prelude {
+    //! The types most often needed to drive the game from another binary.
+
+    pub use crate::{
+        AppPlugin, AppSystems, ContentPlugin, GamePluginGroup, PausableSystems, Pause,
+        assets::{
+            boss::Boss,
+            enemy::{Enemy, EnemyManifest},
+            level::Level,
+            world::LdtkWorld,
+        },
+        config::GameConfig,
+        gameplay::{
+            combat::{DamageEvent, Health},
+            enemies::{boss::BossDefeated, hooks::EnemyHooksAppExt},
+            player::Player,
+        },
+        screens::{Area, Screen},
+    };
+}
+
+use bevy::{
+    app::PluginGroupBuilder, asset::AssetMetaCheck, image::ImageSamplerDescriptor, prelude::*,
+};
+
+use crate::{config::GameConfig, gameplay::camera::PlayerCamera};
+
+/// The whole game, including Bevy's [`DefaultPlugins`].
+pub struct AppPlugin;
+
+impl Plugin for AppPlugin {
+    fn build(&self, app: &mut App) {
+        // Load the config first, since it affects how the other plugins are set up.
+        let config = GameConfig::load();
+
+        // Add Bevy plugins.
+        app.add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                    // This causes errors and even panics on web build on itch.
+                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                    meta_check: AssetMetaCheck::Never,
+                    ..default()
+                })
+                .set(ImagePlugin {
+                    // Set the default image sampler to nearest since we're using pixel art for
+                    // everything.
+                    default_sampler: ImageSamplerDescriptor::nearest(),
+                })
+                .set(WindowPlugin {
+                    primary_window: config.primary_window().into(),
+                    ..default()
+                }),
+        );
+
+        app.insert_resource(config);
+
+        app.add_plugins(GamePluginGroup);
+    }
+}
+
+/// The game's asset types and their loaders, without anything that uses them.
+///
+/// Lets tools load levels and manifests exactly like the game does. Needs Bevy's [`AssetPlugin`]
+/// and [`ImagePlugin`], but not a window or renderer.
+pub struct ContentPlugin;
+
+impl Plugin for ContentPlugin {
+    fn build(&self, app: &mut App) {
+        // Usually registered by the `SpritePlugin`, but the enemy manifest has atlas layouts.
+        app.init_asset::<TextureAtlasLayout>()
+            .add_plugins((assets::plugin, animation::plugin));
+    }
+}
+
+/// Every plugin that makes up the game, without any of Bevy's own plugins.
+///
+/// Loads the [`GameConfig`] if it hasn't already been inserted.
+pub struct GamePluginGroup;
+
+impl PluginGroup for GamePluginGroup {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(core_plugin)
+            .add(ContentPlugin)
+            .add(asset_tracking::plugin)
+            .add(assist::plugin)
+            .add(physics::plugin)
+            .add(save::plugin)
+            .add(settings::plugin)
+            .add(input::plugin)
+            .add(controller::plugin)
+            .add(gameplay::plugin);
+        #[cfg(feature = "audio")]
+        let group = group.add(audio::plugin);
+        #[cfg(feature = "render")]
+        let group = group.add(background::plugin);
+        #[cfg(feature = "dev")]
+        let group = group.add(dev_tools::plugin);
+        group
+            .add(menus::plugin)
+            .add(screens::plugin)
+            .add(theme::plugin)
+    }
+}
+
+fn core_plugin(app: &mut App) {
+    if !app.world().contains_resource::<GameConfig>() {
+        app.insert_resource(GameConfig::load());
+    }
+
+    // Order new `AppSystems` variants by adding them here:
+    app.configure_sets(
+        Update,
+        (
+            AppSystems::TickTimers,
+            AppSystems::RecordInput,
+            AppSystems::Update,
+        )
+            .chain(),
+    );
+
+    // Set up the `Pause` state.
+    app.init_state::<Pause>();
+    app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+
+    // Spawn the main camera.
+    app.add_systems(Startup, spawn_camera);
+}
+
+/// High-level groupings of systems for the app in the `Update` schedule.
+/// When adding a new variant, make sure to order it in the `configure_sets`
+/// call above.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum AppSystems {
+    /// Tick timers.
+    TickTimers,
+    /// Record player input.
+    RecordInput,
+    /// Do everything else (consider splitting this into further variants).
+    Update,
+}
+
+/// Whether or not the game is paused.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Pause(pub bool);
+
+/// A system set for systems that shouldn't run while the game is paused.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PausableSystems;
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Camera"),
+        Camera2d,
+        PlayerCamera,
+        audio::SoundListener::default(),
+        Projection::Orthographic(OrthographicProjection {
+            scale: 0.75 * 1. / 32.,
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+}
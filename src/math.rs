@@ -0,0 +1,68 @@
+//! Math helpers shared between modules.
+
+use bevy::math::VectorSpace;
+
+/// Moves `current` towards `target`, closing the gap at an exponential `rate` per second.
+///
+/// Unlike lerping by `rate * dt` each frame, this gives the same result however `dt` is split up,
+/// so smoothing doesn't speed up or slow down with the frame rate. After `1 / rate` seconds about
+/// 63% of the gap is closed, and it never overshoots.
+pub fn exp_smooth<T: VectorSpace<Scalar = f32>>(current: T, target: T, rate: f32, dt: f32) -> T {
+    current.lerp(target, 1.0 - (-rate * dt).exp())
+}
+
+/// Zeroes an analog `axis` inside the `deadzone` and rescales the rest to still reach `1.0`, so
+/// the output grows smoothly from zero at the edge of the deadzone rather than jumping.
+pub fn apply_deadzone(axis: f32, deadzone: f32) -> f32 {
+    let magnitude = ((axis.abs() - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+    magnitude.copysign(axis)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::*;
+
+    /// Smooths from `start` to `target` for one second, at `hz` updates per second.
+    fn smooth_for_a_second<T: VectorSpace<Scalar = f32>>(start: T, target: T, hz: u32) -> T {
+        let dt = 1.0 / hz as f32;
+        (0..hz).fold(start, |current, _| exp_smooth(current, target, 4.0, dt))
+    }
+
+    #[test]
+    fn independent_of_frame_rate() {
+        let [at_30, at_60, at_240] = [30, 60, 240].map(|hz| smooth_for_a_second(0.0, 1.0, hz));
+        assert!((at_30 - at_60).abs() < 1e-5, "{at_30} != {at_60}");
+        assert!((at_60 - at_240).abs() < 1e-5, "{at_60} != {at_240}");
+        // 1 - e^-4
+        assert!((at_60 - 0.981_684).abs() < 1e-5, "{at_60}");
+    }
+
+    #[test]
+    fn independent_of_frame_rate_vec2() {
+        let start = Vec2::new(-3.0, 2.0);
+        let target = Vec2::new(5.0, -1.0);
+        let [at_30, at_60, at_240] = [30, 60, 240].map(|hz| smooth_for_a_second(start, target, hz));
+        assert!(at_30.abs_diff_eq(at_60, 1e-4), "{at_30} != {at_60}");
+        assert!(at_60.abs_diff_eq(at_240, 1e-4), "{at_60} != {at_240}");
+    }
+
+    #[test]
+    fn never_overshoots() {
+        // Even with a huge time step, the result stops at the target.
+        assert_eq!(exp_smooth(0.0, 1.0, 4.0, 100.0), 1.0);
+        assert_eq!(exp_smooth(2.0, 2.0, 4.0, 1.0 / 60.0), 2.0);
+    }
+
+    #[test]
+    fn deadzone_is_continuous() {
+        assert_eq!(apply_deadzone(0.1, 0.2), 0.0);
+        assert_eq!(apply_deadzone(-0.2, 0.2), 0.0);
+        assert!(apply_deadzone(0.21, 0.2) < 0.02);
+        assert!((apply_deadzone(0.6, 0.2) - 0.5).abs() < 1e-6);
+        assert_eq!(apply_deadzone(-1.0, 0.2), -1.0);
+        // Sticks can report slightly more than `1.0` at their edges.
+        assert_eq!(apply_deadzone(1.05, 0.2), 1.0);
+    }
+}
@@ -0,0 +1,222 @@
+//! The calibration menu, shown before the player's first game.
+//!
+//! A block sweeps back and forth across the screen at up to [`PREVIEW_MAX_BETA`], drawn with
+//! the same length contraction, Doppler shift and heat haze as in the game, while the player picks
+//! an [`EffectsIntensity`] that they are comfortable with. The choice is saved, and can be changed
+//! later from the settings menu.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::ResourceHandles,
+    gameplay::camera::PlayerCamera,
+    menus::{Menu, main::enter_loading_or_gameplay},
+    physics::{
+        DopplerShift, DopplerSprite, EffectsIntensity, HeatHaze, LorentzFactor, doppler_factor,
+        gamma, haze_strength,
+    },
+    screens::Screen,
+    settings::Settings,
+    theme::prelude::*,
+};
+
+/// How far the preview moves either side of the middle of the screen, in world units.
+const PREVIEW_AMPLITUDE: f32 = 6.0;
+const PREVIEW_PERIOD_SECS: f32 = 3.0;
+/// The preview's top speed, as a fraction of the speed of light.
+const PREVIEW_MAX_BETA: f32 = 0.85;
+const PREVIEW_SIZE: f32 = 1.5;
+const INTENSITY_STEP: f32 = 0.1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, load_effects_intensity)
+        .add_systems(
+            OnEnter(Menu::Calibration),
+            (spawn_calibration_menu, spawn_calibration_preview),
+        )
+        .add_systems(
+            Update,
+            (
+                animate_calibration_preview,
+                update_intensity_label.run_if(
+                    resource_changed::<EffectsIntensity>
+                        .or(any_match_filter::<Added<IntensityLabel>>),
+                ),
+            )
+                .run_if(in_state(Menu::Calibration)),
+        )
+        .add_systems(
+            Update,
+            save_effects_intensity.run_if(
+                resource_changed::<EffectsIntensity>.and(not(resource_added::<EffectsIntensity>)),
+            ),
+        );
+}
+
+/// Whether the player has yet to go through the calibration menu.
+pub(super) fn needs_calibration(settings: &Settings) -> bool {
+    settings.effects_intensity.is_none()
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CalibrationPreview;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(super) struct IntensityLabel;
+
+fn load_effects_intensity(settings: Res<Settings>, mut intensity: ResMut<EffectsIntensity>) {
+    if let Some(saved) = settings.effects_intensity {
+        intensity.0 = saved;
+    }
+}
+
+fn save_effects_intensity(intensity: Res<EffectsIntensity>, mut settings: ResMut<Settings>) {
+    settings.effects_intensity = Some(intensity.0);
+}
+
+fn spawn_calibration_menu(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Calibration Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            // Leave the middle of the screen clear for the preview.
+            justify_content: JustifyContent::SpaceBetween,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::vertical(px(40)),
+            ..default()
+        },
+        Pickable::IGNORE,
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Calibration),
+        children![
+            (
+                Name::new("Calibration Header"),
+                Node {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(10),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                children![
+                    widget::header("Relativistic Effects"),
+                    widget::label("Fast objects are squashed and shift color."),
+                    widget::label("Turn the effects down if they're uncomfortable."),
+                ],
+            ),
+            (
+                Name::new("Calibration Controls"),
+                Node {
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(20),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                children![
+                    intensity_widget(),
+                    widget::button("Continue", finish_calibration),
+                ],
+            ),
+        ],
+    ));
+}
+
+/// Buttons to lower and raise the [`EffectsIntensity`], either side of its current value.
+pub(super) fn intensity_widget() -> impl Bundle {
+    (
+        Name::new("Effects Intensity Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_effects_intensity),
+            (
+                Name::new("Current Intensity"),
+                Node {
+                    padding: UiRect::horizontal(px(10)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), IntensityLabel)],
+            ),
+            widget::button_small("+", raise_effects_intensity),
+        ],
+    )
+}
+
+fn lower_effects_intensity(_: On<Pointer<Click>>, mut intensity: ResMut<EffectsIntensity>) {
+    intensity.0 = (intensity.0 - INTENSITY_STEP).max(0.0);
+}
+
+fn raise_effects_intensity(_: On<Pointer<Click>>, mut intensity: ResMut<EffectsIntensity>) {
+    intensity.0 = (intensity.0 + INTENSITY_STEP).min(1.0);
+}
+
+pub(super) fn update_intensity_label(
+    intensity: Res<EffectsIntensity>,
+    mut labels: Query<&mut Text, With<IntensityLabel>>,
+) {
+    for mut label in &mut labels {
+        label.0 = format!("{:3.0}%", 100.0 * intensity.0);
+    }
+}
+
+fn spawn_calibration_preview(camera: Single<Entity, With<PlayerCamera>>, mut commands: Commands) {
+    commands.spawn((
+        Name::new("Calibration Preview"),
+        CalibrationPreview,
+        DopplerSprite,
+        LorentzFactor::default(),
+        Sprite::from_color(Color::WHITE, Vec2::splat(PREVIEW_SIZE)),
+        Transform::default(),
+        ChildOf(*camera),
+        DespawnOnExit(Menu::Calibration),
+    ));
+}
+
+/// Moves the preview back and forth, as seen by an observer to its right.
+fn animate_calibration_preview(
+    time: Res<Time<Real>>,
+    mut previews: Query<
+        (
+            &mut Transform,
+            &mut LorentzFactor,
+            &mut DopplerShift,
+            &mut HeatHaze,
+        ),
+        With<CalibrationPreview>,
+    >,
+) {
+    let omega = TAU / PREVIEW_PERIOD_SECS;
+    let c = PREVIEW_AMPLITUDE * omega / PREVIEW_MAX_BETA;
+    let phase = omega * time.elapsed_secs();
+    let velocity = PREVIEW_AMPLITUDE * omega * phase.cos();
+
+    for (mut transform, mut lorentz, mut shift, mut haze) in &mut previews {
+        transform.translation.x = PREVIEW_AMPLITUDE * phase.sin();
+        lorentz.0 = Vec2::new(gamma(velocity, c), 1.0);
+        shift.0 = doppler_factor(velocity, c);
+        haze.strength = haze_strength((velocity / c).abs());
+        haze.direction = Vec2::X * velocity.signum();
+    }
+}
+
+fn finish_calibration(
+    _: On<Pointer<Click>>,
+    intensity: Res<EffectsIntensity>,
+    resource_handles: Res<ResourceHandles>,
+    mut settings: ResMut<Settings>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    settings.effects_intensity = Some(intensity.0);
+    enter_loading_or_gameplay(&resource_handles, &mut next_screen);
+}
@@ -0,0 +1,178 @@
+//! The controls menu, opened from the settings menu.
+//!
+//! Pressing an action's rebind button waits for the next key or mouse button, which then replaces
+//! that action's bindings in the player's [`Settings`]. `Escape` cancels a rebind rather than being
+//! bound, so the menu can always be left.
+
+use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    input::{Action, Binding},
+    menus::Menu,
+    settings::Settings,
+    theme::prelude::*,
+};
+
+const CANCEL_REBIND_KEY: KeyCode = KeyCode::Escape;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Controls), spawn_controls_menu);
+    app.add_systems(OnExit(Menu::Controls), cancel_rebind);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(
+                input_just_pressed(Action::Back).and(not(resource_exists::<PendingRebind>)),
+            ),
+            capture_rebind.run_if(resource_exists::<PendingRebind>),
+            update_binding_labels.run_if(
+                resource_changed::<Settings>
+                    .or(resource_changed::<PendingRebind>)
+                    .or(resource_removed::<PendingRebind>)
+                    .or(any_match_filter::<Added<BindingLabel>>),
+            ),
+        )
+            .chain()
+            .run_if(in_state(Menu::Controls)),
+    );
+}
+
+/// The action waiting for the player to press its new binding.
+#[derive(Resource, Debug)]
+struct PendingRebind(Action);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BindingLabel(Action);
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Controls Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Controls),
+        children![
+            widget::header("Controls"),
+            controls_grid(),
+            widget::button("Reset Controls", reset_controls),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn controls_grid() -> impl Bundle {
+    (
+        Name::new("Controls Grid"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: px(10),
+            ..default()
+        },
+        Children::spawn(SpawnIter(Action::ALL.into_iter().map(control_row))),
+    )
+}
+
+fn control_row(action: Action) -> impl Bundle {
+    (
+        Name::new(format!("{action:?} Row")),
+        Node {
+            display: Display::Grid,
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            ..default()
+        },
+        children![
+            (
+                widget::label(action.name()),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                },
+            ),
+            binding_widget(action),
+        ],
+    )
+}
+
+/// The action's current bindings, with buttons to rebind it and to reset it to its defaults.
+fn binding_widget(action: Action) -> impl Bundle {
+    (
+        Name::new(format!("{action:?} Widget")),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            column_gap: px(10),
+            ..default()
+        },
+        children![
+            widget::button_small("+", move |_: On<Pointer<Click>>, mut commands: Commands| {
+                commands.insert_resource(PendingRebind(action));
+            }),
+            widget::button_small(
+                "-",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    settings.bindings.reset(action);
+                }
+            ),
+            (widget::label(""), BindingLabel(action)),
+        ],
+    )
+}
+
+fn update_binding_labels(
+    settings: Res<Settings>,
+    pending: Option<Res<PendingRebind>>,
+    mut labels: Query<(&BindingLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut labels {
+        text.0 = if pending.as_ref().is_some_and(|pending| pending.0 == label.0) {
+            "Press a key...".to_string()
+        } else {
+            let names = settings
+                .bindings
+                .get(label.0)
+                .iter()
+                .map(|binding| binding.name())
+                .collect::<Vec<_>>();
+            if names.is_empty() {
+                "Unbound".to_string()
+            } else {
+                names.join(" / ")
+            }
+        };
+    }
+}
+
+/// Binds the first key or mouse button pressed to the pending action.
+fn capture_rebind(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pending: Res<PendingRebind>,
+    mut settings: ResMut<Settings>,
+    mut commands: Commands,
+) {
+    if keys.just_pressed(CANCEL_REBIND_KEY) {
+        commands.remove_resource::<PendingRebind>();
+        return;
+    }
+    let Some(binding) = Binding::first_just_pressed(&keys, &mouse) else {
+        return;
+    };
+    settings.bindings.rebind(pending.0, binding);
+    commands.remove_resource::<PendingRebind>();
+}
+
+fn cancel_rebind(mut commands: Commands) {
+    commands.remove_resource::<PendingRebind>();
+}
+
+fn reset_controls(_: On<Pointer<Click>>, mut settings: ResMut<Settings>) {
+    settings.bindings.reset_all();
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
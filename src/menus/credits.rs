@@ -2,17 +2,24 @@
 
 use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::{PlayMusic, StopMusic},
+    input::Action,
+    menus::Menu,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(Action::Back))),
     );
 
     app.load_resource::<CreditsAssets>();
     app.add_systems(OnEnter(Menu::Credits), start_credits_music);
+    app.add_systems(OnExit(Menu::Credits), stop_credits_music);
 }
 
 fn spawn_credits_menu(mut commands: Commands) {
@@ -105,9 +112,13 @@ impl FromWorld for CreditsAssets {
 }
 
 fn start_credits_music(mut commands: Commands, credits_music: Res<CreditsAssets>) {
-    commands.spawn((
-        Name::new("Credits Music"),
-        DespawnOnExit(Menu::Credits),
-        music(credits_music.music.clone(), 0.5),
-    ));
+    commands.trigger(PlayMusic {
+        track: credits_music.music.clone(),
+        volume: 0.5,
+        layers: Vec::new(),
+    });
+}
+
+fn stop_credits_music(mut commands: Commands) {
+    commands.trigger(StopMusic);
 }
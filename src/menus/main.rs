@@ -2,7 +2,13 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    menus::{Menu, calibration::needs_calibration},
+    screens::Screen,
+    settings::Settings,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
@@ -32,7 +38,20 @@ fn spawn_main_menu(mut commands: Commands) {
 fn enter_loading_or_gameplay_screen(
     _: On<Pointer<Click>>,
     resource_handles: Res<ResourceHandles>,
+    settings: Res<Settings>,
+    mut next_menu: ResMut<NextState<Menu>>,
     mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if needs_calibration(&settings) {
+        next_menu.set(Menu::Calibration);
+    } else {
+        enter_loading_or_gameplay(&resource_handles, &mut next_screen);
+    }
+}
+
+pub(super) fn enter_loading_or_gameplay(
+    resource_handles: &ResourceHandles,
+    next_screen: &mut NextState<Screen>,
 ) {
     if resource_handles.is_all_done() {
         next_screen.set(Screen::Gameplay);
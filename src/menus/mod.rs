@@ -1,8 +1,11 @@
 //! The game's menus and transitions between them.
 
+mod calibration;
+mod controls;
 mod credits;
 mod main;
 mod pause;
+mod results;
 mod settings;
 
 use bevy::prelude::*;
@@ -11,10 +14,13 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        calibration::plugin,
+        controls::plugin,
         credits::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
+        results::plugin,
     ));
 }
 
@@ -25,5 +31,11 @@ pub enum Menu {
     Main,
     Credits,
     Settings,
+    /// Lists the controls, opened from the settings menu.
+    Controls,
     Pause,
+    /// Picks the intensity of relativistic effects before the first game.
+    Calibration,
+    /// The stats of a run that was just finished.
+    Results,
 }
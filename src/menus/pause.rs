@@ -2,18 +2,29 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    assist::AssistSettings,
+    gameplay::level::exit::SkipLevel,
+    input::Action,
+    menus::Menu,
+    screens::{Area, Screen},
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(Action::Back))),
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
-    commands.spawn((
+fn spawn_pause_menu(
+    mut commands: Commands,
+    area: Option<Res<State<Area>>>,
+    assist: Res<AssistSettings>,
+) {
+    let mut menu = commands.spawn((
         widget::ui_root("Pause Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Pause),
@@ -21,9 +32,16 @@ fn spawn_pause_menu(mut commands: Commands) {
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
             widget::button("Settings", open_settings_menu),
-            widget::button("Quit to title", quit_to_title),
         ],
     ));
+
+    if area.is_some_and(|area| *area.get() == Area::Level) {
+        if assist.skip_level {
+            menu.with_child(widget::button("Skip level", skip_level));
+        }
+        menu.with_child(widget::button("Return to hub", return_to_hub));
+    }
+    menu.with_child(widget::button("Quit to title", quit_to_title));
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
@@ -34,6 +52,24 @@ fn close_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
 
+fn skip_level(
+    _: On<Pointer<Click>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut commands: Commands,
+) {
+    next_menu.set(Menu::None);
+    commands.trigger(SkipLevel);
+}
+
+fn return_to_hub(
+    _: On<Pointer<Click>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_area: ResMut<NextState<Area>>,
+) {
+    next_menu.set(Menu::None);
+    next_area.set(Area::Hub);
+}
+
 fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
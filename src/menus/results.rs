@@ -0,0 +1,124 @@
+//! The results screen, shown when a run is finished.
+
+use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    Pause,
+    gameplay::level::{
+        exit::RunFinished,
+        stats::{GameStats, LevelRecord},
+    },
+    input::Action,
+    menus::Menu,
+    screens::Area,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(open_results_menu);
+    app.add_systems(OnEnter(Menu::Results), spawn_results_menu);
+    app.add_systems(
+        Update,
+        return_to_hub.run_if(in_state(Menu::Results).and(input_just_pressed(Action::Back))),
+    );
+}
+
+fn open_results_menu(
+    _: On<RunFinished>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::Results);
+}
+
+fn spawn_results_menu(mut commands: Commands, stats: Res<GameStats>) {
+    let (items_collected, items_total) = stats.total_items();
+    commands.spawn((
+        widget::ui_root("Results Menu"),
+        GlobalZIndex(2),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        DespawnOnExit(Menu::Results),
+        children![
+            widget::header("Run complete"),
+            grid(
+                2,
+                vec![
+                    "Total time".to_string(),
+                    format_secs(stats.total_secs()),
+                    "Deaths".to_string(),
+                    stats.total_deaths().to_string(),
+                    "Top speed".to_string(),
+                    format!("{:.0}% of c", stats.max_beta * 100.0),
+                    "Items found".to_string(),
+                    format!("{items_collected}/{items_total}"),
+                    "Score".to_string(),
+                    stats.total_score().to_string(),
+                ],
+            ),
+            widget::header("Levels"),
+            grid(4, stats.levels.iter().flat_map(level_row).collect()),
+            widget::button("Return to hub", return_to_hub_on_click),
+        ],
+    ));
+}
+
+fn level_row(level: &LevelRecord) -> [String; 4] {
+    [
+        level.name.clone(),
+        format_secs(level.elapsed_secs),
+        match level.deaths {
+            1 => "1 death".to_string(),
+            deaths => format!("{deaths} deaths"),
+        },
+        format!("{}/{} items", level.items_collected, level.items_total),
+    ]
+}
+
+fn format_secs(secs: f32) -> String {
+    let minutes = (secs / 60.0) as u32;
+    format!("{minutes}:{:04.1}", secs - minutes as f32 * 60.0)
+}
+
+/// Lays `content` out in `columns`, with the first column right-aligned and the rest
+/// left-aligned.
+fn grid(columns: u16, content: Vec<String>) -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(10),
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::auto(columns),
+            ..default()
+        },
+        Children::spawn(SpawnIter(content.into_iter().enumerate().map(
+            move |(i, text)| {
+                (
+                    widget::label(text),
+                    Node {
+                        justify_self: if i.is_multiple_of(columns as usize) {
+                            JustifySelf::End
+                        } else {
+                            JustifySelf::Start
+                        },
+                        ..default()
+                    },
+                )
+            },
+        ))),
+    )
+}
+
+fn return_to_hub_on_click(
+    _: On<Pointer<Click>>,
+    next_menu: ResMut<NextState<Menu>>,
+    next_area: ResMut<NextState<Area>>,
+) {
+    return_to_hub(next_menu, next_area);
+}
+
+fn return_to_hub(mut next_menu: ResMut<NextState<Menu>>, mut next_area: ResMut<NextState<Area>>) {
+    next_menu.set(Menu::None);
+    next_area.set(Area::Hub);
+}
@@ -1,21 +1,43 @@
 //! The settings menu.
 //!
-//! Additional settings and accessibility options should go here.
+//! Additional settings and accessibility options should go here. Assist options are grouped
+//! under their own header. Everything but the assist options is saved in [`Settings`].
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    assist::{AssistSettings, MIN_DAMAGE_MULTIPLIER, MIN_TIME_SCALE},
+    input::Action,
+    menus::{
+        Menu,
+        calibration::{IntensityLabel, intensity_widget, update_intensity_label},
+    },
+    physics::EffectsIntensity,
+    screens::Screen,
+    settings::{MAX_VOLUME, MIN_VOLUME, Settings},
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(Action::Back))),
     );
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_setting_labels
+                .run_if(resource_changed::<Settings>.or(any_match_filter::<Added<SettingLabel>>)),
+            update_intensity_label.run_if(
+                resource_changed::<EffectsIntensity>.or(any_match_filter::<Added<IntensityLabel>>),
+            ),
+            update_assist_labels.run_if(
+                resource_changed::<AssistSettings>.or(any_match_filter::<Added<AssistLabel>>),
+            ),
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -27,6 +49,9 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::header("Assist"),
+            assist_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -43,64 +68,211 @@ fn settings_grid() -> impl Bundle {
             ..default()
         },
         children![
+            row_label("Master Volume"),
+            setting_widget(SettingOption::Volume),
+            row_label("Fullscreen"),
+            setting_widget(SettingOption::Fullscreen),
+            row_label("VSync"),
+            setting_widget(SettingOption::Vsync),
+            row_label("Toggle Walk"),
+            setting_widget(SettingOption::ToggleWalk),
+            row_label("Relativistic Effects"),
+            intensity_widget(),
+        ],
+    )
+}
+
+/// Buttons to lower and raise a setting, either side of its current value. Toggles are turned off
+/// by lowering and on by raising.
+fn setting_widget(option: SettingOption) -> impl Bundle {
+    (
+        Name::new(format!("{option:?} Widget")),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small(
+                "-",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    option.step(&mut settings, -1.0);
+                }
+            ),
             (
-                widget::label("Master Volume"),
+                Name::new("Current Value"),
                 Node {
-                    justify_self: JustifySelf::End,
+                    padding: UiRect::horizontal(px(10)),
+                    justify_content: JustifyContent::Center,
                     ..default()
+                },
+                children![(widget::label(""), SettingLabel(option))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<Settings>| {
+                    option.step(&mut settings, 1.0);
                 }
             ),
-            global_volume_widget(),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+#[derive(Reflect, Debug, Clone, Copy)]
+enum SettingOption {
+    Volume,
+    Fullscreen,
+    Vsync,
+    ToggleWalk,
+}
+
+impl SettingOption {
+    fn step(self, settings: &mut Settings, direction: f32) {
+        match self {
+            Self::Volume => {
+                settings.volume = (settings.volume + 0.1 * direction).clamp(MIN_VOLUME, MAX_VOLUME);
+            }
+            Self::Fullscreen => settings.fullscreen = direction > 0.0,
+            Self::Vsync => settings.vsync = direction > 0.0,
+            Self::ToggleWalk => settings.input_options.toggle_walk = direction > 0.0,
+        }
+    }
+
+    fn value(self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+        match self {
+            Self::Volume => format!("{:3.0}%", 100.0 * settings.volume),
+            Self::Fullscreen => on_off(settings.fullscreen),
+            Self::Vsync => on_off(settings.vsync),
+            Self::ToggleWalk => on_off(settings.input_options.toggle_walk),
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SettingLabel(SettingOption);
+
+fn update_setting_labels(settings: Res<Settings>, mut labels: Query<(&SettingLabel, &mut Text)>) {
+    for (label, mut text) in &mut labels {
+        text.0 = label.0.value(&settings);
+    }
+}
+
+fn assist_grid() -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Assist Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(10),
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            ..default()
+        },
+        children![
+            row_label("Damage Taken"),
+            assist_widget(AssistOption::DamageMultiplier),
+            row_label("Infinite Jumps"),
+            assist_widget(AssistOption::InfiniteJumps),
+            row_label("Game Speed"),
+            assist_widget(AssistOption::TimeScale),
+            row_label("Skip Level"),
+            assist_widget(AssistOption::SkipLevel),
+        ],
+    )
+}
+
+fn row_label(text: &str) -> impl Bundle {
+    (
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
+    )
+}
+
+/// Buttons to lower and raise an assist option, either side of its current value. Toggles are
+/// turned off by lowering and on by raising.
+fn assist_widget(option: AssistOption) -> impl Bundle {
+    (
+        Name::new(format!("{option:?} Widget")),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small(
+                "-",
+                move |_: On<Pointer<Click>>, mut assist: ResMut<AssistSettings>| {
+                    option.step(&mut assist, -1.0);
+                }
+            ),
             (
-                Name::new("Current Volume"),
+                Name::new("Current Value"),
                 Node {
                     padding: UiRect::horizontal(px(10)),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), AssistLabel(option))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: On<Pointer<Click>>, mut assist: ResMut<AssistSettings>| {
+                    option.step(&mut assist, 1.0);
+                }
             ),
-            widget::button_small("+", raise_global_volume),
         ],
     )
 }
 
-const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
-
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+#[derive(Reflect, Debug, Clone, Copy)]
+enum AssistOption {
+    DamageMultiplier,
+    InfiniteJumps,
+    TimeScale,
+    SkipLevel,
 }
 
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+impl AssistOption {
+    fn step(self, assist: &mut AssistSettings, direction: f32) {
+        match self {
+            Self::DamageMultiplier => {
+                assist.damage_multiplier =
+                    (assist.damage_multiplier + 0.25 * direction).clamp(MIN_DAMAGE_MULTIPLIER, 1.0);
+            }
+            Self::InfiniteJumps => assist.infinite_jumps = direction > 0.0,
+            Self::TimeScale => {
+                assist.time_scale =
+                    (assist.time_scale + 0.1 * direction).clamp(MIN_TIME_SCALE, 1.0);
+            }
+            Self::SkipLevel => assist.skip_level = direction > 0.0,
+        }
+    }
+
+    fn value(self, assist: &AssistSettings) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+        match self {
+            Self::DamageMultiplier => format!("{:3.0}%", 100.0 * assist.damage_multiplier),
+            Self::InfiniteJumps => on_off(assist.infinite_jumps),
+            Self::TimeScale => format!("{:3.0}%", 100.0 * assist.time_scale),
+            Self::SkipLevel => on_off(assist.skip_level),
+        }
+    }
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct AssistLabel(AssistOption);
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
-) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+fn update_assist_labels(assist: Res<AssistSettings>, mut labels: Query<(&AssistLabel, &mut Text)>) {
+    for (label, mut text) in &mut labels {
+        text.0 = label.0.value(&assist);
+    }
+}
+
+fn open_controls_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
 }
 
 fn go_back_on_click(
@@ -0,0 +1,215 @@
+use avian2d::prelude::{LinearVelocity, Position};
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, encase::private::ShaderType},
+    sprite_render::{AlphaMode2d, Material2d, Material2dPlugin},
+};
+
+use crate::{
+    PausableSystems,
+    gameplay::{combat::HitFlash, player::Player},
+    physics::{EffectsIntensity, HeatHaze, LocalSpeedOfLight, beta},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<DopplerMaterial>::default())
+        .init_resource::<DopplerAssets>()
+        .add_observer(spawn_doppler_mesh)
+        .add_systems(Update, update_doppler_shifts.in_set(PausableSystems))
+        .add_systems(PostUpdate, sync_doppler_sprites);
+}
+
+/// Renders an entity's [`Sprite`] through a [`DopplerMaterial`], so that it appears blue-shifted
+/// while approaching the player and red-shifted while receding.
+///
+/// The strength of the effect depends on the entity's speed relative to [`LocalSpeedOfLight`].
+/// Fast enough entities also shimmer with a [`HeatHaze`].
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+#[require(DopplerShift, HeatHaze)]
+pub struct DopplerSprite;
+
+/// The relativistic Doppler factor of an entity as observed by the player.
+///
+/// Values greater than `1.0` are blue-shifted (approaching), and values less than `1.0` are
+/// red-shifted (receding).
+#[derive(Component, Reflect, Deref, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct DopplerShift(pub f32);
+
+impl Default for DopplerShift {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The mesh entity that draws a [`DopplerSprite`] in place of its [`Sprite`].
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DopplerSpriteMesh(Entity);
+
+#[derive(AsBindGroup, Asset, Reflect, Clone, Default)]
+#[uniform(0, DopplerUniforms)]
+pub struct DopplerMaterial {
+    /// The Doppler factor to apply. See [`DopplerShift`].
+    pub shift: f32,
+    /// The region of [`texture`] to draw, in UV coordinates.
+    ///
+    /// [`texture`]: Self::texture
+    pub uv_rect: Rect,
+    pub flip_x: bool,
+    /// How far the sprite is washed out to white, from `0.0` to `1.0`. See [`HitFlash`].
+    pub flash: f32,
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Handle<Image>,
+}
+
+impl Material2d for DopplerMaterial {
+    fn fragment_shader() -> bevy::shader::ShaderRef {
+        "shaders/doppler.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+#[derive(ShaderType)]
+#[repr(C)]
+struct DopplerUniforms {
+    uv_rect: Vec4,
+    shift: f32,
+    flip_x: u32,
+    flash: f32,
+    // Maintain 16-byte alignment for WASM targets
+    _pad: f32,
+}
+
+impl From<&DopplerMaterial> for DopplerUniforms {
+    fn from(value: &DopplerMaterial) -> Self {
+        Self {
+            uv_rect: Vec4::new(
+                value.uv_rect.min.x,
+                value.uv_rect.min.y,
+                value.uv_rect.max.x,
+                value.uv_rect.max.y,
+            ),
+            shift: value.shift,
+            flip_x: value.flip_x.into(),
+            flash: value.flash,
+            _pad: 0.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct DopplerAssets {
+    mesh: Handle<Mesh>,
+}
+
+impl FromWorld for DopplerAssets {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            mesh: world
+                .resource_mut::<Assets<Mesh>>()
+                .add(Rectangle::from_size(Vec2::ONE)),
+        }
+    }
+}
+
+/// Returns the relativistic Doppler factor for a source moving towards the observer at
+/// `radial_speed`. Negative speeds are receding.
+pub fn doppler_factor(radial_speed: f32, c: f32) -> f32 {
+    let b = beta(radial_speed, c);
+    ((1.0 + b) / (1.0 - b)).sqrt()
+}
+
+fn update_doppler_shifts(
+    c: Res<LocalSpeedOfLight>,
+    player: Single<(&Position, &LinearVelocity), With<Player>>,
+    mut sources: Query<(&Position, &LinearVelocity, &mut DopplerShift), Without<Player>>,
+) {
+    let (player_pos, player_vel) = player.into_inner();
+    for (position, velocity, mut shift) in &mut sources {
+        let towards_player = (player_pos.0 - position.0).normalize_or_zero();
+        let radial_speed = (velocity.0 - player_vel.0).dot(towards_player);
+        shift.0 = doppler_factor(radial_speed, c.0);
+    }
+}
+
+fn spawn_doppler_mesh(
+    add: On<Add, DopplerSprite>,
+    assets: Res<DopplerAssets>,
+    mut materials: ResMut<Assets<DopplerMaterial>>,
+    mut sprites: Query<&mut Sprite>,
+    mut commands: Commands,
+) {
+    let Ok(mut sprite) = sprites.get_mut(add.entity) else {
+        return;
+    };
+
+    let size = sprite.custom_size.unwrap_or(Vec2::ONE);
+    let material = materials.add(DopplerMaterial {
+        texture: sprite.image.clone(),
+        ..default()
+    });
+
+    // The mesh draws the sprite from here on.
+    sprite.color = Color::NONE;
+
+    let mesh = commands
+        .spawn((
+            Name::new("Doppler Sprite"),
+            ChildOf(add.entity),
+            Transform::from_scale(size.extend(1.0)),
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(material),
+        ))
+        .id();
+    commands.entity(add.entity).insert(DopplerSpriteMesh(mesh));
+}
+
+fn sync_doppler_sprites(
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    intensity: Res<EffectsIntensity>,
+    sprites: Query<(
+        &Sprite,
+        &DopplerShift,
+        &DopplerSpriteMesh,
+        Option<&HitFlash>,
+    )>,
+    mut meshes: Query<(&MeshMaterial2d<DopplerMaterial>, &mut Transform)>,
+    mut materials: ResMut<Assets<DopplerMaterial>>,
+) {
+    for (sprite, shift, mesh, flash) in &sprites {
+        let Ok((handle, mut transform)) = meshes.get_mut(mesh.0) else {
+            continue;
+        };
+
+        let size = sprite.custom_size.unwrap_or(Vec2::ONE);
+        if transform.scale.xy() != size {
+            transform.scale = size.extend(1.0);
+        }
+
+        let Some(material) = materials.get_mut(handle) else {
+            continue;
+        };
+
+        material.shift = intensity.doppler(shift.0);
+        material.flip_x = sprite.flip_x;
+        material.flash = flash.map_or(0.0, HitFlash::intensity);
+        material.uv_rect = sprite
+            .texture_atlas
+            .as_ref()
+            .and_then(|atlas| {
+                let layout = layouts.get(&atlas.layout)?;
+                let rect = layout.textures.get(atlas.index)?.as_rect();
+                Some(Rect::from_corners(
+                    rect.min / layout.size.as_vec2(),
+                    rect.max / layout.size.as_vec2(),
+                ))
+            })
+            .unwrap_or(Rect::new(0.0, 0.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,75 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{PausableSystems, controller::CharacterController};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        apply_gravity_wells
+            .before(PhysicsSystems::StepSimulation)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Attracts dynamic bodies and character controllers within [`radius`] with an inverse-square
+/// falloff.
+///
+/// [`radius`]: Self::radius
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct GravityWell {
+    /// The acceleration felt at a distance of one unit.
+    pub strength: f32,
+    /// Bodies further away than this are unaffected.
+    pub radius: f32,
+}
+
+impl GravityWell {
+    /// Distances are clamped to at least this, to avoid flinging bodies that pass through the
+    /// center.
+    const MIN_DISTANCE: f32 = 0.5;
+
+    /// Returns the acceleration towards the well for a body at `offset` from its center.
+    pub fn acceleration(&self, offset: Vec2) -> Vec2 {
+        let distance = offset.length();
+        if distance >= self.radius || distance == 0.0 {
+            return Vec2::ZERO;
+        }
+
+        let falloff = distance.max(Self::MIN_DISTANCE).powi(2);
+        -offset / distance * self.strength / falloff
+    }
+}
+
+fn apply_gravity_wells(
+    time: Res<Time>,
+    wells: Query<(&GravityWell, &GlobalTransform)>,
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &Position,
+            &mut LinearVelocity,
+            Option<&GravityScale>,
+            Has<CharacterController>,
+        ),
+        Without<GravityWell>,
+    >,
+) {
+    let dt = time.delta_secs();
+    for (rigid_body, position, mut velocity, gravity_scale, is_character) in &mut bodies {
+        if !rigid_body.is_dynamic() && !is_character {
+            continue;
+        }
+
+        let scale = gravity_scale.map_or(1.0, |scale| scale.0);
+        let acceleration = wells
+            .iter()
+            .map(|(well, transform)| well.acceleration(position.0 - transform.translation().xy()))
+            .sum::<Vec2>();
+
+        if acceleration != Vec2::ZERO {
+            velocity.0 += scale * acceleration * dt;
+        }
+    }
+}
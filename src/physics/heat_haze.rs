@@ -0,0 +1,164 @@
+use avian2d::prelude::LinearVelocity;
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, encase::private::ShaderType},
+    sprite_render::{AlphaMode2d, Material2d, Material2dPlugin},
+};
+
+use crate::{
+    PausableSystems,
+    gameplay::player::Player,
+    physics::{EffectsIntensity, LocalSpeedOfLight, beta},
+};
+
+/// The relative speed, as a fraction of the speed of light, at which the haze starts to show.
+const HAZE_MIN_BETA: f32 = 0.3;
+/// The relative speed at which the haze is at full strength.
+const HAZE_MAX_BETA: f32 = 0.9;
+/// How much larger than the sprite the haze is along the direction of travel, at full strength.
+const HAZE_STRETCH: f32 = 2.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<HeatHazeMaterial>::default())
+        .init_resource::<HeatHazeAssets>()
+        .add_observer(spawn_heat_haze_mesh)
+        .add_systems(Update, update_heat_haze.in_set(PausableSystems))
+        .add_systems(PostUpdate, sync_heat_haze_meshes);
+}
+
+/// Draws a shimmer around an entity that is moving close to the speed of light relative to the
+/// player, smeared out along its direction of travel.
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct HeatHaze {
+    /// How visible the haze is, from `0.0` to `1.0`.
+    pub strength: f32,
+    /// The direction of travel relative to the player.
+    pub direction: Vec2,
+}
+
+/// The mesh entity that draws a [`HeatHaze`].
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HeatHazeMesh(Entity);
+
+#[derive(AsBindGroup, Asset, Reflect, Clone, Default)]
+#[uniform(0, HeatHazeUniforms)]
+pub struct HeatHazeMaterial {
+    /// See [`HeatHaze::strength`].
+    pub strength: f32,
+}
+
+impl Material2d for HeatHazeMaterial {
+    fn fragment_shader() -> bevy::shader::ShaderRef {
+        "shaders/heat_haze.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+#[derive(ShaderType)]
+#[repr(C)]
+struct HeatHazeUniforms {
+    strength: f32,
+    // Maintain 16-byte alignment for WASM targets
+    _pad: Vec3,
+}
+
+impl From<&HeatHazeMaterial> for HeatHazeUniforms {
+    fn from(value: &HeatHazeMaterial) -> Self {
+        Self {
+            strength: value.strength,
+            _pad: Vec3::ZERO,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct HeatHazeAssets {
+    mesh: Handle<Mesh>,
+}
+
+impl FromWorld for HeatHazeAssets {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            mesh: world
+                .resource_mut::<Assets<Mesh>>()
+                .add(Rectangle::from_size(Vec2::ONE)),
+        }
+    }
+}
+
+/// Returns the strength of the haze around something moving at `beta` relative to the player.
+pub fn haze_strength(beta: f32) -> f32 {
+    ((beta - HAZE_MIN_BETA) / (HAZE_MAX_BETA - HAZE_MIN_BETA)).clamp(0.0, 1.0)
+}
+
+fn update_heat_haze(
+    c: Res<LocalSpeedOfLight>,
+    player: Single<&LinearVelocity, With<Player>>,
+    mut sources: Query<(&LinearVelocity, &mut HeatHaze), Without<Player>>,
+) {
+    for (velocity, mut haze) in &mut sources {
+        let relative = velocity.0 - player.0;
+        let b = beta(relative.length(), c.0);
+        haze.strength = haze_strength(b);
+        haze.direction = relative.normalize_or(haze.direction);
+    }
+}
+
+fn spawn_heat_haze_mesh(
+    add: On<Add, HeatHaze>,
+    assets: Res<HeatHazeAssets>,
+    mut materials: ResMut<Assets<HeatHazeMaterial>>,
+    mut commands: Commands,
+) {
+    let mesh = commands
+        .spawn((
+            Name::new("Heat Haze"),
+            ChildOf(add.entity),
+            // Behind the sprite, so it only shows around the edges.
+            Transform::from_xyz(0.0, 0.0, -0.1),
+            Visibility::Hidden,
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(materials.add(HeatHazeMaterial::default())),
+        ))
+        .id();
+    commands.entity(add.entity).insert(HeatHazeMesh(mesh));
+}
+
+fn sync_heat_haze_meshes(
+    sources: Query<(&HeatHaze, &HeatHazeMesh, Option<&Sprite>)>,
+    mut meshes: Query<(
+        &MeshMaterial2d<HeatHazeMaterial>,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    mut materials: ResMut<Assets<HeatHazeMaterial>>,
+    intensity: Res<EffectsIntensity>,
+) {
+    for (haze, mesh, sprite) in &sources {
+        let Ok((handle, mut transform, mut visibility)) = meshes.get_mut(mesh.0) else {
+            continue;
+        };
+
+        let strength = haze.strength * intensity.0;
+        if strength <= 0.0 {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+
+        // Stretch along the direction of travel, and pad a little across it.
+        let size = sprite.and_then(|s| s.custom_size).unwrap_or(Vec2::ONE);
+        let extent = size.max_element();
+        transform.rotation = Quat::from_rotation_z(haze.direction.to_angle());
+        transform.scale = Vec3::new(extent * (1.0 + HAZE_STRETCH * strength), extent * 1.25, 1.0);
+
+        if let Some(material) = materials.get_mut(handle) {
+            material.strength = strength;
+        }
+    }
+}
@@ -8,6 +8,12 @@ pub enum GamePhysicsLayers {
     LevelGeometry,
     Player,
     Enemy,
+    Npc,
+    PlayerProjectile,
+    EnemyProjectile,
+    Item,
+    Hazard,
+    Trigger,
 }
 
 impl BitOr for GamePhysicsLayers {
@@ -23,18 +29,76 @@ pub trait GamePhysicsLayersExt {
     fn level_geometry() -> Self;
     fn player() -> Self;
     fn enemy() -> Self;
+    fn enemy_hitbox() -> Self;
+    fn corpse() -> Self;
+    fn npc() -> Self;
+    fn player_projectile() -> Self;
+    fn enemy_projectile() -> Self;
+    fn item() -> Self;
+    fn hazard() -> Self;
+    fn trigger() -> Self;
 }
 
 impl GamePhysicsLayersExt for CollisionLayers {
     fn level_geometry() -> Self {
-        CollisionLayers::new(LevelGeometry, Player | Enemy)
+        CollisionLayers::new(
+            LevelGeometry,
+            // Includes itself so that dynamic props rest on the terrain.
+            [
+                LevelGeometry,
+                Player,
+                Enemy,
+                Npc,
+                PlayerProjectile,
+                EnemyProjectile,
+                Item,
+            ],
+        )
     }
 
     fn player() -> Self {
-        CollisionLayers::new(Player, LevelGeometry | Enemy)
+        CollisionLayers::new(
+            Player,
+            [LevelGeometry, Enemy, EnemyProjectile, Hazard, Trigger],
+        )
     }
 
     fn enemy() -> Self {
-        CollisionLayers::new(Enemy, LevelGeometry | Player)
+        CollisionLayers::new(Enemy, [LevelGeometry, Player, PlayerProjectile, Hazard])
+    }
+
+    /// Enemy attacks only reach the player, so they don't stop projectiles or get stood on.
+    fn enemy_hitbox() -> Self {
+        CollisionLayers::new(Enemy, Player)
+    }
+
+    /// Dead enemies only rest on the terrain, so nothing can hit or trip over them.
+    fn corpse() -> Self {
+        CollisionLayers::new(Enemy, LevelGeometry)
+    }
+
+    fn npc() -> Self {
+        CollisionLayers::new(Npc, LevelGeometry)
+    }
+
+    fn player_projectile() -> Self {
+        CollisionLayers::new(PlayerProjectile, LevelGeometry | Enemy)
+    }
+
+    fn enemy_projectile() -> Self {
+        CollisionLayers::new(EnemyProjectile, LevelGeometry | Player)
+    }
+
+    fn item() -> Self {
+        CollisionLayers::new(Item, LevelGeometry)
+    }
+
+    /// Hazards are kept off the [`LevelGeometry`] layer so ground checks don't stand on them.
+    fn hazard() -> Self {
+        CollisionLayers::new(Hazard, Player | Enemy)
+    }
+
+    fn trigger() -> Self {
+        CollisionLayers::new(Trigger, Player)
     }
 }
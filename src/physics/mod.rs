@@ -1,34 +1,106 @@
+use std::time::Duration;
+
 use avian2d::{
     PhysicsPlugins,
     physics_transform::PhysicsTransformSystems,
-    prelude::{LinearVelocity, PhysicsSystems},
+    prelude::{
+        Gravity, LinearVelocity, PhysicsSchedule, PhysicsStepSystems, PhysicsSystems, Position,
+    },
 };
 use bevy::{camera::ScalingMode, prelude::*, window::PrimaryWindow};
 
 use crate::{
-    controller::CharacterController,
-    demo::{
+    AppSystems, PausableSystems,
+    config::GameConfig,
+    controller::{CharacterController, GroundVelocity},
+    gameplay::{
+        camera::{CameraRig, PlayerCamera},
         level::LevelGeometry,
-        player::{Player, PlayerCamera},
+        player::Player,
     },
+    math::exp_smooth,
+    units::TilesPerSecondSquared,
 };
 
+// Without a renderer, only the components that drive these effects are used.
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+mod doppler;
+mod gravity_well;
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+mod heat_haze;
 mod layers;
+mod moving_platform;
+mod one_way;
+mod relativity;
+#[cfg(feature = "render")]
+mod speed_vignette;
+mod water;
+mod zero_gravity;
 
+pub use doppler::*;
+pub use gravity_well::*;
+pub use heat_haze::*;
 pub use layers::*;
+pub use moving_platform::*;
+pub use one_way::*;
+pub use relativity::*;
+pub use water::*;
+pub use zero_gravity::*;
+
+/// How quickly Lorentz factors ease towards their targets, per second.
+const LORENTZ_SMOOTHING_RATE: f32 = 4.0;
+/// The player's Lorentz factor at which the music is at its most intense.
+const MUSIC_INTENSITY_MAX_GAMMA: f32 = 3.0;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(PhysicsPlugins::default())
-        .insert_resource(SpeedOfLight(25.0));
+    let speed_of_light = app.world().resource::<GameConfig>().speed_of_light;
+    app.add_plugins((
+        PhysicsPlugins::default().with_collision_hooks::<OneWayPlatformHooks>(),
+        #[cfg(feature = "render")]
+        doppler::plugin,
+        #[cfg(feature = "render")]
+        heat_haze::plugin,
+        #[cfg(feature = "render")]
+        speed_vignette::plugin,
+        gravity_well::plugin,
+        moving_platform::plugin,
+    ))
+    .insert_resource(SpeedOfLight(speed_of_light))
+    .insert_resource(LocalSpeedOfLight(speed_of_light))
+    .init_resource::<RelativityConfig>()
+    .init_resource::<EffectsIntensity>()
+    .init_resource::<MusicIntensity>()
+    .init_resource::<LevelPhysics>();
 
     app.add_systems(
         FixedPostUpdate,
         (
             (update_level_length_contraction, update_length_contraction)
                 .before(PhysicsTransformSystems::Propagate),
-            update_lorentz_factors.in_set(PhysicsSystems::StepSimulation),
+            (update_local_speed_of_light, update_lorentz_factors)
+                .chain()
+                .in_set(PhysicsSystems::StepSimulation),
         ),
     );
+    app.add_observer(snap_lorentz_factors);
+
+    app.add_systems(
+        PhysicsSchedule,
+        enforce_speed_of_light.in_set(PhysicsStepSystems::First),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            update_proper_time.in_set(AppSystems::TickTimers),
+            update_music_intensity,
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        PreUpdate,
+        apply_level_physics.run_if(resource_changed::<LevelPhysics>),
+    );
 }
 
 // TODO: Either refactor this to actually just be player vs level geometry using resources, or
@@ -45,6 +117,157 @@ impl Default for SpeedOfLight {
     }
 }
 
+/// The speed of light where the player currently is, taking [`SpeedOfLightZone`]s into account.
+///
+/// Systems that care about the speed of light from the player's point of view should read this
+/// rather than [`SpeedOfLight`]. Whenever it changes, [`SpeedOfLightChanged`] is triggered.
+#[derive(Resource, Reflect, Deref, Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[reflect(Resource)]
+pub struct LocalSpeedOfLight(pub f32);
+
+/// Triggered when the [`LocalSpeedOfLight`] changes, either because [`SpeedOfLight`] itself was
+/// changed or because the player crossed into a different [`SpeedOfLightZone`].
+///
+/// Systems that cache anything derived from the speed of light should observe this to update
+/// immediately, rather than easing towards the new value.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpeedOfLightChanged {
+    pub previous: f32,
+    pub current: f32,
+}
+
+/// Keeps an entity's speed below the local [`SpeedOfLight`].
+///
+/// The Lorentz factor math clamps speeds on its own, so this isn't needed for correctness, but
+/// without it an entity can move faster than it appears to. Remove this to let an entity break
+/// the limit.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct SpeedOfLightLimit;
+
+/// Physics settings that a level can override, such as low gravity on a moon or heavy damping
+/// underwater.
+///
+/// The active values are kept in a resource while a level is loaded. Gravity is applied through
+/// avian's [`Gravity`], and controllers scale their damping by [`damping_scale`].
+///
+/// [`damping_scale`]: Self::damping_scale
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct LevelPhysics {
+    pub gravity: Vec2,
+    pub damping_scale: f32,
+}
+
+impl Default for LevelPhysics {
+    fn default() -> Self {
+        Self {
+            gravity: Vec2::NEG_Y * TilesPerSecondSquared(9.81).to_world(),
+            damping_scale: 1.0,
+        }
+    }
+}
+
+/// Overrides the global [`SpeedOfLight`] while the player is inside the zone.
+///
+/// The zone is a rectangle of the given size centered on the entity. When zones overlap, the
+/// smallest one containing the player wins, so rooms can be nested inside larger regions.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct SpeedOfLightZone {
+    pub speed_of_light: f32,
+    pub size: Vec2,
+}
+
+impl SpeedOfLightZone {
+    fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        Rect::from_center_size(center, self.size).contains(point)
+    }
+}
+
+/// Returns the speed of light at `point`, taking any [`SpeedOfLightZone`]s into account.
+pub fn effective_speed_of_light(
+    global: &SpeedOfLight,
+    zones: &Query<(&SpeedOfLightZone, &GlobalTransform)>,
+    point: Vec2,
+) -> f32 {
+    zones
+        .iter()
+        .filter(|(zone, transform)| zone.contains(transform.translation().xy(), point))
+        .min_by(|(a, _), (b, _)| {
+            a.size
+                .element_product()
+                .total_cmp(&b.size.element_product())
+        })
+        .map_or(global.0, |(zone, _)| zone.speed_of_light)
+}
+
+/// Global options for how relativistic effects are modeled.
+#[derive(Resource, Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub struct RelativityConfig {
+    pub velocity_composition: VelocityComposition,
+}
+
+/// How strongly relativistic effects are drawn, from `0.0` for not at all to `1.0` for
+/// physically accurate. Some players find the warping disorienting.
+///
+/// This scales length contraction, Doppler shifts, heat haze and the speed vignette where they
+/// are drawn, and the pitch shifts of sounds. Velocities and proper time are unaffected, but
+/// length contraction also scales colliders, so turning it down makes fast bodies collide closer
+/// to their rest size.
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct EffectsIntensity(pub f32);
+
+impl Default for EffectsIntensity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl EffectsIntensity {
+    /// Scales per-axis Lorentz factors towards `1.0`.
+    pub fn gamma(self, gamma: Vec2) -> Vec2 {
+        Vec2::ONE.lerp(gamma, self.0)
+    }
+
+    /// Scales a Doppler factor towards `1.0`, evenly for red and blue shifts.
+    #[cfg_attr(not(any(feature = "render", feature = "audio")), allow(dead_code))]
+    pub fn doppler(self, shift: f32) -> f32 {
+        shift.powf(self.0)
+    }
+}
+
+/// How intense the music should be, from `0.0` with the player at rest to `1.0` once their Lorentz
+/// factor relative to the level reaches [`MUSIC_INTENSITY_MAX_GAMMA`].
+#[derive(Resource, Reflect, Default, Deref, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct MusicIntensity(pub f32);
+
+/// How velocities are combined when computing an entity's speed relative to the player.
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VelocityComposition {
+    /// Velocities are simply subtracted. Cheap, but relative speeds can exceed [`SpeedOfLight`].
+    #[default]
+    Galilean,
+    /// Velocities are composed with relativistic velocity addition, so relative speeds never
+    /// exceed [`SpeedOfLight`].
+    Relativistic,
+}
+
+impl VelocityComposition {
+    /// Returns the velocity of an object moving at `target` as observed from a frame moving at
+    /// `observer`. Both velocities are measured in the level's frame.
+    fn relative_velocity(self, observer: Vec2, target: Vec2, c: f32) -> Vec2 {
+        match self {
+            Self::Galilean => target - observer,
+            // Boost into the observer's frame, i.e. compose `-observer` with `target`.
+            Self::Relativistic => rel_add(-observer, target, c),
+        }
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct LorentzFactor(pub Vec2);
 
@@ -54,21 +277,90 @@ impl Default for LorentzFactor {
     }
 }
 
-fn gamma(s: f32, c: f32) -> f32 {
-    let b = s.abs().min(c * 0.999) / c;
-    1.0 / (1.0 - b * b).sqrt()
+impl LorentzFactor {
+    /// Combines the per-axis factors into the factor for the total relative speed.
+    pub fn scalar(&self) -> f32 {
+        let beta_sq = (Vec2::ONE - 1.0 / (self.0 * self.0)).element_sum();
+        1.0 / (1.0 - beta_sq.min(MAX_BETA * MAX_BETA)).sqrt()
+    }
+}
+
+/// An entity's local clock, which runs slower the faster it moves relative to the player.
+///
+/// Systems that advance per-entity state (animations, timers, AI) should use [`delta`] in place of
+/// [`Time::delta`] for entities that have this component.
+///
+/// [`delta`]: Self::delta
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(LorentzFactor)]
+pub struct ProperTime {
+    rate: f32,
+    delta: Duration,
+}
+
+impl Default for ProperTime {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            delta: Duration::ZERO,
+        }
+    }
+}
+
+impl ProperTime {
+    /// How fast this clock runs relative to the player's clock, between `0.0` and `1.0`.
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// How much proper time advanced since the last update.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+
+fn update_local_speed_of_light(
+    global_c: Res<SpeedOfLight>,
+    zones: Query<(&SpeedOfLightZone, &GlobalTransform)>,
+    player: Single<&Position, With<Player>>,
+    mut local_c: ResMut<LocalSpeedOfLight>,
+    mut commands: Commands,
+) {
+    let c = effective_speed_of_light(&global_c, &zones, player.0);
+    if c != local_c.0 {
+        commands.trigger(SpeedOfLightChanged {
+            previous: local_c.0,
+            current: c,
+        });
+        local_c.0 = c;
+    }
+}
+
+/// Returns the per-axis Lorentz factors of an object moving at `target_vel`, as seen by the
+/// player.
+fn target_lorentz_factor(
+    config: &RelativityConfig,
+    player_vel: Vec2,
+    target_vel: Vec2,
+    c: f32,
+) -> Vec2 {
+    let v = config
+        .velocity_composition
+        .relative_velocity(player_vel, target_vel, c);
+    Vec2::new(gamma(v.x, c), gamma(v.y, c))
 }
 
 fn update_lorentz_factors(
     time: Res<Time>,
-    c: Res<SpeedOfLight>,
-    player_vel: Single<&LinearVelocity, With<Player>>,
+    c: Res<LocalSpeedOfLight>,
+    config: Res<RelativityConfig>,
+    player: Single<&LinearVelocity, With<Player>>,
     mut velocities: Query<(&LinearVelocity, &mut LorentzFactor)>,
 ) {
     for (target_vel, mut lorentz) in &mut velocities {
-        let v = player_vel.0 - target_vel.0;
-        let g = Vec2::new(gamma(v.x, c.0), gamma(v.y, c.0));
-        lorentz.0 = lorentz.0.lerp(g, (4.0 * time.delta_secs()).min(1.0));
+        let g = target_lorentz_factor(&config, player.0, target_vel.0, c.0);
+        lorentz.0 = exp_smooth(lorentz.0, g, LORENTZ_SMOOTHING_RATE, time.delta_secs());
 
         let should_round = (lorentz.0 - 1.0).cmplt(Vec2::splat(0.001));
         if should_round.y {
@@ -80,23 +372,85 @@ fn update_lorentz_factors(
     }
 }
 
+/// Lorentz factors are normally eased to smooth out jittery velocities, but a change in the speed
+/// of light should take effect at once rather than looking like an acceleration.
+fn snap_lorentz_factors(
+    change: On<SpeedOfLightChanged>,
+    config: Res<RelativityConfig>,
+    player: Single<&LinearVelocity, With<Player>>,
+    mut velocities: Query<(&LinearVelocity, &mut LorentzFactor)>,
+) {
+    debug!(
+        "Local speed of light changed from {} to {}",
+        change.previous, change.current
+    );
+    for (target_vel, mut lorentz) in &mut velocities {
+        lorentz.0 = target_lorentz_factor(&config, player.0, target_vel.0, change.current);
+    }
+}
+
+fn enforce_speed_of_light(
+    global_c: Res<SpeedOfLight>,
+    zones: Query<(&SpeedOfLightZone, &GlobalTransform)>,
+    mut bodies: Query<
+        (&Position, &mut LinearVelocity, Option<&GroundVelocity>),
+        With<SpeedOfLightLimit>,
+    >,
+) {
+    for (position, mut velocity, ground_velocity) in &mut bodies {
+        let max_speed = MAX_BETA * effective_speed_of_light(&global_c, &zones, position.0);
+
+        // Kinematic characters are carried by the ground on top of their own velocity, so limit
+        // the sum rather than just their part of it.
+        let carried = ground_velocity.map_or(Vec2::ZERO, |v| **v);
+        let total = velocity.0 + carried;
+        if total.length_squared() > max_speed * max_speed {
+            velocity.0 = total.clamp_length_max(max_speed) - carried;
+        }
+    }
+}
+
+fn apply_level_physics(physics: Res<LevelPhysics>, mut gravity: ResMut<Gravity>) {
+    gravity.0 = physics.gravity;
+}
+
+fn update_proper_time(time: Res<Time>, mut clocks: Query<(&LorentzFactor, &mut ProperTime)>) {
+    for (lorentz, mut proper_time) in &mut clocks {
+        proper_time.rate = 1.0 / lorentz.scalar();
+        proper_time.delta = time.delta().mul_f32(proper_time.rate);
+    }
+}
+
+fn update_music_intensity(
+    level: Single<&LorentzFactor, With<LevelGeometry>>,
+    mut intensity: ResMut<MusicIntensity>,
+) {
+    // Eased along with the Lorentz factor itself.
+    let gamma = level.scalar();
+    intensity.0 = ((gamma - 1.0) / (MUSIC_INTENSITY_MAX_GAMMA - 1.0)).clamp(0.0, 1.0);
+}
+
 fn update_level_length_contraction(
     gamma: Single<&LorentzFactor, With<LevelGeometry>>,
+    intensity: Res<EffectsIntensity>,
     window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<&mut Projection, With<PlayerCamera>>,
+    camera: Single<(&mut Projection, &CameraRig), With<PlayerCamera>>,
     mut player: Single<(&mut Transform, &mut CharacterController), With<Player>>,
 ) {
-    let Projection::Orthographic(proj) = &mut *camera.into_inner() else {
+    let (mut projection, rig) = camera.into_inner();
+    let Projection::Orthographic(proj) = &mut *projection else {
         return;
     };
 
-    let window_size = window.size() * gamma.0;
+    // Contracted along with the level, on top of any zoom from the camera's zone.
+    let gamma = intensity.gamma(gamma.0);
+    let window_size = window.size() * gamma * rig.zoom();
     proj.scaling_mode = ScalingMode::Fixed {
         width: window_size.x,
         height: window_size.y,
     };
 
-    player.0.scale = gamma.0.extend(player.0.scale.z);
+    player.0.scale = gamma.extend(player.0.scale.z);
     // player.1.max_speed = 20. * gamma.0.x;
     // player.1.accel_air = 3.5 * gamma.0.x.sqrt();
     // player.1.accel_ground = 35. * gamma.0.x.sqrt();
@@ -105,9 +459,64 @@ fn update_level_length_contraction(
 }
 
 fn update_length_contraction(
+    intensity: Res<EffectsIntensity>,
     mut transforms: Query<(&LorentzFactor, &mut Transform), Without<LevelGeometry>>,
 ) {
     for (gamma, mut local) in &mut transforms {
-        local.scale = (1.0 / gamma.0).extend(local.scale.z);
+        local.scale = (1.0 / intensity.gamma(gamma.0)).extend(local.scale.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const C: f32 = 1.0;
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{a} != {b}");
+    }
+
+    #[test]
+    fn galilean_subtracts() {
+        let v = VelocityComposition::Galilean.relative_velocity(
+            Vec2::new(-0.5, 0.0),
+            Vec2::new(0.5, 0.0),
+            C,
+        );
+        assert_close(v, Vec2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn relativistic_collinear() {
+        let rel = |observer: f32, target: f32| {
+            VelocityComposition::Relativistic
+                .relative_velocity(Vec2::new(observer, 0.0), Vec2::new(target, 0.0), C)
+                .x
+        };
+
+        // (u + v) / (1 + uv / c^2)
+        assert!((rel(-0.5, 0.5) - 0.8).abs() < 1e-5);
+        assert!((rel(-0.9, 0.9) - 1.8 / 1.81).abs() < 1e-5);
+        assert!((rel(0.5, 0.5)).abs() < 1e-5);
+        assert!(rel(-0.99, 0.99) < C);
+    }
+
+    #[test]
+    fn relativistic_perpendicular() {
+        // An object moving perpendicular to the observer's motion is slowed by `1 / gamma`.
+        let v = VelocityComposition::Relativistic.relative_velocity(
+            Vec2::new(0.6, 0.0),
+            Vec2::new(0.0, 0.5),
+            C,
+        );
+        assert_close(v, Vec2::new(-0.6, 0.4));
+    }
+
+    #[test]
+    fn relativistic_at_rest_matches_galilean() {
+        let observer = Vec2::new(0.3, -0.2);
+        let v = VelocityComposition::Relativistic.relative_velocity(observer, Vec2::ZERO, C);
+        assert_close(v, -observer);
     }
 }
@@ -0,0 +1,81 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::PausableSystems;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        move_platforms
+            .before(PhysicsSystems::StepSimulation)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A kinematic platform that loops through a list of waypoints.
+///
+/// Platforms are moved by setting their [`LinearVelocity`], so characters standing on them can
+/// be carried along by the controller.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(RigidBody::Kinematic)]
+pub struct MovingPlatform {
+    /// Points the platform's center travels between, in order. After the last waypoint it returns
+    /// to the first.
+    pub waypoints: Vec<Vec2>,
+    /// The average speed along each segment, in units per second.
+    pub speed: f32,
+    /// The easing applied to each segment, e.g. to slow down into each stop.
+    pub easing: EaseFunction,
+    segment: usize,
+    progress: f32,
+}
+
+impl MovingPlatform {
+    pub fn new(waypoints: Vec<Vec2>, speed: f32, easing: EaseFunction) -> Self {
+        Self {
+            waypoints,
+            speed,
+            easing,
+            segment: 0,
+            progress: 0.0,
+        }
+    }
+
+    /// Advances the platform by `dt` seconds and returns where it should now be.
+    fn advance(&mut self, dt: f32) -> Option<Vec2> {
+        let n = self.waypoints.len();
+        if n < 2 {
+            return self.waypoints.first().copied();
+        }
+
+        let start = self.waypoints[self.segment % n];
+        let end = self.waypoints[(self.segment + 1) % n];
+        let length = start.distance(end).max(f32::EPSILON);
+
+        self.progress += self.speed * dt / length;
+        if self.progress >= 1.0 {
+            self.segment = (self.segment + 1) % n;
+            self.progress = 0.0;
+            return Some(end);
+        }
+
+        Some(start.lerp(end, self.easing.sample_clamped(self.progress)))
+    }
+}
+
+fn move_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(&mut MovingPlatform, &Position, &mut LinearVelocity)>,
+) {
+    let dt = time.delta_secs();
+    if dt == 0.0 {
+        return;
+    }
+
+    for (mut platform, position, mut velocity) in &mut platforms {
+        velocity.0 = platform
+            .advance(dt)
+            .map_or(Vec2::ZERO, |target| (target - position.0) / dt);
+    }
+}
@@ -0,0 +1,53 @@
+use avian2d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// The minimum upward component of a contact normal for it to count as landing on top of a
+/// [`OneWayPlatform`].
+const MIN_LANDING_NORMAL_Y: f32 = 0.5;
+
+/// A platform that can only be collided with from above.
+///
+/// Bodies pass through it when moving upward, and characters can drop through it on purpose.
+/// Dynamic bodies are filtered by [`OneWayPlatformHooks`], so platforms need
+/// [`ActiveCollisionHooks::MODIFY_CONTACTS`].
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+#[require(ActiveCollisionHooks = ActiveCollisionHooks::MODIFY_CONTACTS)]
+pub struct OneWayPlatform;
+
+impl OneWayPlatform {
+    /// Returns `true` if a body moving at `velocity` should collide with a platform surface with
+    /// the given `normal` (pointing out of the platform).
+    pub fn should_collide(normal: Vec2, velocity: Vec2) -> bool {
+        normal.y >= MIN_LANDING_NORMAL_Y && velocity.y <= 0.0
+    }
+}
+
+/// Collision hooks that let dynamic bodies pass through [`OneWayPlatform`]s from below.
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatform>>,
+    velocities: Query<'w, 's, &'static LinearVelocity>,
+}
+
+impl CollisionHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_contacts(&self, contacts: &mut ContactPair, _commands: &mut Commands) -> bool {
+        // Normals point from the first collider to the second.
+        let (other_body, sign) = if self.platforms.contains(contacts.collider1) {
+            (contacts.body2, 1.0)
+        } else if self.platforms.contains(contacts.collider2) {
+            (contacts.body1, -1.0)
+        } else {
+            return true;
+        };
+
+        let velocity = other_body
+            .and_then(|body| self.velocities.get(body).ok())
+            .map_or(Vec2::ZERO, |v| v.0);
+
+        contacts
+            .manifolds
+            .iter()
+            .all(|manifold| OneWayPlatform::should_collide(sign * manifold.normal, velocity))
+    }
+}
@@ -0,0 +1,100 @@
+//! Special relativity helpers shared by systems that compose or compare velocities.
+//!
+//! All speeds are clamped to [`MAX_BETA`] times the speed of light, so these never return
+//! infinities or NaNs for bodies that happen to reach or exceed `c`.
+
+use bevy::prelude::*;
+
+/// The fastest fraction of [`SpeedOfLight`] used in relativistic calculations.
+///
+/// [`SpeedOfLight`]: super::SpeedOfLight
+pub const MAX_BETA: f32 = 0.999;
+
+/// Returns `speed` as a fraction of `c`, clamped to `±MAX_BETA`.
+pub fn beta(speed: f32, c: f32) -> f32 {
+    (speed / c).clamp(-MAX_BETA, MAX_BETA)
+}
+
+/// Returns the Lorentz factor of an object moving at `speed`.
+pub fn gamma(speed: f32, c: f32) -> f32 {
+    let b = beta(speed, c);
+    1.0 / (1.0 - b * b).sqrt()
+}
+
+/// Returns how close a Lorentz factor is to the largest one allowed by [`MAX_BETA`], from `0.0` at
+/// rest to `1.0` at the clamp.
+///
+/// This is logarithmic in `gamma`, so it keeps rising noticeably all the way up to the clamp
+/// rather than only in the last sliver of speed.
+pub fn lightspeed_proximity(gamma_factor: f32) -> f32 {
+    let max_gamma = gamma(MAX_BETA, 1.0);
+    (gamma_factor.max(1.0).ln() / max_gamma.ln()).min(1.0)
+}
+
+/// Relativistic velocity addition.
+///
+/// Returns the velocity of an object moving at `v` within a frame that itself moves at `u`, as
+/// seen from the frame `u` is measured in. The result is always slower than `c`.
+pub fn rel_add(u: Vec2, v: Vec2, c: f32) -> Vec2 {
+    let u = u.clamp_length_max(c * MAX_BETA);
+    let v = v.clamp_length_max(c * MAX_BETA);
+    let c2 = c * c;
+    let gamma_u = gamma(u.length(), c);
+    let uv = u.dot(v);
+    (u + v / gamma_u + (gamma_u / (1.0 + gamma_u)) * (uv / c2) * u) / (1.0 + uv / c2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const C: f32 = 10.0;
+
+    #[test]
+    fn beta_is_clamped() {
+        assert_eq!(beta(5.0, C), 0.5);
+        assert_eq!(beta(-5.0, C), -0.5);
+        assert_eq!(beta(20.0, C), MAX_BETA);
+        assert_eq!(beta(-20.0, C), -MAX_BETA);
+    }
+
+    #[test]
+    fn gamma_values() {
+        assert_eq!(gamma(0.0, C), 1.0);
+        assert!((gamma(6.0, C) - 1.25).abs() < 1e-5);
+        assert_eq!(gamma(-6.0, C), gamma(6.0, C));
+        assert!(gamma(C, C).is_finite());
+    }
+
+    #[test]
+    fn rel_add_collinear() {
+        // (u + v) / (1 + uv / c^2)
+        let v = rel_add(Vec2::new(5.0, 0.0), Vec2::new(5.0, 0.0), C);
+        assert!(v.abs_diff_eq(Vec2::new(8.0, 0.0), 1e-4), "{v}");
+
+        let v = rel_add(Vec2::new(0.0, -9.0), Vec2::new(0.0, -9.0), C);
+        assert!(v.abs_diff_eq(Vec2::new(0.0, -18.0 / 1.81), 1e-4), "{v}");
+    }
+
+    #[test]
+    fn rel_add_never_exceeds_c() {
+        let v = rel_add(Vec2::new(9.9, 0.0), Vec2::new(9.9, 0.0), C);
+        assert!(v.length() < C);
+
+        let v = rel_add(Vec2::new(50.0, 0.0), Vec2::new(0.0, 50.0), C);
+        assert!(v.length() < C);
+    }
+
+    #[test]
+    fn rel_add_with_rest_frame_is_identity() {
+        let v = Vec2::new(3.0, -4.0);
+        assert!(rel_add(Vec2::ZERO, v, C).abs_diff_eq(v, 1e-5));
+        assert!(rel_add(v, Vec2::ZERO, C).abs_diff_eq(v, 1e-5));
+    }
+
+    #[test]
+    fn rel_add_inverse_cancels() {
+        let u = Vec2::new(6.0, 2.0);
+        assert!(rel_add(-u, u, C).abs_diff_eq(Vec2::ZERO, 1e-4));
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, encase::private::ShaderType},
+    ui_render::prelude::{MaterialNode, UiMaterial, UiMaterialPlugin},
+};
+
+use crate::{
+    PausableSystems,
+    gameplay::level::LevelGeometry,
+    physics::{EffectsIntensity, LorentzFactor, lightspeed_proximity},
+    screens::Screen,
+};
+
+/// How close to the clamp the player's Lorentz factor has to be before the vignette shows.
+const VIGNETTE_MIN_PROXIMITY: f32 = 0.15;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(UiMaterialPlugin::<SpeedVignetteMaterial>::default())
+        .add_systems(OnEnter(Screen::Gameplay), spawn_speed_vignette)
+        .add_systems(
+            Update,
+            update_speed_vignette
+                .run_if(in_state(Screen::Gameplay))
+                .in_set(PausableSystems),
+        );
+}
+
+/// Darkens the edges of the screen and splits them into colored fringes as the player's Lorentz
+/// factor approaches the clamp, so they can feel how close to `c` they are.
+#[derive(AsBindGroup, Asset, Reflect, Clone, Default)]
+#[uniform(0, SpeedVignetteUniforms)]
+pub struct SpeedVignetteMaterial {
+    /// How strong the vignette is, from `0.0` to `1.0`.
+    pub strength: f32,
+}
+
+impl UiMaterial for SpeedVignetteMaterial {
+    fn fragment_shader() -> bevy::shader::ShaderRef {
+        "shaders/speed_vignette.wgsl".into()
+    }
+}
+
+#[derive(ShaderType)]
+#[repr(C)]
+struct SpeedVignetteUniforms {
+    strength: f32,
+    // Maintain 16-byte alignment for WASM targets
+    _pad: Vec3,
+}
+
+impl From<&SpeedVignetteMaterial> for SpeedVignetteUniforms {
+    fn from(value: &SpeedVignetteMaterial) -> Self {
+        Self {
+            strength: value.strength,
+            _pad: Vec3::ZERO,
+        }
+    }
+}
+
+fn spawn_speed_vignette(
+    mut materials: ResMut<Assets<SpeedVignetteMaterial>>,
+    mut commands: Commands,
+) {
+    commands.spawn((
+        Name::new("Speed Vignette"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        MaterialNode(materials.add(SpeedVignetteMaterial::default())),
+        // Under the HUD and menus.
+        GlobalZIndex(-1),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_speed_vignette(
+    level: Single<&LorentzFactor, With<LevelGeometry>>,
+    intensity: Res<EffectsIntensity>,
+    vignette: Single<&MaterialNode<SpeedVignetteMaterial>>,
+    mut materials: ResMut<Assets<SpeedVignetteMaterial>>,
+) {
+    let proximity = lightspeed_proximity(level.scalar());
+    let strength = ((proximity - VIGNETTE_MIN_PROXIMITY) / (1.0 - VIGNETTE_MIN_PROXIMITY))
+        .clamp(0.0, 1.0)
+        * intensity.0;
+
+    // Only touched when it changes, so the uniform isn't re-uploaded every frame at rest.
+    if materials
+        .get(&vignette.0)
+        .is_some_and(|material| material.strength != strength)
+        && let Some(material) = materials.get_mut(&vignette.0)
+    {
+        material.strength = strength;
+    }
+}
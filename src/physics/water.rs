@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+/// A body of water that characters can swim in.
+///
+/// The volume is a rectangle of the given size centered on the entity. It has no collider;
+/// controllers check whether they're inside with [`contains`].
+///
+/// [`contains`]: Self::contains
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct WaterVolume {
+    pub size: Vec2,
+    /// The upward acceleration applied to anything submerged, in world units per second squared.
+    pub buoyancy: f32,
+}
+
+impl WaterVolume {
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        Rect::from_center_size(center, self.size).contains(point)
+    }
+}
+
+/// Triggered on a character when it enters or leaves a [`WaterVolume`].
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Splash {
+    #[event_target]
+    pub entity: Entity,
+    pub volume: Entity,
+    /// The character's position as it crossed the surface.
+    pub position: Vec2,
+    /// The character's velocity as it crossed the surface.
+    pub velocity: Vec2,
+    /// `true` when entering the water, `false` when leaving it.
+    pub entered: bool,
+}
@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+/// A region without gravity, where characters fly with thrusters instead of walking.
+///
+/// Like a [`SpeedOfLightZone`](super::SpeedOfLightZone), the zone is a rectangle of the given
+/// size centered on the entity, and controllers check whether they're inside with [`contains`].
+///
+/// [`contains`]: Self::contains
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct ZeroGravityZone {
+    pub size: Vec2,
+}
+
+impl ZeroGravityZone {
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        Rect::from_center_size(center, self.size).contains(point)
+    }
+}
@@ -0,0 +1,86 @@
+//! Progress that persists between play sessions.
+//!
+//! Anything stored in [`SaveData`] survives returning to the hub and restarting the game. State
+//! that should only last for a single trip into a level belongs on the level's entities instead,
+//! which are despawned when the run ends. Settings are kept separately, in
+//! [`Settings`](crate::settings::Settings).
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::gameplay::level::state::LevelEventLog;
+
+#[cfg(not(target_family = "wasm"))]
+const SAVE_PATH: &str = "save.json";
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SaveData::load());
+
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(
+        PostUpdate,
+        write_save.run_if(resource_changed::<SaveData>.and(not(resource_added::<SaveData>))),
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct SaveData {
+    pub hub: HubSave,
+    /// Settings saved before they moved to [`Settings`](crate::settings::Settings). They're only
+    /// read, to carry them over the first time settings are loaded, and dropped on the next save.
+    #[serde(rename = "settings", skip_serializing)]
+    pub legacy_settings: Option<LegacySettingsSave>,
+}
+
+/// The persistent state of the hub level.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct HubSave {
+    /// How far through their dialogue each hub NPC is, by name.
+    pub npc_dialogue: HashMap<String, usize>,
+    /// What has happened in the hub, replayed whenever it is spawned.
+    pub events: LevelEventLog,
+}
+
+/// Settings as they used to be saved in [`SaveData`].
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct LegacySettingsSave {
+    pub effects_intensity: Option<f32>,
+}
+
+impl SaveData {
+    #[cfg(not(target_family = "wasm"))]
+    fn load() -> Self {
+        let bytes = match std::fs::read(SAVE_PATH) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return default(),
+            Err(error) => {
+                warn!("Failed to read save file: {error}");
+                return default();
+            }
+        };
+
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            warn!("Failed to parse save file: {error}");
+            default()
+        })
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn load() -> Self {
+        default()
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_save(save: Res<SaveData>) {
+    let result = serde_json::to_vec_pretty(&*save)
+        .map_err(BevyError::from)
+        .and_then(|bytes| Ok(std::fs::write(SAVE_PATH, bytes)?));
+
+    if let Err(error) = result {
+        warn!("Failed to write save file: {error}");
+    }
+}
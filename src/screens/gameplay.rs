@@ -2,11 +2,16 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    audio::StopMusic,
+    gameplay::level::pickups::LevelStats,
+    input::Action,
+    menus::Menu,
+    screens::{Area, Screen},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
-
     // Toggle pause on key press.
     app.add_systems(
         Update,
@@ -14,22 +19,42 @@ pub(super) fn plugin(app: &mut App) {
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                    .and(input_just_pressed(Action::Pause).or(input_just_pressed(Action::Back))),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(input_just_pressed(Action::Pause)),
             ),
         ),
     );
-    app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
+    app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause, stop_music));
+    app.add_systems(OnExit(Area::Level), report_level_stats);
     app.add_systems(
         OnEnter(Menu::None),
         unpause.run_if(in_state(Screen::Gameplay)),
     );
 }
 
+fn report_level_stats(stats: Res<LevelStats>) {
+    info!(
+        "Level finished in {:.1}s with {} points and {}/{} items{}",
+        stats.elapsed_secs,
+        stats.score,
+        stats.items_collected,
+        stats.items_total,
+        if stats.all_items_collected() {
+            " (complete!)"
+        } else {
+            ""
+        },
+    );
+}
+
+fn stop_music(mut commands: Commands) {
+    commands.trigger(StopMusic);
+}
+
 fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(false));
 }
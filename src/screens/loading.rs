@@ -1,5 +1,8 @@
 //! A loading screen during which game assets are loaded if necessary.
 //! This reduces stuttering, especially for audio on Wasm.
+//!
+//! If an asset fails to load, such as a level with a missing layer, the game can't start and the
+//! loading screen shows the errors instead.
 
 use bevy::prelude::*;
 
@@ -10,7 +13,11 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        (
+            enter_gameplay_screen.run_if(all_assets_loaded),
+            show_load_failures.run_if(any_assets_failed),
+        )
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
@@ -18,10 +25,23 @@ fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
         DespawnOnExit(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![(widget::label("Loading..."), LoadingLabel)],
     ));
 }
 
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LoadingLabel;
+
+fn show_load_failures(
+    resource_handles: Res<ResourceHandles>,
+    mut label: Single<&mut Text, With<LoadingLabel>>,
+) {
+    let failures: Vec<_> = resource_handles.failures().collect();
+    let text = format!("Failed to load:\n{}", failures.join("\n"));
+    label.set_if_neq(Text(text));
+}
+
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Gameplay);
 }
@@ -29,3 +49,7 @@ fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
 fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
     resource_handles.is_all_done()
 }
+
+fn any_assets_failed(resource_handles: Res<ResourceHandles>) -> bool {
+    resource_handles.failures().next().is_some()
+}
@@ -9,6 +9,7 @@ use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_sub_state::<Area>();
 
     app.add_plugins((
         gameplay::plugin,
@@ -27,3 +28,14 @@ pub enum Screen {
     Loading,
     Gameplay,
 }
+
+/// Where the player is while in [`Screen::Gameplay`].
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(Screen = Screen::Gameplay)]
+pub enum Area {
+    /// The persistent hub level, from which other levels are entered.
+    #[default]
+    Hub,
+    /// A level entered through one of the hub's doors.
+    Level,
+}
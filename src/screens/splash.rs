@@ -1,4 +1,8 @@
-//! A splash screen that plays briefly at startup.
+//! A sequence of splash screen logos that plays at startup.
+//!
+//! The logos are listed in the [`GameConfig`], and each fades in and out in turn before the title
+//! screen. Pressing space, enter, or clicking skips to the end of the current logo, and pressing
+//! escape skips the whole sequence.
 
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
@@ -6,31 +10,36 @@ use bevy::{
     prelude::*,
 };
 
-use crate::{AppSystems, screens::Screen, theme::prelude::*};
+use crate::{
+    AppSystems,
+    config::{GameConfig, SplashLogo},
+    input::Action,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Spawn splash screen.
     app.insert_resource(ClearColor(SPLASH_BACKGROUND_COLOR));
-    app.add_systems(OnEnter(Screen::Splash), spawn_splash_screen);
+    app.add_systems(OnEnter(Screen::Splash), start_splash_sequence);
+    app.add_systems(OnExit(Screen::Splash), end_splash_sequence);
 
-    // Animate splash screen.
+    // Animate splash screen, and move through the sequence.
     app.add_systems(
         Update,
         (
             tick_fade_in_out.in_set(AppSystems::TickTimers),
-            apply_fade_in_out.in_set(AppSystems::Update),
-        )
-            .run_if(in_state(Screen::Splash)),
-    );
-
-    // Add splash timer.
-    app.add_systems(OnEnter(Screen::Splash), insert_splash_timer);
-    app.add_systems(OnExit(Screen::Splash), remove_splash_timer);
-    app.add_systems(
-        Update,
-        (
-            tick_splash_timer.in_set(AppSystems::TickTimers),
-            check_splash_timer.in_set(AppSystems::Update),
+            (
+                skip_splash_logo.run_if(
+                    input_just_pressed(KeyCode::Space)
+                        .or(input_just_pressed(KeyCode::Enter))
+                        .or(input_just_pressed(MouseButton::Left)),
+                ),
+                advance_splash_sequence,
+                apply_fade_in_out,
+            )
+                .chain()
+                .in_set(AppSystems::Update),
         )
             .run_if(in_state(Screen::Splash)),
     );
@@ -38,46 +47,81 @@ pub(super) fn plugin(app: &mut App) {
     // Exit the splash screen early if the player hits escape.
     app.add_systems(
         Update,
-        enter_title_screen
-            .run_if(input_just_pressed(KeyCode::Escape).and(in_state(Screen::Splash))),
+        enter_title_screen.run_if(input_just_pressed(Action::Back).and(in_state(Screen::Splash))),
     );
 }
 
 const SPLASH_BACKGROUND_COLOR: Color = srgb_hex("#282828");
-const SPLASH_DURATION_SECS: f32 = 1.8;
-const SPLASH_FADE_DURATION_SECS: f32 = 0.6;
 
-fn spawn_splash_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        widget::ui_root("Splash Screen"),
-        BackgroundColor(SPLASH_BACKGROUND_COLOR),
-        DespawnOnExit(Screen::Splash),
-        children![(
-            Name::new("Splash image"),
-            Node {
-                margin: UiRect::all(Val::Auto),
-                width: percent(70),
-                ..default()
-            },
-            ImageNode::new(asset_server.load_with_settings(
+/// The logos left to show, in order.
+#[derive(Resource, Debug)]
+struct SplashSequence {
+    logos: Vec<(Handle<Image>, SplashLogo)>,
+    next: usize,
+}
+
+/// The root that each logo is shown under.
+#[derive(Component)]
+struct SplashScreen;
+
+fn start_splash_sequence(
+    config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    // Every logo is loaded up front, so the later ones are ready by the time they're shown.
+    let logos = config
+        .splash
+        .iter()
+        .map(|logo| {
+            let image = asset_server.load_with_settings(
                 // This should be an embedded asset for instant loading, but that is
                 // currently [broken on Windows Wasm builds](https://github.com/bevyengine/bevy/issues/14246).
-                "images/splash.png",
+                logo.image.clone(),
                 |settings: &mut ImageLoaderSettings| {
-                    // Make an exception for the splash image in case
+                    // Make an exception for the splash images in case
                     // `ImagePlugin::default_nearest()` is used for pixel art.
                     settings.sampler = ImageSampler::linear();
                 },
-            )),
-            ImageNodeFadeInOut {
-                total_duration: SPLASH_DURATION_SECS,
-                fade_duration: SPLASH_FADE_DURATION_SECS,
-                t: 0.0,
-            },
-        )],
+            );
+            (image, logo.clone())
+        })
+        .collect();
+    commands.insert_resource(SplashSequence { logos, next: 0 });
+
+    commands.spawn((
+        widget::ui_root("Splash Screen"),
+        BackgroundColor(SPLASH_BACKGROUND_COLOR),
+        DespawnOnExit(Screen::Splash),
+        SplashScreen,
     ));
 }
 
+fn end_splash_sequence(mut commands: Commands) {
+    commands.remove_resource::<SplashSequence>();
+}
+
+fn splash_logo(image: Handle<Image>, logo: &SplashLogo) -> impl Bundle {
+    (
+        Name::new("Splash image"),
+        Node {
+            margin: UiRect::all(Val::Auto),
+            width: percent(70),
+            ..default()
+        },
+        ImageNode {
+            // Hidden until the first fade is applied.
+            color: Color::NONE,
+            ..ImageNode::new(image)
+        },
+        ImageNodeFadeInOut {
+            total_duration: logo.duration_secs,
+            fade_duration: logo.fade_secs,
+            t: 0.0,
+        },
+    )
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 struct ImageNodeFadeInOut {
@@ -98,6 +142,15 @@ impl ImageNodeFadeInOut {
         // Regular trapezoid-shaped graph, flat at the top with alpha = 1.0.
         ((1.0 - (2.0 * t - 1.0).abs()) / fade).min(1.0)
     }
+
+    /// When the fade out starts. Fading in and out each take half of the fade duration.
+    fn fade_out_start(&self) -> f32 {
+        self.total_duration - self.fade_duration / 2.0
+    }
+
+    fn is_finished(&self) -> bool {
+        self.t >= self.total_duration
+    }
 }
 
 fn tick_fade_in_out(time: Res<Time>, mut animation_query: Query<&mut ImageNodeFadeInOut>) {
@@ -108,36 +161,39 @@ fn tick_fade_in_out(time: Res<Time>, mut animation_query: Query<&mut ImageNodeFa
 
 fn apply_fade_in_out(mut animation_query: Query<(&ImageNodeFadeInOut, &mut ImageNode)>) {
     for (anim, mut image) in &mut animation_query {
-        image.color.set_alpha(anim.alpha())
+        image.color = Color::WHITE.with_alpha(anim.alpha());
     }
 }
 
-#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
-#[reflect(Resource)]
-struct SplashTimer(Timer);
-
-impl Default for SplashTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(SPLASH_DURATION_SECS, TimerMode::Once))
+/// Fades the current logo out early.
+fn skip_splash_logo(mut animation_query: Query<&mut ImageNodeFadeInOut>) {
+    for mut anim in &mut animation_query {
+        anim.t = anim.t.max(anim.fade_out_start());
     }
 }
 
-fn insert_splash_timer(mut commands: Commands) {
-    commands.init_resource::<SplashTimer>();
-}
-
-fn remove_splash_timer(mut commands: Commands) {
-    commands.remove_resource::<SplashTimer>();
-}
-
-fn tick_splash_timer(time: Res<Time>, mut timer: ResMut<SplashTimer>) {
-    timer.0.tick(time.delta());
-}
+/// Replaces the current logo with the next one once it's faded out, and enters the title screen
+/// after the last.
+fn advance_splash_sequence(
+    mut sequence: ResMut<SplashSequence>,
+    screen: Single<Entity, With<SplashScreen>>,
+    current: Query<(Entity, &ImageNodeFadeInOut)>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut commands: Commands,
+) {
+    if let Some((entity, anim)) = current.iter().next() {
+        if !anim.is_finished() {
+            return;
+        }
+        commands.entity(entity).despawn();
+    }
 
-fn check_splash_timer(timer: ResMut<SplashTimer>, mut next_screen: ResMut<NextState<Screen>>) {
-    if timer.0.just_finished() {
+    let Some((image, logo)) = sequence.logos.get(sequence.next) else {
         next_screen.set(Screen::Title);
-    }
+        return;
+    };
+    commands.spawn((splash_logo(image.clone(), logo), ChildOf(*screen)));
+    sequence.next += 1;
 }
 
 fn enter_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
@@ -0,0 +1,199 @@
+//! Settings chosen by the player, kept in a RON file in the platform's config directory.
+//!
+//! [`Settings`] is loaded before the first frame and written back whenever it changes. The rest of
+//! the game reads the resources the settings are applied to, like [`GlobalVolume`], the primary
+//! [`Window`] and the [`InputBindings`], rather than [`Settings`] itself. Web builds keep the
+//! defaults and don't save.
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{InputBindings, InputOptions},
+    save::SaveData,
+};
+
+#[cfg(not(target_family = "wasm"))]
+const SETTINGS_FILE: &str = "settings.ron";
+
+pub const MIN_VOLUME: f32 = 0.0;
+pub const MAX_VOLUME: f32 = 3.0;
+
+pub(super) fn plugin(app: &mut App) {
+    let settings = Settings::load(app.world().resource::<SaveData>());
+    app.insert_resource(settings);
+
+    app.add_systems(
+        PreUpdate,
+        (
+            apply_volume,
+            apply_window_settings,
+            apply_bindings,
+            apply_input_options,
+        )
+            .run_if(resource_changed::<Settings>),
+    );
+
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(
+        PostUpdate,
+        write_settings.run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+    );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// The [`GlobalVolume`], from [`MIN_VOLUME`] to [`MAX_VOLUME`].
+    pub volume: f32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// See [`EffectsIntensity`](crate::physics::EffectsIntensity). This is `None` until the
+    /// player has been through the calibration menu, which is shown before their first game.
+    pub effects_intensity: Option<f32>,
+    /// The keys and mouse buttons the player has rebound.
+    pub bindings: InputBindings,
+    pub input_options: InputOptions,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            fullscreen: false,
+            vsync: true,
+            effects_intensity: None,
+            bindings: default(),
+            input_options: default(),
+        }
+    }
+}
+
+impl Settings {
+    /// The settings file, in the platform's config directory. `None` if there's no home directory
+    /// to find it in.
+    #[cfg(not(target_family = "wasm"))]
+    fn path() -> Option<std::path::PathBuf> {
+        let dirs = directories::ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))?;
+        Some(dirs.config_dir().join(SETTINGS_FILE))
+    }
+
+    /// Loads the settings file. Without one, settings are carried over from the `save` instead,
+    /// where they used to be kept.
+    #[cfg(not(target_family = "wasm"))]
+    fn load(save: &SaveData) -> Self {
+        let Some(path) = Self::path() else {
+            warn!("No config directory to load settings from");
+            return default();
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                let settings = Self::migrate(save);
+                // Written straight away, since the old settings are gone from the next save.
+                if save.legacy_settings.is_some() {
+                    settings.save();
+                }
+                return settings;
+            }
+            Err(error) => {
+                warn!("Failed to read settings file: {error}");
+                return default();
+            }
+        };
+
+        ron::from_str(&text).unwrap_or_else(|error| {
+            warn!("Failed to parse settings file: {error}");
+            default()
+        })
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn load(_save: &SaveData) -> Self {
+        default()
+    }
+
+    /// The default settings, with any that are still in the `save` carried over.
+    #[cfg(not(target_family = "wasm"))]
+    fn migrate(save: &SaveData) -> Self {
+        let Some(legacy) = &save.legacy_settings else {
+            return default();
+        };
+        info!("Carrying settings over from the save file");
+        Self {
+            effects_intensity: legacy.effects_intensity,
+            ..default()
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(self, default())
+            .map_err(BevyError::from)
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                Ok(std::fs::write(&path, text)?)
+            });
+
+        if let Err(error) = result {
+            warn!("Failed to write settings file: {error}");
+        }
+    }
+}
+
+fn apply_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    let volume = Volume::Linear(settings.volume);
+    if global_volume.volume != volume {
+        global_volume.volume = volume;
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    let present_mode = if settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    // Only touched when different, since any change to the window is sent to the OS.
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+}
+
+fn apply_bindings(settings: Res<Settings>, mut bindings: ResMut<InputBindings>) {
+    if *bindings != settings.bindings {
+        *bindings = settings.bindings.clone();
+    }
+}
+
+fn apply_input_options(settings: Res<Settings>, mut options: ResMut<InputOptions>) {
+    if *options != settings.input_options {
+        *options = settings.input_options.clone();
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_settings(settings: Res<Settings>) {
+    settings.save();
+}
@@ -1,6 +1,14 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
-use crate::{asset_tracking::LoadResource, audio::sound_effect};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::{SoundLimit, SoundPool, sound_effect},
+};
+
+/// How soon another button's hover sound can play, so sweeping over a menu doesn't rattle.
+const HOVER_SOUND_COOLDOWN_SECS: f32 = 0.08;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_observer(apply_interaction_palette_on_click);
@@ -80,10 +88,22 @@ struct InteractionAssets {
 impl FromWorld for InteractionAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
-        Self {
+        let interaction_assets = Self {
             hover: assets.load("audio/sound_effects/button_hover.ogg"),
             click: assets.load("audio/sound_effects/button_click.ogg"),
+        };
+
+        // Only there with audio output.
+        if let Some(mut pool) = world.get_resource_mut::<SoundPool>() {
+            pool.set_limit(
+                &interaction_assets.hover,
+                SoundLimit {
+                    max_concurrent: 1,
+                    cooldown: Duration::from_secs_f32(HOVER_SOUND_COOLDOWN_SECS),
+                },
+            );
         }
+        interaction_assets
     }
 }
 
@@ -9,3 +9,29 @@ pub const BUTTON_TEXT: Color = srgb_hex("#ececec");
 pub const BUTTON_BACKGROUND: Color = srgb_hex("#4666bf");
 pub const BUTTON_HOVERED_BACKGROUND: Color = srgb_hex("#6299d1");
 pub const BUTTON_PRESSED_BACKGROUND: Color = srgb_hex("#3d4999");
+
+/// Tint for the afterimages left behind by a fast-moving player, at low and high speed.
+pub const AFTERIMAGE_SLOW: Color = srgb_hex("#6fd6ff");
+pub const AFTERIMAGE_FAST: Color = srgb_hex("#c86bff");
+
+/// The boss health bar, filled and empty.
+pub const BOSS_HEALTH_FILL: Color = srgb_hex("#c43d3d");
+pub const BOSS_HEALTH_BACKGROUND: Color = srgb_hex("#2b1a1a");
+
+/// The player's health hearts in the HUD, full and empty.
+pub const HEART_FULL: Color = srgb_hex("#e0474c");
+pub const HEART_EMPTY: Color = srgb_hex("#3a2326");
+
+/// The HUD speedometer, from rest to near the speed of light, and its empty background.
+pub const SPEEDOMETER_SLOW: Color = srgb_hex("#6fd6ff");
+pub const SPEEDOMETER_FAST: Color = srgb_hex("#c86bff");
+pub const SPEEDOMETER_BACKGROUND: Color = srgb_hex("#1c1a2b");
+
+/// The HUD gamma gauge's segments, lit from calm to warning, and unlit.
+pub const GAMMA_GAUGE_CALM: Color = srgb_hex("#e8c547");
+pub const GAMMA_GAUGE_WARNING: Color = srgb_hex("#ff3b3b");
+pub const GAMMA_GAUGE_OFF: Color = srgb_hex("#2a2420");
+
+/// The on-screen touch controls: the joystick and button backgrounds, and the joystick's knob.
+pub const TOUCH_CONTROL_BACKGROUND: Color = srgb_hex("#ececec40");
+pub const TOUCH_CONTROL_KNOB: Color = srgb_hex("#ececec99");
@@ -0,0 +1,44 @@
+//! Units for world-space quantities.
+//!
+//! The world is measured in meters, and levels are laid out on a grid of [`TILE_SIZE`] tiles.
+//! Tunables that are really about the level grid (how far a character can jump, how close a wall
+//! has to be) should be written in tiles with these wrappers, so that changing the tile size
+//! scales them along with the level geometry instead of silently breaking them.
+
+use bevy::prelude::*;
+
+/// The size of one level tile.
+pub const TILE_SIZE: WorldMeters = WorldMeters(1.0);
+
+/// A length in world units.
+#[derive(Reflect, Default, Deref, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct WorldMeters(pub f32);
+
+impl WorldMeters {
+    /// Converts a length measured in level tiles.
+    pub const fn from_tiles(tiles: f32) -> Self {
+        Self(tiles * TILE_SIZE.0)
+    }
+}
+
+/// A speed measured in level tiles.
+#[derive(Reflect, Default, Deref, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct TilesPerSecond(pub f32);
+
+impl TilesPerSecond {
+    /// Returns the speed in world units per second.
+    pub const fn to_world(self) -> f32 {
+        self.0 * TILE_SIZE.0
+    }
+}
+
+/// An acceleration measured in level tiles.
+#[derive(Reflect, Default, Deref, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct TilesPerSecondSquared(pub f32);
+
+impl TilesPerSecondSquared {
+    /// Returns the acceleration in world units per second squared.
+    pub const fn to_world(self) -> f32 {
+        self.0 * TILE_SIZE.0
+    }
+}
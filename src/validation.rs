@@ -0,0 +1,339 @@
+//! Checks for mistakes in levels that wouldn't stop them loading.
+//!
+//! Used by the level diagnostics dev tool while the game runs, and by the `validate_levels`
+//! binary before uploading a build.
+//!
+//! Besides checking the level data itself, the player's movement is roughly simulated on the
+//! level's tile grid to find items and exits that can't be reached from the player spawn. There's
+//! no navigation graph to ask, so this assumes the player is one tile in size and can walk, fall,
+//! swim, jump, and wall jump as high as it can jump from the ground. Dashes and moving platforms
+//! are ignored, so a warning can be a false alarm if the intended route uses them.
+
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use thiserror::Error;
+
+use crate::{
+    assets::{
+        enemy::EnemyManifest,
+        level::{Level, LevelCollider},
+        world::LevelNeighbour,
+    },
+    controller::CharacterController,
+    physics::LevelPhysics,
+    units::TILE_SIZE,
+};
+
+/// The physics timestep that jumps are simulated at, matching the default [`Time<Fixed>`].
+const JUMP_SIMULATION_HZ: f64 = 64.0;
+
+/// Something wrong with a level that was still loaded.
+#[derive(Error, Debug)]
+pub enum LevelWarning {
+    #[error("unknown enemy label {label:?} at {position}")]
+    UnknownEnemy { label: String, position: Vec2 },
+    #[error("the player spawn at {0} is inside solid terrain")]
+    SpawnInsideTerrain(Vec2),
+    #[error("there is no ground below the player spawn at {0}")]
+    SpawnOverPit(Vec2),
+    #[error("{kind} collider {rect:?} extends outside the level")]
+    ColliderOutOfBounds { kind: &'static str, rect: URect },
+    #[error("entity {identifier} ({iid}) extends outside the level")]
+    EntityOutOfBounds { identifier: String, iid: String },
+    #[error("{kind} spawn at {position} is inside solid terrain")]
+    EntityInsideTerrain { kind: &'static str, position: Vec2 },
+    #[error("the level has no exits, doors, neighbours or bosses to leave by")]
+    NoExit,
+    #[error("item {item:?} at {position} can't be reached from the player spawn")]
+    UnreachableItem { item: String, position: Vec2 },
+    #[error("exit {id} can't be reached from the player spawn")]
+    UnreachableExit { id: String },
+}
+
+/// What a level is checked against, besides its own contents.
+#[derive(Default, Clone, Copy)]
+pub struct ValidationContext<'a> {
+    /// Enemy labels are only checked if the manifest is given.
+    pub manifest: Option<&'a EnemyManifest>,
+    /// The player's controller, in world units. Reachability is only checked if it's given.
+    pub player_controller: Option<&'a CharacterController>,
+    /// Levels next to this one in its world, which the player can walk into.
+    pub neighbours: &'a [LevelNeighbour],
+}
+
+/// Checks a level for mistakes that wouldn't stop it loading.
+pub fn validate_level(level: &Level, context: &ValidationContext) -> Vec<LevelWarning> {
+    let mut warnings = Vec::new();
+
+    if let Some(manifest) = context.manifest {
+        warnings.extend(
+            level
+                .enemy_spawns
+                .iter()
+                .filter(|spawn| !manifest.enemies.contains_key(&spawn.label))
+                .map(|spawn| LevelWarning::UnknownEnemy {
+                    label: spawn.label.clone(),
+                    position: spawn.position,
+                }),
+        );
+    }
+
+    // Solid ground, in tiles
+    let solid: Vec<_> = level
+        .terrain_colliders
+        .iter()
+        .chain(level.surface_colliders.iter().map(|sc| &sc.collider))
+        .map(|collider| collider.as_rect())
+        .collect();
+    let standable: Vec<_> = solid
+        .iter()
+        .copied()
+        .chain(level.platform_colliders.iter().map(|pc| pc.as_rect()))
+        .collect();
+
+    let spawn = level.player_spawn / TILE_SIZE.0;
+    if solid.iter().any(|rect| rect.contains(spawn)) {
+        warnings.push(LevelWarning::SpawnInsideTerrain(level.player_spawn));
+    } else if !standable
+        .iter()
+        .any(|rect| rect.min.x <= spawn.x && spawn.x <= rect.max.x && rect.max.y <= spawn.y)
+    {
+        warnings.push(LevelWarning::SpawnOverPit(level.player_spawn));
+    }
+
+    let spawns = [
+        level
+            .enemy_spawns
+            .iter()
+            .map(|spawn| ("enemy", spawn.position))
+            .collect::<Vec<_>>(),
+        level
+            .npc_spawns
+            .iter()
+            .map(|spawn| ("npc", spawn.position))
+            .collect(),
+        level
+            .items
+            .iter()
+            .map(|spawn| ("item", spawn.position))
+            .collect(),
+        level
+            .props
+            .iter()
+            .map(|spawn| ("prop", spawn.position))
+            .collect(),
+    ];
+    warnings.extend(
+        spawns
+            .into_iter()
+            .flatten()
+            .filter(|(_, position)| {
+                solid
+                    .iter()
+                    .any(|rect| rect.contains(*position / TILE_SIZE.0))
+            })
+            .map(|(kind, position)| LevelWarning::EntityInsideTerrain { kind, position }),
+    );
+
+    // Beating a boss leaves a level without exits
+    let has_boss = level
+        .entities
+        .iter()
+        .any(|entity| entity.identifier == "Boss");
+    if level.exits.is_empty()
+        && level.doors.is_empty()
+        && context.neighbours.is_empty()
+        && !has_boss
+    {
+        warnings.push(LevelWarning::NoExit);
+    }
+
+    if let Some(controller) = context.player_controller {
+        let reachable = reachable_tiles(level, controller);
+        let is_reachable = |position: Vec2| {
+            // The player is about a tile tall, so can touch things in the tile above it.
+            let tile = (position / TILE_SIZE.0).floor().as_ivec2();
+            reachable.contains(&tile) || reachable.contains(&(tile - IVec2::Y))
+        };
+        warnings.extend(
+            level
+                .items
+                .iter()
+                .filter(|spawn| !is_reachable(spawn.position))
+                .map(|spawn| LevelWarning::UnreachableItem {
+                    item: spawn.item.clone(),
+                    position: spawn.position,
+                }),
+        );
+        warnings.extend(
+            level
+                .exits
+                .iter()
+                .filter(|exit| {
+                    let rect = exit.rect;
+                    let min = (rect.min / TILE_SIZE.0).floor().as_ivec2();
+                    let max = (rect.max / TILE_SIZE.0).ceil().as_ivec2();
+                    !(min.x..max.x)
+                        .flat_map(|x| (min.y..max.y).map(move |y| IVec2::new(x, y)))
+                        .any(|tile| reachable.contains(&tile))
+                })
+                .map(|exit| LevelWarning::UnreachableExit {
+                    id: exit.id.clone(),
+                }),
+        );
+    }
+
+    let bounds = URect::from_corners(UVec2::ZERO, level.grid_size);
+    let tile_colliders = [
+        ("terrain", &level.terrain_colliders),
+        ("platform", &level.platform_colliders),
+    ]
+    .into_iter()
+    .flat_map(|(kind, colliders)| colliders.iter().map(move |c| (kind, c.0)))
+    .chain(
+        level
+            .hazard_colliders
+            .iter()
+            .map(|hc| ("hazard", hc.collider.0)),
+    )
+    .chain(
+        level
+            .surface_colliders
+            .iter()
+            .map(|sc| ("surface", sc.collider.0)),
+    );
+    warnings.extend(
+        tile_colliders
+            .filter(|(_, rect)| bounds.union(*rect) != bounds)
+            .map(|(kind, rect)| LevelWarning::ColliderOutOfBounds { kind, rect }),
+    );
+
+    let world_bounds = Rect::from_corners(Vec2::ZERO, level.grid_size.as_vec2() * TILE_SIZE.0);
+    warnings.extend(
+        level
+            .entities
+            .iter()
+            .filter(|entity| world_bounds.union(entity.rect) != world_bounds)
+            .map(|entity| LevelWarning::EntityOutOfBounds {
+                identifier: entity.identifier.clone(),
+                iid: entity.iid.clone(),
+            }),
+    );
+
+    warnings
+}
+
+/// How high and how far the player can jump from flat ground, in world units.
+fn jump_reach(controller: &CharacterController, physics: &LevelPhysics) -> Vec2 {
+    let dt = (1.0 / JUMP_SIMULATION_HZ) as f32;
+    let gravity = -physics.gravity.y;
+    let max_ticks = controller.jump_max_ticks.max(controller.jump_min_ticks);
+
+    let mut velocity = Vec2::new(controller.max_speed, 0.0);
+    let mut position = Vec2::ZERO;
+    let mut height = 0.0_f32;
+    for tick in 0.. {
+        if tick < max_ticks {
+            velocity.y += dt * controller.jump_impulse;
+        }
+        velocity.y -= dt * gravity;
+        velocity.x = (velocity.x + dt * controller.accel_air).min(controller.max_speed);
+        velocity.x *= 1.0 / (1.0 + controller.damping_air * physics.damping_scale * dt);
+        position += dt * velocity;
+        height = height.max(position.y);
+        // Give up on jumps that never come down, such as in zero gravity.
+        if position.y < 0.0 || tick > 10 * JUMP_SIMULATION_HZ as u32 {
+            break;
+        }
+    }
+    Vec2::new(position.x, height)
+}
+
+/// The tiles the player can get to from the player spawn.
+///
+/// Searches the level's tile grid, where in the air the player can move up as many tiles as it can
+/// jump, sideways as many as it can cover in a jump, and down as far as it likes. Landing on
+/// solid ground or a one-way platform, touching a wall, or swimming restores the full jump.
+fn reachable_tiles(level: &Level, controller: &CharacterController) -> HashSet<IVec2> {
+    let size = level.grid_size.as_ivec2();
+    let grid = |colliders: &mut dyn Iterator<Item = &LevelCollider>| {
+        let mut tiles = vec![false; (size.x * size.y) as usize];
+        for rect in colliders.map(|collider| collider.0) {
+            let rect = rect.intersect(URect::from_corners(UVec2::ZERO, level.grid_size));
+            for y in rect.min.y..rect.max.y {
+                for x in rect.min.x..rect.max.x {
+                    tiles[(y * size.x as u32 + x) as usize] = true;
+                }
+            }
+        }
+        tiles
+    };
+    let solid = grid(
+        &mut level
+            .terrain_colliders
+            .iter()
+            .chain(level.surface_colliders.iter().map(|sc| &sc.collider)),
+    );
+    let platforms = grid(&mut level.platform_colliders.iter());
+    let hazards = grid(&mut level.hazard_colliders.iter().map(|hc| &hc.collider));
+
+    let index = |tile: IVec2| {
+        (tile.cmpge(IVec2::ZERO).all() && tile.cmplt(size).all())
+            .then(|| (tile.y * size.x + tile.x) as usize)
+    };
+    let is_open = |tile: IVec2| index(tile).is_some_and(|i| !solid[i] && !hazards[i]);
+    let in_water = |tile: IVec2| {
+        let center = (tile.as_vec2() + 0.5) * TILE_SIZE.0;
+        level
+            .water_volumes
+            .iter()
+            .any(|water| water.rect.contains(center))
+    };
+    let is_solid = |tile: IVec2| index(tile).is_some_and(|i| solid[i]);
+    let is_supported = |tile: IVec2| {
+        in_water(tile)
+            || index(tile - IVec2::Y).is_some_and(|i| solid[i] || platforms[i])
+            || is_solid(tile + IVec2::X)
+            || is_solid(tile - IVec2::X)
+    };
+
+    let reach = jump_reach(controller, &level.physics) / TILE_SIZE.0;
+    let jump = (reach.y.floor() as u32).min(u8::MAX as u32) as u8;
+    let sideways = (reach.x.floor() as u32).min(u8::MAX as u32) as u8;
+
+    let start = (level.player_spawn / TILE_SIZE.0).floor().as_ivec2();
+    let mut reachable = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    if is_open(start) {
+        queue.push_back((start, 0, sideways));
+    }
+    while let Some((tile, up, side)) = queue.pop_front() {
+        let (up, side) = if is_supported(tile) {
+            (jump, sideways)
+        } else {
+            (up, side)
+        };
+        if !visited.insert((tile, up, side)) {
+            continue;
+        }
+        reachable.insert(tile);
+
+        if up > 0 && is_open(tile + IVec2::Y) {
+            queue.push_back((tile + IVec2::Y, up - 1, side));
+        }
+        // Falling ends the jump. One-way platforms can be dropped through.
+        if is_open(tile - IVec2::Y) {
+            queue.push_back((tile - IVec2::Y, 0, side));
+        }
+        if side > 0 {
+            for step in [IVec2::X, IVec2::NEG_X] {
+                if is_open(tile + step) {
+                    queue.push_back((tile + step, up, side - 1));
+                }
+            }
+        }
+    }
+    reachable
+}